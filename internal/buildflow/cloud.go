@@ -156,6 +156,7 @@ func BuildCloud(opts CloudOptions) (*CloudResult, error) {
 		Tag:        tag,
 		Token:      cfg.Token,
 		WorkDir:    pushWorkDir,
+		Snapshot:   cfg.Snapshot,
 		Insecure:   opts.InsecureRegistry,
 	})
 	if err != nil {
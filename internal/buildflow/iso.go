@@ -6,6 +6,7 @@ import (
 
 	"github.com/HackerOS-Linux-System/hackeros-builder/internal/hkgen"
 	"github.com/HackerOS-Linux-System/hackeros-builder/internal/isobuild"
+	"github.com/HackerOS-Linux-System/hackeros-builder/internal/liveparse"
 	"github.com/HackerOS-Linux-System/hackeros-builder/internal/ociimage"
 	"github.com/HackerOS-Linux-System/hackeros-builder/internal/preflight"
 	"github.com/HackerOS-Linux-System/hackeros-builder/internal/util"
@@ -53,6 +54,13 @@ func BuildIso(opts IsoOptions) error {
 		return err
 	}
 
+	// Struktura projektu jest tu potrzebna tylko dla config/hooks/install
+	// (reszta trafila juz do obrazu OCI w "build cloud").
+	project, err := liveparse.Parse(opts.ProjectDir)
+	if err != nil {
+		return fmt.Errorf("parsowanie struktury projektu: %w", err)
+	}
+
 	repository := opts.Repository
 	tag := opts.Tag
 	if repository == "" {
@@ -103,6 +111,9 @@ func BuildIso(opts IsoOptions) error {
 		WorkDir:       isoWorkDir,
 		VolumeName:    volumeName,
 		SkipInstaller: skipInstaller,
+		Installer:     cfg.Installer,
+		InstallHooks:  project.InstallHooks,
+		Timeouts:      cfg.Timeouts,
 	}); err != nil {
 		return fmt.Errorf("budowa ISO: %w", err)
 	}
@@ -3,6 +3,7 @@ package config
 import (
 	"fmt"
 	"strings"
+	"time"
 
 	"github.com/HackerOS-Linux-System/hackeros-builder/internal/hk"
 )
@@ -79,9 +80,34 @@ type Config struct {
 	Token       string
 	Release     string
 
+	// Components to komponenty archiwum Debiana z [release] -> components
+	// (main/contrib/non-free/non-free-firmware). Przekazywane do debootstrap
+	// (--components) i wpisywane do /etc/apt/sources.list w rootfs. Brak
+	// klucza w config.hk -> DefaultComponents(Release).
+	Components []string
+
+	// Snapshot to znacznik czasu archiwum snapshot.debian.org
+	// (YYYYMMDDTHHMMSSZ, np. "20250701T000000Z") z [release] -> snapshot.
+	// Gdy ustawiony, debootstrap i sources.list obrazu uzywaja stanu
+	// archiwum z tej chwili -- te same wersje pakietow przy kazdym buildzie.
+	// Puste -- biezace archiwum (deb.debian.org).
+	Snapshot string
+
 	// Project to zawartosc sekcji [project] -- wszystkie pola opcjonalne,
 	// brak calej sekcji nie jest bledem (stosowane sa wartosci domyslne).
 	Project ProjectConfig
+
+	// System to zawartosc sekcji [system] -- ustawienia wpisywane do obrazu.
+	System SystemConfig
+
+	// Installer to zawartosc sekcji [installer] -- ustawienia Calamares w ISO.
+	Installer InstallerConfig
+
+	// Grub to zawartosc sekcji [grub] -- ustawienia GRUB systemu docelowego.
+	Grub GrubConfig
+
+	// Timeouts to zawartosc sekcji [timeouts] -- limity czasu faz buildu.
+	Timeouts TimeoutsConfig
 }
 
 // ProjectConfig to zawartosc sekcji [project] w config/config.hk.
@@ -109,6 +135,16 @@ type ProjectConfig struct {
 	// Wartosc domyslna (brak lub selinux=false): AppArmor.
 	// selinux=true: SELinux.
 	MAC MACSystem
+
+	// AptParallel wlacza na czas buildu konfiguracje apt przyspieszajaca
+	// pobieranie (pipelining HTTP, bez plikow Translation) -- patrz
+	// rootfs/aptconf.go. Nie trafia do obrazu. Domyslnie false.
+	AptParallel bool
+
+	// VerifyPackages wlacza na koniec buildu weryfikacje zainstalowanych
+	// pakietow: "apt-get check" i sumy kontrolne plikow (debsums) -- patrz
+	// rootfs/verify.go. Wydluza build. Domyslnie false.
+	VerifyPackages bool
 }
 
 // IsAtomicBuild zwraca true jesli projekt ma byc budowany jako pelny
@@ -153,6 +189,43 @@ var knownReleases = map[string]bool{
 	"unstable": true,
 }
 
+// releasesWithoutSecuritySuite to galezie rozwojowe bez suite
+// "<wydanie>-security" -- poprawki trafiaja tam zwyklym uploadem.
+var releasesWithoutSecuritySuite = map[string]bool{
+	"sid":          true,
+	"unstable":     true,
+	"experimental": true,
+}
+
+// knownComponents to komponenty archiwum Debiana akceptowane w
+// [release] -> components.
+var knownComponents = map[string]bool{
+	"main":              true,
+	"contrib":           true,
+	"non-free":          true,
+	"non-free-firmware": true,
+}
+
+// releasesWithoutFirmwareComponent to wydania sprzed Debiana 12 --
+// komponent non-free-firmware zostal wydzielony z non-free dopiero w
+// bookworm, wczesniej firmware lezal w non-free.
+var releasesWithoutFirmwareComponent = map[string]bool{
+	"stretch":  true,
+	"buster":   true,
+	"bullseye": true,
+}
+
+// DefaultComponents zwraca domyslne komponenty dla danego wydania -- zestaw
+// "desktopowy" (kodeki, firmware WiFi/GPU, sterowniki z contrib/non-free),
+// bez ktorego obraz z samym "main" nie wstanie na wiekszosci laptopow.
+// Dla wydan sprzed bookworm pomija non-free-firmware, ktory wtedy nie istnial.
+func DefaultComponents(release string) []string {
+	if releasesWithoutFirmwareComponent[release] {
+		return []string{"main", "contrib", "non-free"}
+	}
+	return []string{"main", "contrib", "non-free", "non-free-firmware"}
+}
+
 // Load wczytuje i parsuje config.hk z podanej sciezki.
 func Load(path string) (*Config, error) {
 	parsed, err := hk.LoadFile(path)
@@ -191,15 +264,173 @@ func Load(path string) (*Config, error) {
 		return nil, err
 	}
 
+	components, err := loadComponents(parsed, cfg.Release)
+	if err != nil {
+		return nil, err
+	}
+	cfg.Components = components
+
+	snapshot, err := loadSnapshot(parsed)
+	if err != nil {
+		return nil, err
+	}
+	cfg.Snapshot = snapshot
+
 	proj, err := loadProjectSection(parsed)
 	if err != nil {
 		return nil, err
 	}
 	cfg.Project = proj
 
+	sys, err := loadSystemSection(parsed, cfg.HasComponent(cfg.FirmwareComponent()), cfg.HasComponent("non-free"))
+	if err != nil {
+		return nil, err
+	}
+	cfg.System = sys
+
+	// security_updates zalezy od [release]: bez suite -security nie ma
+	// skad brac poprawek, a snapshot archiwum nigdy ich nie dostanie.
+	if sys.SecurityUpdates {
+		if releasesWithoutSecuritySuite[cfg.Release] {
+			return nil, fmt.Errorf("config.hk: [system] -> security_updates: %s nie ma archiwum "+
+				"bezpieczenstwa (%s-security) -- poprawki przychodza zwyklym \"apt upgrade\"", cfg.Release, cfg.Release)
+		}
+		if cfg.Snapshot != "" {
+			return nil, fmt.Errorf("config.hk: [system] -> security_updates nie dziala z [release] -> snapshot " +
+				"-- archiwum z ustalonej chwili nie dostaje nowych poprawek")
+		}
+	}
+
+	inst, err := loadInstallerSection(parsed)
+	if err != nil {
+		return nil, err
+	}
+	cfg.Installer = inst
+
+	grub, err := loadGrubSection(parsed)
+	if err != nil {
+		return nil, err
+	}
+	cfg.Grub = grub
+
+	timeouts, err := loadTimeoutsSection(parsed)
+	if err != nil {
+		return nil, err
+	}
+	cfg.Timeouts = timeouts
+
 	return cfg, nil
 }
 
+// loadComponents wczytuje opcjonalny klucz [release] -> components.
+// Brak klucza -> DefaultComponents(release), brak bledu.
+func loadComponents(parsed *hk.HkConfig, release string) ([]string, error) {
+	sec, err := parsed.Section("release")
+	if err != nil {
+		return DefaultComponents(release), nil
+	}
+	val, ok := sec.Get("components")
+	if !ok {
+		return DefaultComponents(release), nil
+	}
+
+	list, err := valueAsStringList(val)
+	if err != nil {
+		return nil, fmt.Errorf("config.hk: [release] -> components: %w", err)
+	}
+	components, err := normalizeComponents(list, release)
+	if err != nil {
+		return nil, fmt.Errorf("config.hk: [release] -> components: %w", err)
+	}
+	return components, nil
+}
+
+// snapshotLayout to format znacznika czasu w URL snapshot.debian.org.
+const snapshotLayout = "20060102T150405Z"
+
+// loadSnapshot wczytuje opcjonalny klucz [release] -> snapshot i sprawdza
+// format (snapshotLayout). Znacznik z przyszlosci to blad -- archiwum go
+// nie zna, a build nie bylby powtarzalny.
+func loadSnapshot(parsed *hk.HkConfig) (string, error) {
+	sec, err := parsed.Section("release")
+	if err != nil {
+		return "", nil
+	}
+	v, ok := optString(sec, "snapshot")
+	if !ok || v == "" {
+		return "", nil
+	}
+	t, err := time.Parse(snapshotLayout, v)
+	if err != nil {
+		return "", fmt.Errorf(
+			"config.hk: [release] -> snapshot: oczekiwano znacznika YYYYMMDDTHHMMSSZ (np. 20250701T000000Z), otrzymano %q", v)
+	}
+	if t.After(time.Now()) {
+		return "", fmt.Errorf("config.hk: [release] -> snapshot: %s jest w przyszlosci", v)
+	}
+	return v, nil
+}
+
+// normalizeComponents waliduje liste komponentow (znane nazwy, obecnosc
+// "main", non-free-firmware tylko od bookworm) i usuwa duplikaty,
+// zachowujac kolejnosc podana przez uzytkownika.
+func normalizeComponents(list []string, release string) ([]string, error) {
+	seen := make(map[string]bool)
+	var out []string
+	for _, c := range list {
+		c = strings.ToLower(c)
+		if !knownComponents[c] {
+			return nil, fmt.Errorf(
+				"nieznany komponent %q -- dozwolone: main, contrib, non-free, non-free-firmware", c)
+		}
+		if c == "non-free-firmware" && releasesWithoutFirmwareComponent[release] {
+			return nil, fmt.Errorf(
+				"komponent non-free-firmware istnieje dopiero od Debiana 12 (bookworm) -- "+
+					"dla %s firmware jest w non-free", release)
+		}
+		if !seen[c] {
+			seen[c] = true
+			out = append(out, c)
+		}
+	}
+	if !seen["main"] {
+		return nil, fmt.Errorf("lista komponentow musi zawierac \"main\" (otrzymano %v)", list)
+	}
+	return out, nil
+}
+
+// valueAsStringList zamienia wartosc .hk na liste stringow: tablica
+// ([a, b]) jest brana element po elemencie, skalar jest dzielony na
+// spacjach i przecinkach ("a b" albo "a,b"). Puste elementy sa pomijane.
+func valueAsStringList(val hk.HkValue) ([]string, error) {
+	var raw []string
+	if arr, err := val.AsArray(); err == nil {
+		for _, item := range arr {
+			s, err := item.AsString()
+			if err != nil {
+				return nil, err
+			}
+			raw = append(raw, s)
+		}
+	} else {
+		s, err := val.AsString()
+		if err != nil {
+			return nil, err
+		}
+		raw = strings.FieldsFunc(s, func(r rune) bool {
+			return r == ',' || r == ' ' || r == '\t'
+		})
+	}
+
+	var out []string
+	for _, s := range raw {
+		if s = strings.TrimSpace(s); s != "" {
+			out = append(out, s)
+		}
+	}
+	return out, nil
+}
+
 // loadProjectSection wczytuje opcjonalna sekcje [project].
 // Brak sekcji lub poszczegolnych kluczy -> wartosci domyslne, brak bledu.
 func loadProjectSection(parsed *hk.HkConfig) (ProjectConfig, error) {
@@ -254,6 +485,18 @@ func loadProjectSection(parsed *hk.HkConfig) (ProjectConfig, error) {
 		}
 	}
 
+	if val, ok := sec.Get("apt_parallel"); ok {
+		if s, err := val.AsString(); err == nil {
+			p.AptParallel = isTruthy(strings.TrimSpace(s))
+		}
+	}
+
+	if val, ok := sec.Get("verify_packages"); ok {
+		if s, err := val.AsString(); err == nil {
+			p.VerifyPackages = isTruthy(strings.TrimSpace(s))
+		}
+	}
+
 	return p, nil
 }
 
@@ -352,6 +595,31 @@ func (c *Config) IsKnownRelease() bool {
 	return knownReleases[c.Release]
 }
 
+// ComponentsArg zwraca komponenty w formacie flagi debootstrap --components
+// (rozdzielone przecinkami, np. "main,contrib,non-free,non-free-firmware").
+func (c *Config) ComponentsArg() string {
+	return strings.Join(c.Components, ",")
+}
+
+// HasComponent zwraca true jesli dany komponent archiwum jest wlaczony.
+func (c *Config) HasComponent(name string) bool {
+	for _, comp := range c.Components {
+		if comp == name {
+			return true
+		}
+	}
+	return false
+}
+
+// FirmwareComponent zwraca komponent archiwum z firmware i mikrokodem CPU:
+// non-free-firmware, a dla wydan sprzed bookworm non-free.
+func (c *Config) FirmwareComponent() string {
+	if releasesWithoutFirmwareComponent[c.Release] {
+		return "non-free"
+	}
+	return "non-free-firmware"
+}
+
 // ImageRepository buduje pelna sciezke repozytorium OCI.
 func (c *Config) ImageRepository(registryHost, imageName string) string {
 	return fmt.Sprintf("%s/%s/%s", registryHost, toLower(c.AccountName), imageName)
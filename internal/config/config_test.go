@@ -3,7 +3,10 @@ package config
 import (
 	"os"
 	"path/filepath"
+	"reflect"
+	"strings"
 	"testing"
+	"time"
 )
 
 func writeTestConfig(t *testing.T, content string) string {
@@ -16,6 +19,18 @@ func writeTestConfig(t *testing.T, content string) string {
 	return path
 }
 
+// testBaseConfig to minimalny poprawny config.hk. Konczy sie sekcja
+// [release], wiec testy moga dopisac do niej klucze albo otworzyc kolejna
+// sekcje.
+const testBaseConfig = "[account]\n-> type => user\n-> name => michal\n\n" +
+	"[auth]\n-> token => x\n\n[release]\n-> name => trixie\n"
+
+// loadWith wczytuje testBaseConfig z dopisanym fragmentem extra.
+func loadWith(t *testing.T, extra string) (*Config, error) {
+	t.Helper()
+	return Load(writeTestConfig(t, testBaseConfig+extra))
+}
+
 func TestLoad_ValidConfig(t *testing.T) {
 	path := writeTestConfig(t, `[account]
 -> type => user
@@ -92,3 +107,596 @@ func TestImageRepository_LowercasesAccountName(t *testing.T) {
 		t.Errorf("oczekiwano %q, otrzymano %q", want, repo)
 	}
 }
+
+func TestDefaultComponents_PerBranch(t *testing.T) {
+	cases := map[string]string{
+		"bookworm": "main,contrib,non-free,non-free-firmware",
+		"trixie":   "main,contrib,non-free,non-free-firmware",
+		"sid":      "main,contrib,non-free,non-free-firmware",
+		"bullseye": "main,contrib,non-free",
+	}
+	for release, want := range cases {
+		cfg := &Config{Release: release, Components: DefaultComponents(release)}
+		if got := cfg.ComponentsArg(); got != want {
+			t.Errorf("%s: oczekiwano %q, otrzymano %q", release, want, got)
+		}
+	}
+}
+
+func TestLoad_ComponentsDefaultWhenMissing(t *testing.T) {
+	path := writeTestConfig(t, `[account]
+-> type => user
+-> name => michal
+
+[auth]
+-> token => x
+
+[release]
+-> name => trixie
+`)
+	cfg, err := Load(path)
+	if err != nil {
+		t.Fatalf("Load zwrocilo blad: %v", err)
+	}
+	if got := cfg.ComponentsArg(); got != "main,contrib,non-free,non-free-firmware" {
+		t.Errorf("oczekiwano domyslnych komponentow dla trixie, otrzymano %q", got)
+	}
+}
+
+func TestLoad_ComponentsArrayAndString(t *testing.T) {
+	for _, value := range []string{"[main, non-free-firmware, main]", "main non-free-firmware"} {
+		path := writeTestConfig(t, `[account]
+-> type => user
+-> name => michal
+
+[auth]
+-> token => x
+
+[release]
+-> name => bookworm
+-> components => `+value+`
+`)
+		cfg, err := Load(path)
+		if err != nil {
+			t.Fatalf("%s: Load zwrocilo blad: %v", value, err)
+		}
+		if got := cfg.ComponentsArg(); got != "main,non-free-firmware" {
+			t.Errorf("%s: oczekiwano \"main,non-free-firmware\", otrzymano %q", value, got)
+		}
+	}
+}
+
+func TestLoad_ComponentsValidation(t *testing.T) {
+	cases := map[string]string{
+		"brak main":               "-> name => trixie\n-> components => [contrib]\n",
+		"nieznany komponent":      "-> name => trixie\n-> components => [main, restricted]\n",
+		"firmware przed debian12": "-> name => bullseye\n-> components => [main, non-free-firmware]\n",
+	}
+	for name, release := range cases {
+		path := writeTestConfig(t, "[account]\n-> type => user\n-> name => michal\n\n"+
+			"[auth]\n-> token => x\n\n[release]\n"+release)
+		if _, err := Load(path); err == nil {
+			t.Errorf("%s: oczekiwano bledu walidacji komponentow", name)
+		}
+	}
+}
+
+func TestLoad_PasswordPolicy(t *testing.T) {
+	cfg, err := loadWith(t, "\n[system]\n-> password_max_days => 90\n-> password_warn_days => 14\n"+
+		"-> editor => vim\n-> color_prompt => true\n\n[installer]\n-> force_password_change => true\n")
+	if err != nil {
+		t.Fatalf("Load zwrocilo blad: %v", err)
+	}
+	if cfg.System.PasswordMaxDays != 90 || cfg.System.PasswordWarnDays != 14 {
+		t.Errorf("oczekiwano 90/14, otrzymano %d/%d", cfg.System.PasswordMaxDays, cfg.System.PasswordWarnDays)
+	}
+	if cfg.System.Editor != "vim" || !cfg.System.ColorPrompt || cfg.System.BashCompletion {
+		t.Errorf("oczekiwano editor=vim, color_prompt, bez bash_completion; otrzymano %+v", cfg.System)
+	}
+	if !cfg.Installer.ForcePasswordChange {
+		t.Error("oczekiwano ForcePasswordChange = true")
+	}
+}
+
+func TestLoad_InstallerBootloaderID(t *testing.T) {
+	cfg, err := loadWith(t, "")
+	if err != nil {
+		t.Fatalf("Load zwrocilo blad: %v", err)
+	}
+	if got := cfg.Installer.EFIBootloaderID(); got != DefaultBootloaderID {
+		t.Errorf("oczekiwano domyslnego %q, otrzymano %q", DefaultBootloaderID, got)
+	}
+
+	cfg, err = loadWith(t, "\n[installer]\n-> bootloader_id => HackerOS-Dev\n")
+	if err != nil {
+		t.Fatalf("Load zwrocilo blad: %v", err)
+	}
+	if got := cfg.Installer.EFIBootloaderID(); got != "HackerOS-Dev" {
+		t.Errorf("oczekiwano \"HackerOS-Dev\", otrzymano %q", got)
+	}
+}
+
+func TestLoad_InstallerUserUID(t *testing.T) {
+	cfg, err := loadWith(t, "\n[installer]\n-> user_uid => 1500\n")
+	if err != nil {
+		t.Fatalf("Load zwrocilo blad: %v", err)
+	}
+	if cfg.Installer.UserUID != 1500 || cfg.Installer.UserGID != 1500 {
+		t.Errorf("oczekiwano 1500/1500, otrzymano %d/%d", cfg.Installer.UserUID, cfg.Installer.UserGID)
+	}
+}
+
+func TestLoad_GrubSection(t *testing.T) {
+	cfg, err := loadWith(t, "\n[grub]\n-> timeout => 0\n-> default => 2\n-> recovery => true\n")
+	if err != nil {
+		t.Fatalf("Load zwrocilo blad: %v", err)
+	}
+	if cfg.Grub.Timeout == nil || *cfg.Grub.Timeout != 0 || cfg.Grub.Default != "2" ||
+		cfg.Grub.Recovery == nil || !*cfg.Grub.Recovery {
+		t.Errorf("niepoprawnie wczytana sekcja [grub]: %+v", cfg.Grub)
+	}
+}
+
+func TestLoad_Timeouts(t *testing.T) {
+	cfg, err := loadWith(t, "")
+	if err != nil {
+		t.Fatalf("Load zwrocilo blad: %v", err)
+	}
+	if cfg.Timeouts != DefaultTimeouts() {
+		t.Errorf("bez sekcji [timeouts] oczekiwano domyslnych limitow, otrzymano %+v", cfg.Timeouts)
+	}
+
+	cfg, err = loadWith(t, "\n[timeouts]\n-> debootstrap => 120\n-> hooks => 0\n")
+	if err != nil {
+		t.Fatalf("Load zwrocilo blad: %v", err)
+	}
+	if cfg.Timeouts.Debootstrap != 2*time.Hour || cfg.Timeouts.Hooks != 0 ||
+		cfg.Timeouts.AptUpdate != DefaultTimeouts().AptUpdate {
+		t.Errorf("niepoprawnie wczytana sekcja [timeouts]: %+v", cfg.Timeouts)
+	}
+}
+
+func TestLoad_InstallerPostInstall(t *testing.T) {
+	cfg, err := loadWith(t, "\n[installer]\n"+
+		"-> post_install => [\"systemctl enable ssh\", \"echo a, b > /etc/motd\"]\n-> post_install_fail_fast => false\n")
+	if err != nil {
+		t.Fatalf("Load zwrocilo blad: %v", err)
+	}
+	want := []string{"systemctl enable ssh", "echo a, b > /etc/motd"}
+	if !reflect.DeepEqual(cfg.Installer.PostInstall, want) {
+		t.Errorf("post_install: oczekiwano %q, otrzymano %q", want, cfg.Installer.PostInstall)
+	}
+	if !cfg.Installer.PostInstallContinueOnError {
+		t.Error("post_install_fail_fast => false: oczekiwano PostInstallContinueOnError")
+	}
+
+	cfg, err = loadWith(t, "\n[installer]\n-> post_install => update-grub\n")
+	if err != nil {
+		t.Fatalf("Load zwrocilo blad: %v", err)
+	}
+	if len(cfg.Installer.PostInstall) != 1 || cfg.Installer.PostInstallContinueOnError {
+		t.Errorf("pojedyncza komenda: otrzymano %q (continue=%v)",
+			cfg.Installer.PostInstall, cfg.Installer.PostInstallContinueOnError)
+	}
+}
+
+func TestMinStorage_Arithmetic(t *testing.T) {
+	in := InstallerConfig{
+		ESPSizeMiB:     512,
+		SeparateVarLog: true,
+		VarLogSize:     PartitionSize{Value: 4, Unit: "GiB"},
+		SwapSize:       PartitionSize{Value: 2048, Unit: "MiB"},
+	}
+	// 512 + 4096 + 2048 + 8192 (root procentowy) = 14848 MiB -> 15 GB.
+	if got := in.MinStorageGB(); got != 15 {
+		t.Errorf("oczekiwano 15 GB, otrzymano %d", got)
+	}
+	want := "ESP 512 MiB + /var/log 4096 MiB + swap 2048 MiB + / (minimum) 8192 MiB = 14848 MiB (15 GB)"
+	if got := in.MinStorageSummary(); got != want {
+		t.Errorf("oczekiwano %q, otrzymano %q", want, got)
+	}
+
+	in = InstallerConfig{
+		SeparateHome: true,
+		RootSize:     PartitionSize{Value: 20, Unit: "GiB"},
+		HomeSize:     PartitionSize{Value: 100, Unit: "%"},
+	}
+	if got := in.MinStorageGB(); got != 20 {
+		t.Errorf("root o stalym rozmiarze: oczekiwano 20 GB bez minimum root, otrzymano %d", got)
+	}
+	if got := (InstallerConfig{ESPSizeMiB: 512}).MinStorageSummary(); got != "" {
+		t.Errorf("uklad procentowy: oczekiwano pustego rachunku, otrzymano %q", got)
+	}
+}
+
+func TestLoad_InstallerSerialConsole(t *testing.T) {
+	for val, want := range map[string]string{"": "auto", "auto": "auto", "true": "true", "false": "false"} {
+		extra := "\n[installer]\n"
+		if val != "" {
+			extra += "-> serial_console => " + val + "\n"
+		}
+		cfg, err := loadWith(t, extra)
+		if err != nil {
+			t.Fatalf("serial_console => %q: Load zwrocilo blad: %v", val, err)
+		}
+		got := "auto"
+		switch sc := cfg.Installer.SerialConsole; {
+		case sc == nil:
+		case *sc:
+			got = "true"
+		default:
+			got = "false"
+		}
+		if got != want {
+			t.Errorf("serial_console => %q: oczekiwano %s, otrzymano %s", val, want, got)
+		}
+	}
+}
+
+func TestLoad_InstallerServices(t *testing.T) {
+	cfg, err := loadWith(t, "\n[installer]\n-> disable_services => [bluetooth, cups]\n"+
+		"-> mask_services => avahi-daemon.socket\n")
+	if err != nil {
+		t.Fatalf("Load zwrocilo blad: %v", err)
+	}
+	if want := []string{"bluetooth", "cups"}; !reflect.DeepEqual(cfg.Installer.DisableServices, want) {
+		t.Errorf("disable_services: oczekiwano %v, otrzymano %v", want, cfg.Installer.DisableServices)
+	}
+	if want := []string{"avahi-daemon.socket"}; !reflect.DeepEqual(cfg.Installer.MaskServices, want) {
+		t.Errorf("mask_services: oczekiwano %v, otrzymano %v", want, cfg.Installer.MaskServices)
+	}
+}
+
+func TestLoad_InstallerSwapSize(t *testing.T) {
+	cfg, err := loadWith(t, "\n[installer]\n-> swap_size => 8G\n")
+	if err != nil {
+		t.Fatalf("Load zwrocilo blad: %v", err)
+	}
+	if got := cfg.Installer.SwapSize.String(); got != "8GiB" || !cfg.Installer.UsesPartitionLayout() {
+		t.Errorf("swap_size: oczekiwano 8GiB w ukladzie, otrzymano %q", got)
+	}
+	if got := cfg.Installer.StorageThresholdGB(); got != 16 {
+		t.Errorf("oczekiwano progu dysku 16 GB (swap 8 + root 8), otrzymano %d", got)
+	}
+}
+
+func TestLoad_InstallerPartitionLayout(t *testing.T) {
+	cfg, err := loadWith(t, "\n[installer]\n-> separate_home => true\n-> root_size => 30G\n")
+	if err != nil {
+		t.Fatalf("Load zwrocilo blad: %v", err)
+	}
+	if got := cfg.Installer.RootSize.String(); got != "30GiB" {
+		t.Errorf("root_size: oczekiwano \"30GiB\", otrzymano %q", got)
+	}
+	if got := cfg.Installer.HomeSize.String(); got != "100%" {
+		t.Errorf("home_size: oczekiwano domyslnego \"100%%\", otrzymano %q", got)
+	}
+
+	cfg, err = loadWith(t, "\n[installer]\n-> separate_home => true\n-> root_size => 40GiB\n-> home_size => 60GiB\n")
+	if err != nil {
+		t.Fatalf("Load zwrocilo blad: %v", err)
+	}
+	if got := cfg.Installer.StorageThresholdGB(); got != 100 {
+		t.Errorf("oczekiwano progu dysku 100 GB dla 40+60 GiB, otrzymano %d", got)
+	}
+
+	cfg, err = loadWith(t, "\n[installer]\n-> separate_var_log => true\n")
+	if err != nil {
+		t.Fatalf("Load zwrocilo blad: %v", err)
+	}
+	if got := cfg.Installer.VarLogSize.String(); got != "4GiB" || !cfg.Installer.UsesPartitionLayout() {
+		t.Errorf("separate_var_log: oczekiwano domyslnego 4GiB w ukladzie, otrzymano %q", got)
+	}
+}
+
+func TestLoad_InstallerFilesystems(t *testing.T) {
+	cfg, err := loadWith(t, "\n[installer]\n-> separate_home => true\n"+
+		"-> home_filesystem => xfs\n-> btrfs_mount_options => [noatime, compress=zstd:3]\n"+
+		"-> btrfs_layout => full\n")
+	if err != nil {
+		t.Fatalf("Load zwrocilo blad: %v", err)
+	}
+	if cfg.Installer.HomeFilesystem != "xfs" {
+		t.Errorf("home_filesystem: oczekiwano xfs, otrzymano %q", cfg.Installer.HomeFilesystem)
+	}
+	if got := cfg.Installer.MountOptions["btrfs"]; len(got) != 2 || got[1] != "compress=zstd:3" {
+		t.Errorf("btrfs_mount_options: otrzymano %v", got)
+	}
+	if cfg.Installer.BtrfsLayout != BtrfsLayoutFull {
+		t.Errorf("btrfs_layout: oczekiwano full, otrzymano %q", cfg.Installer.BtrfsLayout)
+	}
+
+	cfg, err = loadWith(t, "\n[installer]\n-> bootloader => systemd-boot\n")
+	if err != nil {
+		t.Fatalf("Load zwrocilo blad: %v", err)
+	}
+	if !cfg.Installer.UsesSystemdBoot() || cfg.Installer.ESPSizeMiB != 1024 {
+		t.Errorf("systemd-boot: oczekiwano ESP 1024 MiB, otrzymano %d", cfg.Installer.ESPSizeMiB)
+	}
+}
+
+func TestLoad_InstallerLanguagePacks(t *testing.T) {
+	cfg, err := loadWith(t, "\n[installer]\n-> language_packs => [pl, de]\n")
+	if err != nil {
+		t.Fatalf("Load zwrocilo blad: %v", err)
+	}
+	if l := cfg.Installer.LanguagePacks; len(l) != 2 || l[0] != "pl" || l[1] != "de" {
+		t.Errorf("oczekiwano [pl de], otrzymano %v", cfg.Installer.LanguagePacks)
+	}
+}
+
+func TestLoad_InstallerKeyboard(t *testing.T) {
+	cfg, err := loadWith(t, "\n[installer]\n-> keyboard_secondary => us\n")
+	if err != nil {
+		t.Fatalf("Load zwrocilo blad: %v", err)
+	}
+	if cfg.Installer.KeyboardSecondary != "us" || cfg.Installer.KeyboardToggle != DefaultKeyboardToggle {
+		t.Errorf("oczekiwano us/%s, otrzymano %q/%q",
+			DefaultKeyboardToggle, cfg.Installer.KeyboardSecondary, cfg.Installer.KeyboardToggle)
+	}
+}
+
+func TestLoad_ProjectAptParallel(t *testing.T) {
+	cfg, err := loadWith(t, "")
+	if err != nil {
+		t.Fatalf("Load zwrocilo blad: %v", err)
+	}
+	if cfg.Project.AptParallel || cfg.Project.VerifyPackages {
+		t.Error("apt_parallel i verify_packages powinny byc domyslnie wylaczone")
+	}
+
+	cfg, err = loadWith(t, "\n[project]\n-> apt_parallel => true\n-> verify_packages => true\n")
+	if err != nil {
+		t.Fatalf("Load zwrocilo blad: %v", err)
+	}
+	if !cfg.Project.AptParallel {
+		t.Error("oczekiwano AptParallel=true dla apt_parallel => true")
+	}
+	if !cfg.Project.VerifyPackages {
+		t.Error("oczekiwano VerifyPackages=true dla verify_packages => true")
+	}
+}
+
+func TestLoad_SystemConsoleFont(t *testing.T) {
+	cfg, err := loadWith(t, "")
+	if err != nil {
+		t.Fatalf("Load zwrocilo blad: %v", err)
+	}
+	if cfg.System.ConsoleFont != ConsoleFontAuto {
+		t.Errorf("oczekiwano domyslnie ConsoleFont=auto, otrzymano %q", cfg.System.ConsoleFont)
+	}
+
+	for in, want := range map[string]string{
+		"none":                       ConsoleFontNone,
+		"Lat15-Terminus32x16.psf.gz": "Lat15-Terminus32x16",
+	} {
+		cfg, err := loadWith(t, "\n[system]\n-> console_font => "+in+"\n")
+		if err != nil {
+			t.Fatalf("console_font => %s: Load zwrocilo blad: %v", in, err)
+		}
+		if cfg.System.ConsoleFont != want {
+			t.Errorf("console_font => %s: oczekiwano %q, otrzymano %q", in, want, cfg.System.ConsoleFont)
+		}
+	}
+}
+
+func TestLoad_SystemMicrocode(t *testing.T) {
+	cfg, err := loadWith(t, "")
+	if err != nil {
+		t.Fatalf("Load zwrocilo blad: %v", err)
+	}
+	if !cfg.System.Microcode {
+		t.Error("mikrokod powinien byc domyslnie wlaczony przy non-free-firmware")
+	}
+
+	cfg, err = loadWith(t, "-> components => main\n")
+	if err != nil {
+		t.Fatalf("Load zwrocilo blad: %v", err)
+	}
+	if cfg.System.Microcode {
+		t.Error("bez komponentu z firmware mikrokod powinien byc pominiety")
+	}
+
+	cfg, err = loadWith(t, "\n[system]\n-> microcode => false\n")
+	if err != nil {
+		t.Fatalf("Load zwrocilo blad: %v", err)
+	}
+	if cfg.System.Microcode {
+		t.Error("microcode => false nie wylacza mikrokodu")
+	}
+}
+
+func TestLoad_SystemNvidia(t *testing.T) {
+	for _, nv := range []string{NvidiaNouveau, NvidiaNonfree, NvidiaOpen} {
+		cfg, err := loadWith(t, "\n[system]\n-> nvidia => "+nv+"\n")
+		if err != nil {
+			t.Fatalf("nvidia => %s: Load zwrocilo blad: %v", nv, err)
+		}
+		if cfg.System.Nvidia != nv {
+			t.Errorf("oczekiwano Nvidia=%q, otrzymano %q", nv, cfg.System.Nvidia)
+		}
+	}
+
+	cfg, err := loadWith(t, "-> components => main, contrib, non-free-firmware\n\n[system]\n-> nvidia => nouveau\n")
+	if err != nil {
+		t.Fatalf("nvidia => nouveau bez non-free: Load zwrocilo blad: %v", err)
+	}
+	if cfg.System.Nvidia != NvidiaNouveau {
+		t.Errorf("oczekiwano Nvidia=nouveau, otrzymano %q", cfg.System.Nvidia)
+	}
+}
+
+func TestLoad_SystemLoginDefs(t *testing.T) {
+	cfg, err := loadWith(t, "\n[system]\n-> login_defs => hardened\n-> umask => 0027\n")
+	if err != nil {
+		t.Fatalf("Load zwrocilo blad: %v", err)
+	}
+	if cfg.System.LoginDefs != LoginDefsHardened || cfg.System.Umask != "027" {
+		t.Errorf("oczekiwano hardened/027, otrzymano %q/%q", cfg.System.LoginDefs, cfg.System.Umask)
+	}
+	// Niecytowana umask to dla parsera .hk liczba (027 -> 27) -- tak jak w
+	// powloce, brakujace zera wiodace sa dopelniane.
+	for in, want := range map[string]string{"027": "027", "22": "022", "077": "077", `"0027"`: "027"} {
+		cfg, err := loadWith(t, "\n[system]\n-> umask => "+in+"\n")
+		if err != nil {
+			t.Fatalf("umask => %s: Load zwrocilo blad: %v", in, err)
+		}
+		if cfg.System.Umask != want {
+			t.Errorf("umask => %s: oczekiwano %q, otrzymano %q", in, want, cfg.System.Umask)
+		}
+	}
+	if got := LoginDefsValue(LoginDefsHardened, "HOME_MODE"); got != "0700" {
+		t.Errorf("hardened: oczekiwano HOME_MODE 0700, otrzymano %q", got)
+	}
+	if got := LoginDefsValue(LoginDefsStandard, "HOME_MODE"); got != "" {
+		t.Errorf("standard nie powinien ustawiac HOME_MODE, otrzymano %q", got)
+	}
+
+	// password_warn_days => 0 (bez ostrzezenia) nie jest "brakiem klucza" --
+	// nadpisuje PASS_WARN_AGE 14 z presetu hardened.
+	cfg, err = loadWith(t, "\n[system]\n-> login_defs => hardened\n-> password_warn_days => 0\n")
+	if err != nil {
+		t.Fatalf("password_warn_days => 0: Load zwrocilo blad: %v", err)
+	}
+	if !cfg.System.PasswordWarnDaysSet || cfg.System.PasswordWarnDays != 0 {
+		t.Errorf("oczekiwano ustawionego PasswordWarnDays=0, otrzymano %d (set=%v)",
+			cfg.System.PasswordWarnDays, cfg.System.PasswordWarnDaysSet)
+	}
+
+	// Jawna polityka hasel ma pierwszenstwo przed presetem.
+	if _, err := loadWith(t, "\n[system]\n-> login_defs => hardened\n"+
+		"-> password_max_days => 730\n-> password_warn_days => 400\n"); err != nil {
+		t.Errorf("password_max_days nadpisuje preset: %v", err)
+	}
+}
+
+func TestLoad_ReleaseSnapshot(t *testing.T) {
+	cfg, err := loadWith(t, "-> snapshot => 20250701T000000Z\n")
+	if err != nil {
+		t.Fatalf("Load zwrocilo blad: %v", err)
+	}
+	if cfg.Snapshot != "20250701T000000Z" {
+		t.Errorf("oczekiwano Snapshot=20250701T000000Z, otrzymano %q", cfg.Snapshot)
+	}
+}
+
+func TestLoad_SystemDNS(t *testing.T) {
+	cfg, err := loadWith(t, "\n[system]\n-> dns => resolved\n-> dns_servers => [1.1.1.1, 9.9.9.9]\n")
+	if err != nil {
+		t.Fatalf("Load zwrocilo blad: %v", err)
+	}
+	if cfg.System.DNS != DNSResolved || len(cfg.System.DNSServers) != 2 {
+		t.Errorf("oczekiwano resolved z 2 serwerami, otrzymano %q %v", cfg.System.DNS, cfg.System.DNSServers)
+	}
+}
+
+func TestLoad_SystemSecurityUpdates(t *testing.T) {
+	cfg, err := loadWith(t, "\n[system]\n-> security_updates => true\n")
+	if err != nil {
+		t.Fatalf("Load zwrocilo blad: %v", err)
+	}
+	if !cfg.System.SecurityUpdates {
+		t.Error("oczekiwano SecurityUpdates=true")
+	}
+
+	sid := strings.Replace(testBaseConfig, "trixie", "sid", 1)
+	if _, err := Load(writeTestConfig(t, sid+"\n[system]\n-> security_updates => true\n")); err == nil {
+		t.Error("security_updates na sid: oczekiwano bledu walidacji")
+	}
+}
+
+func TestLoad_SystemGeneralize(t *testing.T) {
+	cfg, err := loadWith(t, "")
+	if err != nil {
+		t.Fatalf("Load zwrocilo blad: %v", err)
+	}
+	if cfg.System.Generalize {
+		t.Error("generalize powinno byc domyslnie wylaczone")
+	}
+
+	cfg, err = loadWith(t, "\n[system]\n-> generalize => true\n")
+	if err != nil {
+		t.Fatalf("Load zwrocilo blad: %v", err)
+	}
+	if !cfg.System.Generalize {
+		t.Error("oczekiwano Generalize=true dla generalize => true")
+	}
+}
+
+// TestLoad_RejectsBadValues sprawdza, ze Load odrzuca niepoprawne wartosci.
+// Pusta sekcja oznacza dopisanie kluczy do [release] z testBaseConfig.
+func TestLoad_RejectsBadValues(t *testing.T) {
+	for _, tc := range []struct{ section, keys string }{
+		{"", "-> snapshot => 2025-07-01\n"},
+		{"", "-> snapshot => 20251301T000000Z\n"},
+		{"", "-> snapshot => 29991231T000000Z\n"},
+		{"", "-> snapshot => 20250601T000000Z\n\n[system]\n-> security_updates => true\n"},
+		{"", "-> components => main\n\n[system]\n-> microcode => true\n"},
+		{"", "-> components => main, contrib, non-free-firmware\n\n[system]\n-> nvidia => nonfree\n"},
+
+		{"system", "-> password_max_days => 0\n"},
+		{"system", "-> password_max_days => abc\n"},
+		{"system", "-> password_max_days => 100000\n"},
+		{"system", "-> editor => notepad\n"},
+		{"system", "-> initramfs_compress => lzma\n"},
+		{"system", "-> time_sync => none\n-> ntp_servers => [pool.ntp.org]\n"},
+		{"system", "-> console_font => ../x\n"},
+		{"system", "-> nvidia => nvidia-legacy\n"},
+		{"system", "-> login_defs => paranoid\n"},
+		{"system", "-> umask => 777\n"},
+		{"system", "-> umask => 089\n"},
+		{"system", "-> umask => 2.5\n"},
+		{"system", "-> umask => u=rwx\n"},
+		// hardened: PASS_MAX_DAYS 365
+		{"system", "-> login_defs => hardened\n-> password_warn_days => 365\n"},
+		{"system", "-> password_max_days => 30\n-> password_warn_days => 30\n"},
+		{"system", "-> dns => dnsmasq\n"},
+		{"system", "-> dns => static\n"},
+		{"system", "-> dns_servers => [1.1.1.1]\n"},
+		{"system", "-> dns => static\n-> dns_servers => [dns.example.com]\n"},
+
+		{"grub", "-> timeout => -1\n"},
+		{"grub", "-> default => ostatni\n"},
+		{"grub", "-> timeout => 0\n-> hidden_menu => true\n"},
+
+		{"timeouts", "-> apt_update => -5\n"},
+
+		{"installer", "-> bootloader_id => Hacker OS\n"},
+		{"installer", "-> user_uid => 0\n"},
+		{"installer", "-> user_uid => 999\n"},
+		{"installer", "-> user_gid => 1001\n"},
+		{"installer", "-> post_install => [\"ls\", \"\"]\n"},
+		{"installer", "-> disable_services => [\"cups; reboot\"]\n"},
+		{"installer", "-> disable_services => cups\n-> mask_services => cups\n"},
+		{"installer", "-> swap_size => 10%\n"},
+		{"installer", "-> swap_size => 8G\n-> required_storage_gb => 10\n"},
+		{"installer", "-> root_size => 30GiB\n"},
+		{"installer", "-> var_log_size => 2GiB\n"},
+		{"installer", "-> separate_var_log => true\n-> var_log_size => 10%\n"},
+		{"installer", "-> separate_home => true\n-> root_size => 30TB\n"},
+		{"installer", "-> separate_home => true\n-> root_size => 60%\n-> home_size => 50%\n"},
+		{"installer", "-> separate_home => true\n-> root_size => 100%\n"},
+		{"installer", "-> esp_size_mib => 10\n"},
+		{"installer", "-> bootloader => lilo\n"},
+		{"installer", "-> bootloader => systemd-boot\n-> esp_size_mib => 256\n"},
+		{"installer", "-> root_filesystem => ntfs\n"},
+		{"installer", "-> home_filesystem => ext4\n"},
+		{"installer", "-> ext4_mount_options => \"noatime;reboot\"\n"},
+		{"installer", "-> btrfs_layout => suse\n"},
+		{"installer", "-> apt_snapshots => true\n"},
+		{"installer", "-> language_packs => [pl, klingon]\n"},
+		{"installer", "-> keyboard_secondary => \"us;reboot\"\n"},
+		{"installer", "-> keyboard_toggle => grp:ctrl_shift_toggle\n"},
+		{"installer", "-> keyboard_secondary => us\n-> keyboard_toggle => alt_shift\n"},
+		{"installer", "-> serial_console => ttyS0\n"},
+		{"installer", "-> serial_console => \"yes please\"\n"},
+	} {
+		extra := tc.keys
+		if tc.section != "" {
+			extra = "\n[" + tc.section + "]\n" + tc.keys
+		}
+		if _, err := loadWith(t, extra); err == nil {
+			t.Errorf("%q: oczekiwano bledu walidacji", extra)
+		}
+	}
+}
@@ -0,0 +1,112 @@
+package config
+
+import (
+	"fmt"
+	"strconv"
+	"strings"
+
+	"github.com/HackerOS-Linux-System/hackeros-builder/internal/hk"
+)
+
+// GrubConfig to zawartosc sekcji [grub] w config/config.hk -- ustawienia
+// GRUB zainstalowanego systemu (nie menu samego ISO). Wpisywane do obrazu
+// jako /etc/default/grub.d/hackeros.cfg, czytane przez update-grub /
+// grub-mkconfig, takze w instalatorze. Wszystkie pola opcjonalne.
+type GrubConfig struct {
+	// Timeout to GRUB_TIMEOUT w sekundach; nil -- domyslne Debiana (5).
+	Timeout *int
+
+	// Default to GRUB_DEFAULT: numer pozycji menu albo "saved" (ostatnio
+	// wybrana, z GRUB_SAVEDEFAULT=true). Puste -- domyslne (0).
+	Default string
+
+	// HiddenMenu to GRUB_TIMEOUT_STYLE=hidden: menu pojawia sie tylko po
+	// nacisnieciu klawisza (Esc/Shift) w ciagu Timeout sekund.
+	HiddenMenu bool
+
+	// OSProber to odwrotnosc GRUB_DISABLE_OS_PROBER: czy update-grub
+	// dopisuje inne systemy z dysku (dual-boot). nil -- domyslne Debiana
+	// (od bookworm: wylaczone).
+	OSProber *bool
+
+	// OSProberSkip to GRUB_OS_PROBER_SKIP_LIST -- systemy wykryte przez
+	// os-prober, ktorych NIE dopisywac do menu, w formie "UUID" albo
+	// "UUID@/dev/sdXN" (UUID z "blkid" / wyjscia os-prober).
+	OSProberSkip []string
+
+	// Recovery to odwrotnosc GRUB_DISABLE_RECOVERY: pozycje "(recovery
+	// mode)" (tryb jednego uzytkownika) dla kazdego zainstalowanego jadra w
+	// "Advanced options" -- z poprzednim jadrem, gdy nowe nie wstaje. nil --
+	// domyslne Debiana (wlaczone).
+	Recovery *bool
+}
+
+// IsEmpty zwraca true gdy sekcja [grub] niczego nie ustawia.
+func (g GrubConfig) IsEmpty() bool {
+	return g.Timeout == nil && g.Default == "" && !g.HiddenMenu &&
+		g.OSProber == nil && len(g.OSProberSkip) == 0 && g.Recovery == nil
+}
+
+// loadGrubSection wczytuje opcjonalna sekcje [grub].
+// Brak sekcji lub poszczegolnych kluczy -> wartosci domyslne, brak bledu.
+func loadGrubSection(parsed *hk.HkConfig) (GrubConfig, error) {
+	var g GrubConfig
+	sec, err := parsed.Section("grub")
+	if err != nil {
+		return g, nil
+	}
+
+	if n, ok, err := optInt(sec, "timeout", 0, 3600); err != nil {
+		return GrubConfig{}, err
+	} else if ok {
+		g.Timeout = &n
+	}
+
+	if def, ok := optString(sec, "default"); ok && def != "" {
+		if n, err := strconv.Atoi(def); (err != nil || n < 0) && def != "saved" {
+			return GrubConfig{}, fmt.Errorf(
+				"config.hk: [grub] -> default: oczekiwano numeru pozycji (>= 0) albo \"saved\", otrzymano %q", def)
+		}
+		g.Default = def
+	}
+
+	if v, ok := optBool(sec, "hidden_menu"); ok {
+		g.HiddenMenu = v
+	}
+
+	if v, ok := optBool(sec, "os_prober"); ok {
+		g.OSProber = &v
+	}
+
+	if v, ok := optBool(sec, "recovery"); ok {
+		g.Recovery = &v
+	}
+
+	if val, ok := sec.Get("os_prober_skip"); ok {
+		list, err := valueAsStringList(val)
+		if err != nil {
+			return GrubConfig{}, fmt.Errorf("config.hk: [grub] -> os_prober_skip: %w", err)
+		}
+		for _, entry := range list {
+			if strings.ContainsAny(entry, `"'$\`+"`") {
+				return GrubConfig{}, fmt.Errorf(
+					"config.hk: [grub] -> os_prober_skip: niedozwolony znak w %q "+
+						"(oczekiwano UUID albo UUID@/dev/...)", entry)
+			}
+		}
+		g.OSProberSkip = list
+	}
+
+	if g.OSProber != nil && !*g.OSProber && len(g.OSProberSkip) > 0 {
+		return GrubConfig{}, fmt.Errorf(
+			"config.hk: [grub] -> os_prober_skip wymaga os_prober = true (lub braku klucza os_prober)")
+	}
+
+	if g.HiddenMenu && g.Timeout != nil && *g.Timeout == 0 {
+		return GrubConfig{}, fmt.Errorf(
+			"config.hk: [grub] -> hidden_menu wymaga timeout > 0 -- " +
+				"przy 0 nie byloby jak wywolac menu klawiszem")
+	}
+
+	return g, nil
+}
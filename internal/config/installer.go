@@ -0,0 +1,472 @@
+package config
+
+import (
+	"fmt"
+	"regexp"
+	"sort"
+	"strings"
+
+	"github.com/HackerOS-Linux-System/hackeros-builder/internal/hk"
+)
+
+// DefaultBootloaderID to domyslna nazwa wpisu EFI (grub-install
+// --bootloader-id) -- widoczna w menu bootowania firmware i jako katalog
+// EFI/<id>/ na partycji ESP.
+const DefaultBootloaderID = "HackerOS"
+
+// bootloaderIDPattern: id trafia do nazwy katalogu na FAT (ESP) i do
+// etykiety wpisu NVRAM -- tylko bezpieczne znaki, bez spacji.
+var bootloaderIDPattern = regexp.MustCompile(`^[A-Za-z0-9._-]{1,32}$`)
+
+// xkbLayoutPattern / xkbGroupTogglePattern: kody ukladow XKB ("us", "pl",
+// "latam") i opcje przelaczania grup ("grp:alt_shift_toggle") -- trafiaja
+// do komendy sed w shellprocess, wiec tylko bezpieczne znaki.
+var (
+	xkbLayoutPattern      = regexp.MustCompile(`^[a-z]{2,8}$`)
+	xkbGroupTogglePattern = regexp.MustCompile(`^grp:[a-z0-9_]{1,32}$`)
+)
+
+// serviceUnitPattern: nazwa jednostki systemd ("cups", "avahi-daemon.socket",
+// "getty@tty2.service") -- trafia do komendy systemctl w shellprocess.
+var serviceUnitPattern = regexp.MustCompile(`^[A-Za-z0-9@._:-]{1,128}$`)
+
+// DefaultKeyboardToggle to przelacznik ukladow gdy keyboard_secondary
+// jest ustawione bez keyboard_toggle.
+const DefaultKeyboardToggle = "grp:alt_shift_toggle"
+
+// LanguagePack to sufiksy nazw pakietow jezykowych jednego jezyka w
+// Debianie: Mozilla -- firefox-esr-l10n-* i thunderbird-l10n-*,
+// LibreOffice -- libreoffice-l10n-*, Hunspell -- slownik hunspell-*.
+// Samo srodowisko (GNOME, KDE, Xfce) ma tlumaczenia w swoich pakietach.
+type LanguagePack struct {
+	Mozilla     string
+	LibreOffice string
+	Hunspell    string
+}
+
+// LanguagePacks to obslugiwane jezyki [installer] -> language_packs (kod
+// jezyka jak w LANG, np. "pl" z pl_PL.UTF-8).
+var LanguagePacks = map[string]LanguagePack{
+	"cs": {"cs", "cs", "cs"},
+	"de": {"de", "de", "de-de"},
+	"es": {"es-es", "es", "es"},
+	"fr": {"fr", "fr", "fr"},
+	"it": {"it", "it", "it"},
+	"nl": {"nl", "nl", "nl"},
+	"pl": {"pl", "pl", "pl"},
+	"pt": {"pt-pt", "pt", "pt-pt"},
+	"ru": {"ru", "ru", "ru"},
+	"uk": {"uk", "uk", "uk"},
+}
+
+// Bootloadery systemu docelowego ([installer] -> bootloader).
+const (
+	BootloaderGrub        = "grub"
+	BootloaderSystemdBoot = "systemd-boot"
+)
+
+// systemdBootESPSizeMiB to domyslny (i minimalny zalecany) rozmiar ESP
+// dla systemd-boot -- jadra i initramfs leza na ESP, nie w /boot.
+const systemdBootESPSizeMiB = 1024
+
+// Uklady podwolumenow btrfs ([installer] -> btrfs_layout).
+const (
+	BtrfsLayoutSimple = "simple"
+	BtrfsLayoutFull   = "full"
+)
+
+// InstallerConfig to zawartosc sekcji [installer] w config/config.hk --
+// ustawienia instalatora Calamares wstrzykiwanego do ISO przez "build iso".
+// Nie wplywa na obraz OCI wypychany przez "build cloud".
+// Wszystkie pola opcjonalne -- brak sekcji nie jest bledem.
+type InstallerConfig struct {
+	// ForcePasswordChange wymusza zmiane hasla uzytkownika przy pierwszym
+	// logowaniu ("chage -d 0" na koncie utworzonym przez Calamares).
+	ForcePasswordChange bool
+
+	// Bootloader to bootloader systemu docelowego: BootloaderGrub
+	// (domyslnie, gdy puste) albo BootloaderSystemdBoot -- tylko UEFI,
+	// jadra kopiowane na ESP przez kernel-install.
+	Bootloader string
+
+	// BootloaderID to nazwa wpisu EFI tworzonego przez grub-install
+	// (efiBootloaderId w bootloader.conf). Puste -- DefaultBootloaderID.
+	BootloaderID string
+
+	// SkipSSDTrim wylacza automatyczne TRIM na dyskach SSD (fstrim.timer +
+	// discard=async dla btrfs). Domyslnie false: instalator sam wykrywa
+	// SSD po /sys/block/<dysk>/queue/rotational.
+	SkipSSDTrim bool
+
+	// SkipVMGuestTools wylacza narzedzia goscia maszyn wirtualnych
+	// (qemu-guest-agent, spice-vdagent, open-vm-tools, hyperv-daemons).
+	// Domyslnie false: sa na nosniku, a instalator zostawia w systemie
+	// tylko te pasujace do wykrytego hypervisora.
+	SkipVMGuestTools bool
+
+	// TTYAutologin loguje uzytkownika z instalatora automatycznie na tty1
+	// (override getty@tty1) -- dla urzadzen/kioskow bez pulpitu. Pomijane,
+	// gdy obraz ma menedzer logowania (tam autologin z kroku "users").
+	TTYAutologin bool
+
+	// SerialConsole wlacza konsole szeregowa w systemie docelowym:
+	// serial-getty@ttyS0, console=ttyS0,115200 w wierszu polecen jadra i
+	// terminal szeregowy GRUB. nil ("auto") -- wlaczana, gdy instalator
+	// dziala na nosniku uruchomionym z konsola na ttyS0.
+	SerialConsole *bool
+
+	// UserUID / UserGID to UID i GID konta tworzonego w instalatorze (np.
+	// zgodne z przenoszonym /home z innej maszyny). 0 -- bez zmian
+	// (useradd przydziela pierwszy wolny >= UID_MIN, zwykle 1000).
+	// UserGID domyslnie rowny UserUID.
+	UserUID int
+	UserGID int
+
+	// BtrfsSnapshots instaluje snapper (+ grub-btrfs gdy dostepny) i, przy
+	// instalacji na btrfs z ukladem "@", tworzy konfiguracje snappera i
+	// bazowy snapshot "post-install".
+	BtrfsSnapshots bool
+
+	// SkipAptSnapshots wylacza snapshoty pre/post przy kazdej operacji
+	// apt/dpkg (hook snappera w apt.conf.d). Domyslnie false: przy
+	// BtrfsSnapshots kazda aktualizacja ma punkt powrotu.
+	SkipAptSnapshots bool
+
+	// BtrfsLayout to uklad podwolumenow przy instalacji na btrfs:
+	// BtrfsLayoutSimple (domyslny: @, @home, @var@log) albo BtrfsLayoutFull
+	// (dodatkowo osobne podwolumeny na dane wylaczone ze snapshotow /).
+	BtrfsLayout string
+
+	// KeyboardSecondary to drugi uklad klawiatury XKB dopisywany po
+	// ukladzie wybranym w Calamares (XKBLAYOUT="pl,us"), KeyboardToggle --
+	// opcja XKB przelaczajaca uklady. Puste -- tylko uklad z instalatora.
+	KeyboardSecondary string
+	KeyboardToggle    string
+
+	// LanguagePacks to kody jezykow (klucze config.LanguagePacks), ktorych
+	// pakiety jezykowe trafiaja na nosnik; instalator zostawia w systemie
+	// tylko pakiety jezyka wybranego w kroku "locale". Puste -- bez
+	// pakietow jezykowych (mniejszy obraz).
+	LanguagePacks []string
+
+	// RecommendedRAMGB to prog RAM (GB), ponizej ktorego ekran powitalny
+	// Calamares pokazuje OSTRZEZENIE (instalacja dalej mozliwa).
+	// RequiredStorageGB to minimalny rozmiar dysku (GB) -- ponizej
+	// instalacja jest zablokowana. 0 -- wartosci domyslne (patrz
+	// RAMThresholdGB / StorageThresholdGB).
+	RecommendedRAMGB  int
+	RequiredStorageGB int
+
+	// ESPSizeMiB to rozmiar partycji EFI w ukladzie automatycznym (MiB).
+	// 0 -- domyslne Calamares.
+	ESPSizeMiB int
+
+	// SeparateHome tworzy w ukladzie automatycznym osobna partycje /home.
+	// RootSize / HomeSize to wtedy ich rozmiary (patrz loadPartitionLayout;
+	// domyslnie 40% / reszta). Bez SeparateHome root zajmuje caly dysk.
+	SeparateHome bool
+	RootSize     PartitionSize
+	HomeSize     PartitionSize
+
+	// SeparateVarLog tworzy w ukladzie automatycznym osobna partycje
+	// /var/log o rozmiarze VarLogSize (domyslnie 4GiB) -- logi nie moga
+	// zapelnic /. System plikow jak root.
+	SeparateVarLog bool
+	VarLogSize     PartitionSize
+
+	// SwapSize to rozmiar partycji swap w ukladzie automatycznym (MiB/GiB).
+	// Zerowy -- bez partycji swap w ukladzie, swap wg wyboru uzytkownika w
+	// instalatorze (userSwapChoices).
+	SwapSize PartitionSize
+
+	// RootFilesystem / HomeFilesystem wymuszaja system plikow partycji w
+	// ukladzie automatycznym ("ext4", "btrfs", "xfs"). Puste -- typ
+	// wybrany przez uzytkownika w instalatorze.
+	RootFilesystem string
+	HomeFilesystem string
+
+	// MountOptions to opcje montowania w fstab per system plikow
+	// (klucze <fs>_mount_options) -- dotycza kazdej partycji danego typu,
+	// tak jak mountOptions w Calamares. nil -- "defaults".
+	MountOptions map[string][]string
+
+	// DisableServices / MaskServices to jednostki systemd wylaczane
+	// ("systemctl disable") albo maskowane ("systemctl mask") przez
+	// instalator w systemie docelowym, np. bluetooth, cups, avahi-daemon.
+	// Nazwa bez sufiksu obejmuje wszystkie jednostki o tej nazwie
+	// (.service, .socket, .path, .timer) -- patrz isobuild.resolveServiceUnits.
+	// Nosnik live zostaje bez zmian.
+	DisableServices []string
+	MaskServices    []string
+
+	// PostInstall to komendy sh uruchamiane przez instalator w chroot
+	// systemu docelowego po hookach z config/hooks/install, w kolejnosci z
+	// config.hk (odpowiednik %post z kickstart).
+	PostInstall []string
+
+	// PostInstallContinueOnError: blad hooka instalacji albo komendy
+	// PostInstall jest tylko logowany, instalacja idzie dalej. Domyslnie
+	// false: pierwszy blad przerywa instalacje.
+	PostInstallContinueOnError bool
+}
+
+// RAMThresholdGB zwraca RecommendedRAMGB albo domyslne 2 GB.
+func (in InstallerConfig) RAMThresholdGB() int {
+	if in.RecommendedRAMGB == 0 {
+		return 2
+	}
+	return in.RecommendedRAMGB
+}
+
+// StorageThresholdGB zwraca RequiredStorageGB albo domyslne 4 GB --
+// nie mniej niz MinStorageGB (bezwzgledne rozmiary partycji musza sie
+// zmiescic, inaczej Calamares odmowi dopiero na etapie partycjonowania).
+func (in InstallerConfig) StorageThresholdGB() int {
+	gb := in.RequiredStorageGB
+	if gb == 0 {
+		gb = 4
+	}
+	if min := in.MinStorageGB(); min > gb {
+		return min
+	}
+	return gb
+}
+
+// UsesSystemdBoot zwraca true gdy system docelowy startuje przez
+// systemd-boot zamiast GRUB.
+func (in InstallerConfig) UsesSystemdBoot() bool {
+	return in.Bootloader == BootloaderSystemdBoot
+}
+
+// EFIBootloaderID zwraca BootloaderID albo DefaultBootloaderID gdy puste.
+func (in InstallerConfig) EFIBootloaderID() string {
+	if in.BootloaderID == "" {
+		return DefaultBootloaderID
+	}
+	return in.BootloaderID
+}
+
+// languagePackCodes zwraca posortowane klucze LanguagePacks.
+func languagePackCodes() []string {
+	codes := make([]string, 0, len(LanguagePacks))
+	for c := range LanguagePacks {
+		codes = append(codes, c)
+	}
+	sort.Strings(codes)
+	return codes
+}
+
+// loadInstallerSection wczytuje opcjonalna sekcje [installer].
+// Brak sekcji lub poszczegolnych kluczy -> wartosci domyslne, brak bledu.
+func loadInstallerSection(parsed *hk.HkConfig) (InstallerConfig, error) {
+	var in InstallerConfig
+	sec, err := parsed.Section("installer")
+	if err != nil {
+		return in, nil
+	}
+
+	if v, ok := optBool(sec, "force_password_change"); ok {
+		in.ForcePasswordChange = v
+	}
+
+	if v, ok := optBool(sec, "ssd_trim"); ok {
+		in.SkipSSDTrim = !v
+	}
+	if v, ok := optBool(sec, "vm_guest_tools"); ok {
+		in.SkipVMGuestTools = !v
+	}
+	if v, ok := optBool(sec, "tty_autologin"); ok {
+		in.TTYAutologin = v
+	}
+
+	if v, ok := optString(sec, "serial_console"); ok {
+		switch strings.ToLower(v) {
+		case "auto":
+		case "true", "false":
+			on := isTruthy(v)
+			in.SerialConsole = &on
+		default:
+			return in, fmt.Errorf(
+				"config.hk: [installer] -> serial_console %q: dozwolone \"auto\", \"true\" lub \"false\"", v)
+		}
+	}
+
+	if v, ok := optBool(sec, "btrfs_snapshots"); ok {
+		in.BtrfsSnapshots = v
+	}
+	if v, ok := optBool(sec, "apt_snapshots"); ok {
+		if v && !in.BtrfsSnapshots {
+			return in, fmt.Errorf("config.hk: [installer] -> apt_snapshots wymaga btrfs_snapshots => true")
+		}
+		in.SkipAptSnapshots = !v
+	}
+
+	if v, ok := optString(sec, "btrfs_layout"); ok && v != "" {
+		if v != BtrfsLayoutSimple && v != BtrfsLayoutFull {
+			return in, fmt.Errorf(
+				"config.hk: [installer] -> btrfs_layout %q: dozwolone %q lub %q", v, BtrfsLayoutSimple, BtrfsLayoutFull)
+		}
+		in.BtrfsLayout = v
+	}
+
+	if v, ok := optString(sec, "keyboard_secondary"); ok && v != "" {
+		if !xkbLayoutPattern.MatchString(v) {
+			return in, fmt.Errorf(
+				"config.hk: [installer] -> keyboard_secondary %q: oczekiwano kodu ukladu XKB (np. us, pl, de)", v)
+		}
+		in.KeyboardSecondary = v
+		in.KeyboardToggle = DefaultKeyboardToggle
+	}
+	if v, ok := optString(sec, "keyboard_toggle"); ok && v != "" {
+		if in.KeyboardSecondary == "" {
+			return in, fmt.Errorf("config.hk: [installer] -> keyboard_toggle wymaga keyboard_secondary")
+		}
+		if !xkbGroupTogglePattern.MatchString(v) {
+			return in, fmt.Errorf(
+				"config.hk: [installer] -> keyboard_toggle %q: oczekiwano opcji XKB grp:... (np. %s)", v, DefaultKeyboardToggle)
+		}
+		in.KeyboardToggle = v
+	}
+
+	if val, ok := sec.Get("language_packs"); ok {
+		langs, err := valueAsStringList(val)
+		if err != nil {
+			return in, fmt.Errorf("config.hk: [installer] -> language_packs: %w", err)
+		}
+		for _, l := range langs {
+			if _, known := LanguagePacks[l]; !known {
+				return in, fmt.Errorf(
+					"config.hk: [installer] -> language_packs: nieobslugiwany jezyk %q (dostepne: %s)",
+					l, strings.Join(languagePackCodes(), ", "))
+			}
+		}
+		in.LanguagePacks = langs
+	}
+
+	if n, ok, err := optInt(sec, "recommended_ram_gb", 1, 1024); err != nil {
+		return in, err
+	} else if ok {
+		in.RecommendedRAMGB = n
+	}
+	if n, ok, err := optInt(sec, "required_storage_gb", 1, 65536); err != nil {
+		return in, err
+	} else if ok {
+		in.RequiredStorageGB = n
+	}
+
+	if err := loadPartitionLayout(sec, &in); err != nil {
+		return in, err
+	}
+
+	// Zakres 1000-59999: ponizej to konta systemowe (SYS_UID_MAX 999),
+	// od 60000 -- nobody/zakresy subuid (UID_MAX w login.defs).
+	if n, ok, err := optInt(sec, "user_uid", 1000, 59999); err != nil {
+		return in, err
+	} else if ok {
+		in.UserUID = n
+		in.UserGID = n
+	}
+	if n, ok, err := optInt(sec, "user_gid", 1000, 59999); err != nil {
+		return in, err
+	} else if ok {
+		if in.UserUID == 0 {
+			return in, fmt.Errorf("config.hk: [installer] -> user_gid wymaga user_uid")
+		}
+		in.UserGID = n
+	}
+
+	if bl, ok := optString(sec, "bootloader"); ok && bl != "" {
+		if bl != BootloaderGrub && bl != BootloaderSystemdBoot {
+			return in, fmt.Errorf(
+				"config.hk: [installer] -> bootloader %q: dozwolone %q lub %q", bl, BootloaderGrub, BootloaderSystemdBoot)
+		}
+		in.Bootloader = bl
+	}
+	if in.UsesSystemdBoot() {
+		switch {
+		case in.ESPSizeMiB == 0:
+			in.ESPSizeMiB = systemdBootESPSizeMiB
+		case in.ESPSizeMiB < 512:
+			return in, fmt.Errorf(
+				"config.hk: [installer] -> esp_size_mib = %d za male dla systemd-boot (jadra na ESP) -- minimum 512",
+				in.ESPSizeMiB)
+		}
+	}
+
+	if id, ok := optString(sec, "bootloader_id"); ok && id != "" {
+		if !bootloaderIDPattern.MatchString(id) {
+			return in, fmt.Errorf(
+				"config.hk: [installer] -> bootloader_id %q: dozwolone litery, cyfry, '.', '_', '-' (1-32 znaki)", id)
+		}
+		in.BootloaderID = id
+	}
+
+	services := map[string]string{}
+	for _, key := range []string{"disable_services", "mask_services"} {
+		val, ok := sec.Get(key)
+		if !ok {
+			continue
+		}
+		units, err := valueAsStringList(val)
+		if err != nil {
+			return in, fmt.Errorf("config.hk: [installer] -> %s: %w", key, err)
+		}
+		for _, u := range units {
+			if !serviceUnitPattern.MatchString(u) {
+				return in, fmt.Errorf("config.hk: [installer] -> %s: niepoprawna nazwa jednostki %q", key, u)
+			}
+			if prev, dup := services[u]; dup {
+				return in, fmt.Errorf("config.hk: [installer] -> %s: %q jest juz w %s", key, u, prev)
+			}
+			services[u] = key
+		}
+		if key == "disable_services" {
+			in.DisableServices = units
+		} else {
+			in.MaskServices = units
+		}
+	}
+
+	if val, ok := sec.Get("post_install"); ok {
+		cmds, err := postInstallCommands(val)
+		if err != nil {
+			return in, fmt.Errorf("config.hk: [installer] -> post_install: %w", err)
+		}
+		in.PostInstall = cmds
+	}
+	if v, ok := optBool(sec, "post_install_fail_fast"); ok {
+		in.PostInstallContinueOnError = !v
+	}
+
+	// Po esp_size_mib dla systemd-boot: prog dysku podany wprost nie moze
+	// byc mniejszy niz suma ukladu automatycznego -- Calamares przepuscilby
+	// za maly dysk i partycjonowanie padloby dopiero w trakcie.
+	if min := in.MinStorageGB(); in.RequiredStorageGB != 0 && in.RequiredStorageGB < min {
+		return in, fmt.Errorf("config.hk: [installer] -> required_storage_gb = %d mniejsze niz uklad automatyczny: %s",
+			in.RequiredStorageGB, in.MinStorageSummary())
+	}
+
+	return in, nil
+}
+
+// postInstallCommands zwraca komendy z post_install: tablice
+// ["komenda", ...] albo jedna komende. Inaczej niz valueAsStringList
+// nie dzieli na spacjach -- kazdy element to cala linia sh.
+func postInstallCommands(val hk.HkValue) ([]string, error) {
+	items := []hk.HkValue{val}
+	if arr, err := val.AsArray(); err == nil {
+		items = arr
+	}
+	var cmds []string
+	for i, item := range items {
+		s, err := item.AsString()
+		if err != nil {
+			return nil, fmt.Errorf("element %d: %w", i+1, err)
+		}
+		if s = strings.TrimSpace(s); s == "" {
+			return nil, fmt.Errorf("element %d: pusta komenda", i+1)
+		}
+		cmds = append(cmds, s)
+	}
+	return cmds, nil
+}
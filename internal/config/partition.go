@@ -0,0 +1,312 @@
+package config
+
+import (
+	"fmt"
+	"regexp"
+	"strconv"
+	"strings"
+
+	"github.com/HackerOS-Linux-System/hackeros-builder/internal/hk"
+)
+
+// installerFilesystems to systemy plikow oferowane przez instalator
+// (availableFileSystemTypes w partition.conf).
+var installerFilesystems = []string{"ext4", "btrfs", "xfs"}
+
+// mountOptionPattern: pojedyncza opcja montowania (np. "noatime",
+// "compress=zstd:3") -- bez spacji i przecinkow, te rozdzielaja opcje.
+var mountOptionPattern = regexp.MustCompile(`^[A-Za-z0-9_.:=+-]+$`)
+
+// partitionSizePattern: rozmiar partycji w config.hk -- liczba z jednostka
+// MiB/GiB (dopuszczalne skroty M/G) albo procent wolnego miejsca.
+var partitionSizePattern = regexp.MustCompile(`^([0-9]+)\s*(M|MiB|G|GiB|%)$`)
+
+// PartitionSize to rozmiar partycji w ukladzie automatycznym: Value w
+// jednostce Unit ("MiB", "GiB" albo "%").
+type PartitionSize struct {
+	Value int
+	Unit  string
+}
+
+// String zwraca rozmiar w formacie partitionLayout Calamares ("30GiB", "40%").
+func (p PartitionSize) String() string {
+	return strconv.Itoa(p.Value) + p.Unit
+}
+
+// parsePartitionSize parsuje i waliduje rozmiar partycji.
+func parsePartitionSize(s string) (PartitionSize, error) {
+	m := partitionSizePattern.FindStringSubmatch(s)
+	if m == nil {
+		return PartitionSize{}, fmt.Errorf("oczekiwano np. \"512MiB\", \"30GiB\" albo \"40%%\", otrzymano %q", s)
+	}
+	n, _ := strconv.Atoi(m[1])
+	unit := m[2]
+	switch unit {
+	case "M":
+		unit = "MiB"
+	case "G":
+		unit = "GiB"
+	}
+	if n == 0 || (unit == "%" && n > 100) {
+		return PartitionSize{}, fmt.Errorf("rozmiar poza zakresem: %q", s)
+	}
+	return PartitionSize{Value: n, Unit: unit}, nil
+}
+
+// loadPartitionLayout wczytuje z sekcji [installer] klucze ukladu
+// partycji automatycznych (esp_size_mib, separate_home, root_size,
+// home_size, separate_var_log, var_log_size, swap_size, root_filesystem,
+// home_filesystem, <fs>_mount_options) do in.
+func loadPartitionLayout(sec *hk.OrderedMap, in *InstallerConfig) error {
+	if err := loadFilesystemOptions(sec, in); err != nil {
+		return err
+	}
+	if err := loadVarLogPartition(sec, in); err != nil {
+		return err
+	}
+	if err := loadSwapPartition(sec, in); err != nil {
+		return err
+	}
+
+	if n, ok, err := optInt(sec, "esp_size_mib", 100, 4096); err != nil {
+		return err
+	} else if ok {
+		in.ESPSizeMiB = n
+	}
+
+	if v, ok := optBool(sec, "separate_home"); ok {
+		in.SeparateHome = v
+	}
+
+	sizes := map[string]*PartitionSize{}
+	for _, key := range []string{"root_size", "home_size"} {
+		s, ok := optString(sec, key)
+		if !ok || s == "" {
+			continue
+		}
+		if !in.SeparateHome {
+			return fmt.Errorf("config.hk: [installer] -> %s wymaga separate_home = true "+
+				"(bez osobnego /home partycja root zajmuje caly dysk)", key)
+		}
+		size, err := parsePartitionSize(s)
+		if err != nil {
+			return fmt.Errorf("config.hk: [installer] -> %s: %w", key, err)
+		}
+		sizes[key] = &size
+	}
+	if !in.SeparateHome {
+		if in.HomeFilesystem != "" {
+			return fmt.Errorf("config.hk: [installer] -> home_filesystem wymaga separate_home = true")
+		}
+		return nil
+	}
+
+	// Domyslnie: root 40% wolnego miejsca, /home reszta.
+	in.RootSize = PartitionSize{Value: 40, Unit: "%"}
+	in.HomeSize = PartitionSize{Value: 100, Unit: "%"}
+	if s := sizes["root_size"]; s != nil {
+		in.RootSize = *s
+	}
+	if s := sizes["home_size"]; s != nil {
+		in.HomeSize = *s
+	}
+
+	// Calamares rozdziela procenty od gory: root N% + home 100% znaczy
+	// "/home dostaje reszte". Dwie wartosci procentowe o sumie > 100 to
+	// blad konfiguracji, nie "reszta".
+	if in.RootSize.Unit == "%" && in.HomeSize.Unit == "%" && in.HomeSize.Value != 100 &&
+		in.RootSize.Value+in.HomeSize.Value > 100 {
+		return fmt.Errorf("config.hk: [installer] -> root_size + home_size przekraczaja 100%% (%s + %s)",
+			in.RootSize, in.HomeSize)
+	}
+	if in.RootSize.Unit == "%" && in.RootSize.Value == 100 {
+		return fmt.Errorf("config.hk: [installer] -> root_size = 100%% nie zostawia miejsca na /home")
+	}
+	return nil
+}
+
+// loadSwapPartition wczytuje swap_size -- tylko rozmiar bezwzgledny
+// (MiB/GiB), jak var_log_size.
+func loadSwapPartition(sec *hk.OrderedMap, in *InstallerConfig) error {
+	s, ok := optString(sec, "swap_size")
+	if !ok || s == "" {
+		return nil
+	}
+	size, err := parsePartitionSize(s)
+	if err != nil {
+		return fmt.Errorf("config.hk: [installer] -> swap_size: %w", err)
+	}
+	if size.Unit == "%" {
+		return fmt.Errorf("config.hk: [installer] -> swap_size: oczekiwano rozmiaru w MiB/GiB, otrzymano %q", s)
+	}
+	in.SwapSize = size
+	return nil
+}
+
+// loadVarLogPartition wczytuje separate_var_log / var_log_size. Rozmiar
+// tylko bezwzgledny (MiB/GiB) -- procent zabieralby miejsce root/home.
+// Osobnego calego /var nie oferujemy: /var/lib/dpkg musi zostac na tym
+// samym systemie plikow co /usr.
+func loadVarLogPartition(sec *hk.OrderedMap, in *InstallerConfig) error {
+	if v, ok := optBool(sec, "separate_var_log"); ok {
+		in.SeparateVarLog = v
+	}
+	s, ok := optString(sec, "var_log_size")
+	if !ok || s == "" {
+		if in.SeparateVarLog {
+			in.VarLogSize = PartitionSize{Value: 4, Unit: "GiB"}
+		}
+		return nil
+	}
+	if !in.SeparateVarLog {
+		return fmt.Errorf("config.hk: [installer] -> var_log_size wymaga separate_var_log = true")
+	}
+	size, err := parsePartitionSize(s)
+	if err != nil {
+		return fmt.Errorf("config.hk: [installer] -> var_log_size: %w", err)
+	}
+	if size.Unit == "%" {
+		return fmt.Errorf("config.hk: [installer] -> var_log_size: oczekiwano rozmiaru w MiB/GiB, otrzymano %q", s)
+	}
+	in.VarLogSize = size
+	return nil
+}
+
+// loadFilesystemOptions wczytuje root_filesystem / home_filesystem oraz
+// <fs>_mount_options dla kazdego z installerFilesystems.
+func loadFilesystemOptions(sec *hk.OrderedMap, in *InstallerConfig) error {
+	for key, dst := range map[string]*string{
+		"root_filesystem": &in.RootFilesystem,
+		"home_filesystem": &in.HomeFilesystem,
+	} {
+		fs, ok := optString(sec, key)
+		if !ok || fs == "" {
+			continue
+		}
+		if !containsString(installerFilesystems, fs) {
+			return fmt.Errorf("config.hk: [installer] -> %s: nieznany system plikow %q (dozwolone: %v)",
+				key, fs, installerFilesystems)
+		}
+		*dst = fs
+	}
+
+	for _, fs := range installerFilesystems {
+		key := fs + "_mount_options"
+		val, ok := sec.Get(key)
+		if !ok {
+			continue
+		}
+		opts, err := valueAsStringList(val)
+		if err != nil {
+			return fmt.Errorf("config.hk: [installer] -> %s: %w", key, err)
+		}
+		for _, o := range opts {
+			if !mountOptionPattern.MatchString(o) {
+				return fmt.Errorf("config.hk: [installer] -> %s: niepoprawna opcja montowania %q", key, o)
+			}
+		}
+		if len(opts) == 0 {
+			continue
+		}
+		if in.MountOptions == nil {
+			in.MountOptions = map[string][]string{}
+		}
+		in.MountOptions[fs] = opts
+	}
+	return nil
+}
+
+// UsesPartitionLayout zwraca true gdy instalator dostaje wlasny
+// partitionLayout (osobny /home, /var/log lub swap albo wymuszony system
+// plikow root).
+func (in InstallerConfig) UsesPartitionLayout() bool {
+	return in.SeparateHome || in.SeparateVarLog || in.SwapSize.Value > 0 || in.RootFilesystem != ""
+}
+
+// LayoutFilesystem zwraca fs albo "unknown" (= typ wybrany w instalatorze).
+func LayoutFilesystem(fs string) string {
+	if fs == "" {
+		return "unknown"
+	}
+	return fs
+}
+
+func containsString(list []string, s string) bool {
+	for _, item := range list {
+		if item == s {
+			return true
+		}
+	}
+	return false
+}
+
+// RootMinMiB to minimalne miejsce na / liczone w MinStorageGB, gdy
+// rozmiar root jest procentowy (reszta dysku po partycjach o stalym
+// rozmiarze) -- ponizej system z pulpitem sie nie zmiesci.
+const RootMinMiB = 8 * 1024
+
+// storagePart to jeden skladnik sumy MinStorageGB.
+type storagePart struct {
+	name string
+	mib  int
+}
+
+// minStorageParts zwraca partycje ukladu automatycznego o stalym
+// rozmiarze (ESP, root, /home, /var/log, swap), a przy procentowym root --
+// RootMinMiB. nil, gdy uklad jest w calosci procentowy.
+func (in InstallerConfig) minStorageParts() []storagePart {
+	var parts []storagePart
+	add := func(name string, s PartitionSize) {
+		switch s.Unit {
+		case "MiB":
+			parts = append(parts, storagePart{name, s.Value})
+		case "GiB":
+			parts = append(parts, storagePart{name, s.Value * 1024})
+		}
+	}
+	if in.SeparateHome {
+		add("/", in.RootSize)
+		add("/home", in.HomeSize)
+	}
+	if in.SeparateVarLog {
+		add("/var/log", in.VarLogSize)
+	}
+	add("swap", in.SwapSize)
+	if len(parts) == 0 {
+		return nil
+	}
+	if !in.SeparateHome || in.RootSize.Unit == "%" {
+		parts = append(parts, storagePart{"/ (minimum)", RootMinMiB})
+	}
+	if in.ESPSizeMiB != 0 {
+		parts = append([]storagePart{{"ESP", in.ESPSizeMiB}}, parts...)
+	}
+	return parts
+}
+
+// MinStorageGB zwraca minimalny rozmiar dysku (GB, w gore) wynikajacy z
+// minStorageParts -- 0 gdy uklad jest procentowy.
+func (in InstallerConfig) MinStorageGB() int {
+	mib := 0
+	for _, p := range in.minStorageParts() {
+		mib += p.mib
+	}
+	return (mib + 1023) / 1024
+}
+
+// MinStorageSummary zwraca rachunek MinStorageGB do komunikatow, np.
+// "ESP 512 MiB + swap 4096 MiB + / (minimum) 8192 MiB = 12800 MiB (13 GB)".
+// Puste, gdy uklad jest procentowy.
+func (in InstallerConfig) MinStorageSummary() string {
+	parts := in.minStorageParts()
+	if len(parts) == 0 {
+		return ""
+	}
+	terms := make([]string, len(parts))
+	mib := 0
+	for i, p := range parts {
+		terms[i] = fmt.Sprintf("%s %d MiB", p.name, p.mib)
+		mib += p.mib
+	}
+	return fmt.Sprintf("%s = %d MiB (%d GB)", strings.Join(terms, " + "), mib, in.MinStorageGB())
+}
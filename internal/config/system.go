@@ -0,0 +1,466 @@
+package config
+
+import (
+	"fmt"
+	"net"
+	"regexp"
+	"strconv"
+	"strings"
+
+	"github.com/HackerOS-Linux-System/hackeros-builder/internal/hk"
+)
+
+// SystemConfig to zawartosc sekcji [system] w config/config.hk -- ustawienia
+// wpisywane bezposrednio do obrazu (rootfs) podczas "build cloud", wiec
+// obowiazuja zarowno na nosniku live, jak i w systemie po instalacji
+// (Calamares kopiuje rootfs 1:1 z filesystem.squashfs).
+// Wszystkie pola opcjonalne -- brak sekcji nie jest bledem.
+type SystemConfig struct {
+	// ConsoleFont to font konsoli tekstowej z /usr/share/consolefonts
+	// (nazwa pliku bez ".psf.gz", np. "Lat15-Terminus32x16"), wpisywany
+	// jako FONT= do /etc/default/console-setup. ConsoleFontAuto
+	// (domyslnie) -- HiDPIConsoleFont przy starcie, tylko gdy framebuffer
+	// jest HiDPI; ConsoleFontNone -- font Debiana, obraz bez zmian.
+	ConsoleFont string
+
+	// PasswordMaxDays / PasswordWarnDays to polityka wygasania hasel
+	// (PASS_MAX_DAYS / PASS_WARN_AGE w /etc/login.defs). useradd wpisuje te
+	// wartosci do /etc/shadow kazdego NOWEGO konta -- wlacznie z kontem
+	// tworzonym przez Calamares. PasswordMaxDays 0 -- bez zmian (domyslne
+	// Debiana: 99999). PasswordWarnDays 0 to poprawna wartosc (bez
+	// ostrzezenia), wiec o jej uzyciu decyduje PasswordWarnDaysSet.
+	PasswordMaxDays     int
+	PasswordWarnDays    int
+	PasswordWarnDaysSet bool
+
+	// LoginDefs to preset /etc/login.defs (klucz z LoginDefsPresets):
+	// LoginDefsStandard albo LoginDefsHardened (umask 077, katalogi domowe
+	// 0700, krotsze wygasanie hasel). PasswordMaxDays/PasswordWarnDays
+	// nadpisuja wartosci presetu. Puste -- login.defs bez zmian.
+	LoginDefs string
+
+	// Umask to domyslna umask systemu (UMASK w /etc/login.defs, stosowana
+	// przez pam_umask przy logowaniu), np. "027" -- nadpisuje preset.
+	// Puste -- umask z presetu albo bez zmian.
+	Umask string
+
+	// Editor to domyslny edytor systemu (alternatywa "editor" -- uzywana
+	// przez sensible-editor, visudo, crontab -e). Puste -- bez zmian
+	// (w Debianie nano). Patrz Editors.
+	Editor string
+
+	// Microcode instaluje intel-microcode i amd64-microcode (aktualizacje
+	// mikrokodu CPU ladowane z initramfs; instalator zostawia pakiet
+	// pasujacy do procesora). Domyslnie true, gdy wlaczony jest komponent
+	// z firmware (Config.FirmwareComponent).
+	Microcode bool
+
+	// Nvidia to sterownik kart NVIDIA: NvidiaNonfree (nvidia-driver z
+	// zamknietymi modulami jadra), NvidiaOpen (nvidia-driver z otwartymi
+	// modulami -- tylko GPU od Turinga) albo NvidiaNouveau. Puste jak
+	// NvidiaNouveau: obraz bez sterownika NVIDIA. Instalator usuwa
+	// sterownik, gdy w komputerze nie ma GPU NVIDIA.
+	Nvidia string
+
+	// SecurityUpdates instaluje unattended-upgrades (z apt-listchanges) i
+	// wlacza codzienne, automatyczne instalowanie poprawek z archiwum
+	// bezpieczenstwa Debiana. Wymaga wydania z suite "-security" (nie sid)
+	// i archiwum bez [release] -> snapshot.
+	SecurityUpdates bool
+
+	// Generalize czysci na koniec buildu stan specyficzny dla maszyny
+	// (machine-id, klucze hosta SSH, dzierzawy DHCP, logi -- patrz
+	// rootfs.generalizeImage), zeby obraz mozna bylo klonowac na wiele
+	// komputerow.
+	Generalize bool
+
+	// BashCompletion instaluje bash-completion (uzupelnianie argumentow
+	// komend w powloce interaktywnej).
+	BashCompletion bool
+
+	// InitramfsCompress to kompresja initramfs (COMPRESS= w
+	// /etc/initramfs-tools/initramfs.conf) -- klucz z InitramfsCompressors.
+	// Puste -- bez zmian (w Debianie od bookworm zstd).
+	InitramfsCompress string
+
+	// TimeSync to usluga synchronizacji czasu: TimeSyncTimesyncd
+	// (domyslnie, gdy puste), TimeSyncChrony albo TimeSyncNone.
+	// NTPServers -- wlasne serwery NTP; puste -- serwery Debiana.
+	TimeSync   string
+	NTPServers []string
+
+	// DNS to sposob konfiguracji DNS: DNSResolved (systemd-resolved,
+	// /etc/resolv.conf -> stub) albo DNSStatic (staly /etc/resolv.conf,
+	// NetworkManager go nie nadpisuje). Puste -- bez zmian: resolv.conf
+	// zarzadza stos sieciowy obrazu (NetworkManager/networkd/dhclient).
+	// DNSServers -- adresy IP serwerow; dla DNSResolved puste oznacza
+	// serwery z DHCP.
+	DNS        string
+	DNSServers []string
+
+	// OSName / OSVersion to NAME i VERSION w /etc/os-release obrazu (patrz
+	// rootfs.osRelease). Puste -- DefaultOSName / [project] -> tag.
+	OSName    string
+	OSVersion string
+
+	// ColorPrompt wlacza kolorowy znak zachety w /etc/skel/.bashrc
+	// (force_color_prompt=yes) -- dotyczy kont tworzonych po buildzie.
+	ColorPrompt bool
+}
+
+// DefaultOSName to NAME w /etc/os-release, gdy [system] -> os_name puste.
+const DefaultOSName = "HackerOS"
+
+// Wartosci specjalne [system] -> console_font.
+const (
+	ConsoleFontAuto = "auto"
+	ConsoleFontNone = "none"
+)
+
+// HiDPIConsoleFont to font konsoli dla console_font => auto na ekranach
+// HiDPI (pakiet console-setup-linux).
+const HiDPIConsoleFont = "Lat15-Terminus32x16"
+
+// osNamePattern / osVersionPattern: wartosci trafiaja w cudzyslowy
+// os-release (skladnia powloki) -- bez ", \, $ i `.
+var (
+	osNamePattern    = regexp.MustCompile(`^[A-Za-z0-9 ._+-]{1,64}$`)
+	osVersionPattern = regexp.MustCompile(`^[A-Za-z0-9._+~-]{1,64}$`)
+)
+
+// Presety /etc/login.defs ([system] -> login_defs).
+const (
+	LoginDefsStandard = "standard"
+	LoginDefsHardened = "hardened"
+)
+
+// LoginDef to jeden klucz /etc/login.defs.
+type LoginDef struct {
+	Key, Value string
+}
+
+// LoginDefsPresets to klucze login.defs ustawiane przez preset, w
+// kolejnosci zapisu. standard to wartosci domyslne Debiana (HOME_MODE bez
+// zmian -- useradd wylicza go z UMASK).
+var LoginDefsPresets = map[string][]LoginDef{
+	LoginDefsStandard: {
+		{"UMASK", "022"},
+		{"PASS_MAX_DAYS", "99999"},
+		{"PASS_MIN_DAYS", "0"},
+		{"PASS_WARN_AGE", "7"},
+		{"UID_MIN", "1000"},
+		{"UID_MAX", "60000"},
+		{"LOGIN_RETRIES", "5"},
+	},
+	LoginDefsHardened: {
+		{"UMASK", "077"},
+		{"HOME_MODE", "0700"},
+		{"PASS_MAX_DAYS", "365"},
+		{"PASS_MIN_DAYS", "1"},
+		{"PASS_WARN_AGE", "14"},
+		{"UID_MIN", "1000"},
+		{"UID_MAX", "60000"},
+		{"LOGIN_RETRIES", "3"},
+	},
+}
+
+// LoginDefsValue zwraca wartosc klucza presetu ("" gdy preset go nie
+// ustawia).
+func LoginDefsValue(preset, key string) string {
+	for _, d := range LoginDefsPresets[preset] {
+		if d.Key == key {
+			return d.Value
+		}
+	}
+	return ""
+}
+
+// umaskPattern: umask osemkowa; wlasciciel musi miec pelne prawa (pierwsza
+// cyfra 0) -- inaczej nowe konto nie moze pisac we wlasnym katalogu.
+var umaskPattern = regexp.MustCompile(`^0?0[0-7]{2}$`)
+
+// Uslugi synchronizacji czasu ([system] -> time_sync).
+const (
+	TimeSyncTimesyncd = "timesyncd"
+	TimeSyncChrony    = "chrony"
+	TimeSyncNone      = "none"
+)
+
+// Tryby konfiguracji DNS ([system] -> dns).
+const (
+	DNSResolved = "resolved"
+	DNSStatic   = "static"
+)
+
+// Sterowniki NVIDIA ([system] -> nvidia).
+const (
+	NvidiaNouveau = "nouveau"
+	NvidiaNonfree = "nonfree"
+	NvidiaOpen    = "open"
+)
+
+// NvidiaDrivers to pakiety instalowane dla [system] -> nvidia: moduly
+// jadra (DKMS, stad naglowki jadra), sterownik i firmware GSP
+// (firmware-misc-nonfree). nvidia-driver jest w non-free, firmware w
+// Config.FirmwareComponent.
+var NvidiaDrivers = map[string][]string{
+	NvidiaNonfree: {"nvidia-kernel-dkms", "nvidia-driver", "firmware-misc-nonfree", "linux-headers-amd64"},
+	NvidiaOpen:    {"nvidia-open-kernel-dkms", "nvidia-driver", "firmware-misc-nonfree", "linux-headers-amd64"},
+}
+
+// ntpServerPattern: nazwa hosta albo adres IPv4/IPv6 serwera NTP -- trafia
+// do plikow konfiguracyjnych, wiec bez spacji i znakow specjalnych.
+var ntpServerPattern = regexp.MustCompile(`^[A-Za-z0-9.:-]{1,253}$`)
+
+// InitramfsCompressors to obslugiwane wartosci [system] ->
+// initramfs_compress: kompresor -> pakiet Debiana z jego binarka.
+var InitramfsCompressors = map[string]string{
+	"zstd": "zstd",
+	"lz4":  "lz4",
+	"gzip": "gzip",
+	"xz":   "xz-utils",
+}
+
+// Editors to obslugiwane wartosci [system] -> editor: nazwa -> pakiet
+// Debiana i sciezka rejestrowana jako alternatywa "editor".
+var Editors = map[string]struct{ Package, Path string }{
+	"nano":  {"nano", "/bin/nano"},
+	"vim":   {"vim", "/usr/bin/vim.basic"},
+	"emacs": {"emacs-nox", "/usr/bin/emacs"},
+}
+
+// loadSystemSection wczytuje opcjonalna sekcje [system].
+// Brak sekcji lub poszczegolnych kluczy -> wartosci domyslne, brak bledu.
+// firmware: czy [release] -> components zawiera komponent z mikrokodem,
+// nonFree: czy zawiera non-free.
+func loadSystemSection(parsed *hk.HkConfig, firmware, nonFree bool) (SystemConfig, error) {
+	s := SystemConfig{Microcode: firmware, ConsoleFont: ConsoleFontAuto}
+	sec, err := parsed.Section("system")
+	if err != nil {
+		return s, nil
+	}
+
+	if font, ok := optString(sec, "console_font"); ok {
+		font = strings.TrimSuffix(font, ".psf.gz")
+		if strings.ContainsAny(font, "/ ") {
+			return SystemConfig{}, fmt.Errorf(
+				"config.hk: [system] -> console_font: oczekiwano nazwy pliku z "+
+					"/usr/share/consolefonts (np. Lat15-Terminus32x16), otrzymano %q", font)
+		}
+		s.ConsoleFont = font
+	}
+
+	if n, ok, err := optInt(sec, "password_max_days", 1, 99999); err != nil {
+		return SystemConfig{}, err
+	} else if ok {
+		s.PasswordMaxDays = n
+	}
+	if n, ok, err := optInt(sec, "password_warn_days", 0, 99999); err != nil {
+		return SystemConfig{}, err
+	} else if ok {
+		s.PasswordWarnDays, s.PasswordWarnDaysSet = n, true
+	}
+
+	if p, ok := optString(sec, "login_defs"); ok && p != "" {
+		if _, known := LoginDefsPresets[p]; !known {
+			return SystemConfig{}, fmt.Errorf(
+				"config.hk: [system] -> login_defs %q: dozwolone standard, hardened", p)
+		}
+		s.LoginDefs = p
+	}
+	if u, ok := optUmask(sec); ok && u != "" {
+		if !umaskPattern.MatchString(u) {
+			return SystemConfig{}, fmt.Errorf(
+				"config.hk: [system] -> umask %q: oczekiwano umask osemkowej z pelnymi prawami "+
+					"wlasciciela, np. 022, 027, 077", u)
+		}
+		s.Umask = u[len(u)-3:]
+	}
+	// Polityka hasel i preset skladaja sie w jedno login.defs -- okres
+	// ostrzegania musi byc krotszy niz wynikowa waznosc hasla.
+	maxDays, warnDays := s.PasswordMaxDays, s.PasswordWarnDays
+	if maxDays == 0 {
+		maxDays, _ = strconv.Atoi(LoginDefsValue(s.LoginDefs, "PASS_MAX_DAYS"))
+	}
+	if !s.PasswordWarnDaysSet {
+		warnDays, _ = strconv.Atoi(LoginDefsValue(s.LoginDefs, "PASS_WARN_AGE"))
+	}
+	if maxDays != 0 && warnDays >= maxDays {
+		return SystemConfig{}, fmt.Errorf(
+			"config.hk: [system] -> password_warn_days (%d) musi byc mniejsze niz waznosc hasla (%d dni, "+
+				"password_max_days albo login_defs)", warnDays, maxDays)
+	}
+
+	if ed, ok := optString(sec, "editor"); ok && ed != "" {
+		if _, known := Editors[ed]; !known {
+			return SystemConfig{}, fmt.Errorf(
+				"config.hk: [system] -> editor %q: dozwolone nano, vim, emacs", ed)
+		}
+		s.Editor = ed
+	}
+	if c, ok := optString(sec, "initramfs_compress"); ok && c != "" {
+		if _, known := InitramfsCompressors[c]; !known {
+			return SystemConfig{}, fmt.Errorf(
+				"config.hk: [system] -> initramfs_compress %q: dozwolone zstd, lz4, gzip, xz", c)
+		}
+		s.InitramfsCompress = c
+	}
+	if ts, ok := optString(sec, "time_sync"); ok && ts != "" {
+		if ts != TimeSyncTimesyncd && ts != TimeSyncChrony && ts != TimeSyncNone {
+			return SystemConfig{}, fmt.Errorf(
+				"config.hk: [system] -> time_sync %q: dozwolone timesyncd, chrony, none", ts)
+		}
+		s.TimeSync = ts
+	}
+	if val, ok := sec.Get("ntp_servers"); ok {
+		servers, err := valueAsStringList(val)
+		if err != nil {
+			return SystemConfig{}, fmt.Errorf("config.hk: [system] -> ntp_servers: %w", err)
+		}
+		for _, srv := range servers {
+			if !ntpServerPattern.MatchString(srv) {
+				return SystemConfig{}, fmt.Errorf("config.hk: [system] -> ntp_servers: niepoprawny serwer %q", srv)
+			}
+		}
+		if len(servers) > 0 && s.TimeSync == TimeSyncNone {
+			return SystemConfig{}, fmt.Errorf("config.hk: [system] -> ntp_servers wymaga time_sync innego niz none")
+		}
+		s.NTPServers = servers
+	}
+
+	if dns, ok := optString(sec, "dns"); ok && dns != "" {
+		if dns != DNSResolved && dns != DNSStatic {
+			return SystemConfig{}, fmt.Errorf(
+				"config.hk: [system] -> dns %q: dozwolone resolved, static", dns)
+		}
+		s.DNS = dns
+	}
+	if val, ok := sec.Get("dns_servers"); ok {
+		servers, err := valueAsStringList(val)
+		if err != nil {
+			return SystemConfig{}, fmt.Errorf("config.hk: [system] -> dns_servers: %w", err)
+		}
+		for _, srv := range servers {
+			if net.ParseIP(srv) == nil {
+				return SystemConfig{}, fmt.Errorf("config.hk: [system] -> dns_servers: %q to nie adres IP", srv)
+			}
+		}
+		if len(servers) > 0 && s.DNS == "" {
+			return SystemConfig{}, fmt.Errorf("config.hk: [system] -> dns_servers wymaga dns => resolved albo static")
+		}
+		s.DNSServers = servers
+	}
+	if s.DNS == DNSStatic && len(s.DNSServers) == 0 {
+		return SystemConfig{}, fmt.Errorf("config.hk: [system] -> dns => static wymaga dns_servers")
+	}
+
+	if name, ok := optString(sec, "os_name"); ok && name != "" {
+		if !osNamePattern.MatchString(name) {
+			return SystemConfig{}, fmt.Errorf(
+				"config.hk: [system] -> os_name %q: dozwolone litery, cyfry, spacja i . _ + - (1-64 znaki)", name)
+		}
+		s.OSName = name
+	}
+	if ver, ok := optString(sec, "os_version"); ok && ver != "" {
+		if !osVersionPattern.MatchString(ver) {
+			return SystemConfig{}, fmt.Errorf(
+				"config.hk: [system] -> os_version %q: dozwolone litery, cyfry i . _ + ~ - (1-64 znaki)", ver)
+		}
+		s.OSVersion = ver
+	}
+
+	if v, ok := optBool(sec, "microcode"); ok {
+		if v && !firmware {
+			return SystemConfig{}, fmt.Errorf(
+				"config.hk: [system] -> microcode wymaga komponentu z firmware " +
+					"(non-free-firmware, przed bookworm non-free) w [release] -> components")
+		}
+		s.Microcode = v
+	}
+
+	if nv, ok := optString(sec, "nvidia"); ok && nv != "" {
+		if nv != NvidiaNouveau && nv != NvidiaNonfree && nv != NvidiaOpen {
+			return SystemConfig{}, fmt.Errorf(
+				"config.hk: [system] -> nvidia %q: dozwolone nouveau, nonfree, open", nv)
+		}
+		if nv != NvidiaNouveau && (!nonFree || !firmware) {
+			return SystemConfig{}, fmt.Errorf(
+				"config.hk: [system] -> nvidia => %s wymaga komponentow non-free (nvidia-driver) "+
+					"i non-free-firmware (firmware GSP, przed bookworm non-free) w [release] -> components "+
+					"-- bez nich zostaje nvidia => nouveau", nv)
+		}
+		s.Nvidia = nv
+	}
+
+	if v, ok := optBool(sec, "security_updates"); ok {
+		s.SecurityUpdates = v
+	}
+	if v, ok := optBool(sec, "generalize"); ok {
+		s.Generalize = v
+	}
+	if v, ok := optBool(sec, "bash_completion"); ok {
+		s.BashCompletion = v
+	}
+	if v, ok := optBool(sec, "color_prompt"); ok {
+		s.ColorPrompt = v
+	}
+
+	return s, nil
+}
+
+// optString zwraca przycieta wartosc tekstowa klucza sekcji i true, albo
+// ("", false) gdy klucza nie ma lub nie da sie go skonwertowac na tekst.
+func optString(sec *hk.OrderedMap, key string) (string, bool) {
+	val, ok := sec.Get(key)
+	if !ok {
+		return "", false
+	}
+	s, err := val.AsString()
+	if err != nil {
+		return "", false
+	}
+	return strings.TrimSpace(s), true
+}
+
+// optUmask zwraca [system] -> umask jako tekst. Parser .hk czyta
+// niecytowane 027 albo 0027 jako liczbe 27 (zera wiodace znikaja) --
+// liczba calkowita jest dopelniana zerami do 3 cyfr, jak w powloce, gdzie
+// "umask 27" to tez 027.
+func optUmask(sec *hk.OrderedMap) (string, bool) {
+	val, ok := sec.Get("umask")
+	if !ok {
+		return "", false
+	}
+	if val.Kind == hk.KindNumber {
+		if n := int(val.Num); float64(n) == val.Num && n >= 0 {
+			return fmt.Sprintf("%03d", n), true
+		}
+	}
+	return optString(sec, "umask")
+}
+
+// optBool zwraca wartosc logiczna klucza ("true"/"yes"/"1"/"on" -- patrz
+// isTruthy) i true, albo (false, false) gdy klucza nie ma.
+func optBool(sec *hk.OrderedMap, key string) (bool, bool) {
+	s, ok := optString(sec, key)
+	if !ok {
+		return false, false
+	}
+	return isTruthy(s), true
+}
+
+// optInt zwraca wartosc calkowita klucza z zakresu [min, max]. Brak klucza
+// -> (0, false, nil); wartosc spoza zakresu lub nie-liczba -> blad.
+func optInt(sec *hk.OrderedMap, key string, min, max int) (int, bool, error) {
+	s, ok := optString(sec, key)
+	if !ok {
+		return 0, false, nil
+	}
+	n, err := strconv.Atoi(s)
+	if err != nil || n < min || n > max {
+		return 0, false, fmt.Errorf(
+			"config.hk: klucz '%s' musi byc liczba calkowita z zakresu %d-%d, otrzymano %q",
+			key, min, max, s)
+	}
+	return n, true, nil
+}
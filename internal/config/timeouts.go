@@ -0,0 +1,70 @@
+package config
+
+import (
+	"time"
+
+	"github.com/HackerOS-Linux-System/hackeros-builder/internal/hk"
+)
+
+// TimeoutsConfig to zawartosc sekcji [timeouts] w config/config.hk --
+// limity czasu poszczegolnych faz buildu. Komenda, ktora je przekroczy
+// (zwykle zawieszona na martwym mirrorze), jest zabijana zamiast wisiec
+// w nieskonczonosc. 0 -- bez limitu.
+type TimeoutsConfig struct {
+	// Debootstrap to limit calego debootstrap (pobranie i rozpakowanie
+	// systemu bazowego -- najdluzsza faza).
+	Debootstrap time.Duration
+
+	// AptUpdate to limit jednej proby "apt-get update"; przekroczenie
+	// konczy sie kolejna proba jak kazdy inny blad.
+	AptUpdate time.Duration
+
+	// AptInstall to limit jednego "apt-get install"; po przekroczeniu
+	// instalacja jest ponawiana raz.
+	AptInstall time.Duration
+
+	// Hooks to limit kazdego hooka z config/hooks/live osobno.
+	Hooks time.Duration
+}
+
+// DefaultTimeouts zwraca limity stosowane bez sekcji [timeouts]: krotko
+// dla samego pobierania list pakietow, dluzej dla instalacji i najdluzej
+// dla debootstrap. Pobrania HTTP buildera maja wlasne limity
+// (httpclient).
+func DefaultTimeouts() TimeoutsConfig {
+	return TimeoutsConfig{
+		Debootstrap: 60 * time.Minute,
+		AptUpdate:   10 * time.Minute,
+		AptInstall:  45 * time.Minute,
+		Hooks:       30 * time.Minute,
+	}
+}
+
+// loadTimeoutsSection wczytuje opcjonalna sekcje [timeouts] (wartosci w
+// minutach). Brak sekcji lub klucza -> DefaultTimeouts, brak bledu.
+func loadTimeoutsSection(parsed *hk.HkConfig) (TimeoutsConfig, error) {
+	t := DefaultTimeouts()
+	sec, err := parsed.Section("timeouts")
+	if err != nil {
+		return t, nil
+	}
+
+	for _, k := range []struct {
+		key string
+		dst *time.Duration
+	}{
+		{"debootstrap", &t.Debootstrap},
+		{"apt_update", &t.AptUpdate},
+		{"apt_install", &t.AptInstall},
+		{"hooks", &t.Hooks},
+	} {
+		n, ok, err := optInt(sec, k.key, 0, 24*60)
+		if err != nil {
+			return TimeoutsConfig{}, err
+		}
+		if ok {
+			*k.dst = time.Duration(n) * time.Minute
+		}
+	}
+	return t, nil
+}
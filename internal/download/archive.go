@@ -0,0 +1,31 @@
+package download
+
+import (
+	"fmt"
+	"net/http"
+
+	"github.com/HackerOS-Linux-System/hackeros-builder/internal/httpclient"
+)
+
+// CheckURL sprawdza zadaniem HEAD, ze url istnieje (status 200) -- bez
+// pobierania tresci. Uzywane przed debootstrap, zeby literowka w
+// konfiguracji archiwum nie wychodzila dopiero po kilku minutach buildu.
+func CheckURL(url string) error {
+	req, err := http.NewRequest(http.MethodHead, url, nil)
+	if err != nil {
+		return fmt.Errorf("budowanie zadania HTTP: %w", err)
+	}
+	req.Header.Set("User-Agent", userAgent)
+
+	resp, err := httpClient.Do(req)
+	if err != nil {
+		return fmt.Errorf("zadanie HTTP nie powiodlo sie (timeout %s): %w",
+			httpclient.DefaultTimeout, err)
+	}
+	resp.Body.Close()
+
+	if resp.StatusCode != http.StatusOK {
+		return fmt.Errorf("status HTTP %d (URL: %s)", resp.StatusCode, url)
+	}
+	return nil
+}
@@ -30,6 +30,9 @@ const (
 	// releases zawiedzie (np. brak sieci, zmiana layoutu HTML przez GitHub).
 	// Aktualizuj przy kazdym nowym wydaniu deb-ostree.
 	fallbackVersion = "v0.0.1"
+
+	// userAgent to naglowek User-Agent zadan HTTP buildera.
+	userAgent = "hackeros-builder/0.3 (+https://github.com/HackerOS-Linux-System/hackeros-builder)"
 )
 
 // reReleaseTag to wyrazenie regularne szukajace sciezki do tagu wydania
@@ -43,6 +46,28 @@ var reReleaseTag = regexp.MustCompile(
 
 var httpClient = httpclient.New()
 
+// debOstreeCompat to macierz zgodnosci: wersja deb-ostree -> wydania
+// Debiana, z ktorymi zostala sprawdzona. Binarka jest pobierana z tagu
+// wydania niezaleznie od [release] -> name, wiec stara wersja na nowszym
+// wydaniu (np. v0.0.1 na forky) moze sie nie dogadac z jego ostree/apt.
+// Dopisuj nowe wersje razem z fallbackVersion.
+var debOstreeCompat = map[string][]string{
+	"v0.0.1": {"bookworm", "trixie"},
+}
+
+// DebOstreeCompatibility zwraca wydania Debiana zgodne z wersja deb-ostree
+// i czy release jest wsrod nich. Wersja spoza debOstreeCompat zwraca
+// (nil, false) -- zgodnosc nieznana.
+func DebOstreeCompatibility(version, release string) ([]string, bool) {
+	releases := debOstreeCompat[version]
+	for _, r := range releases {
+		if r == release {
+			return releases, true
+		}
+	}
+	return releases, false
+}
+
 // LatestDebOstreeVersion wykrywa najnowszy tag wydania deb-ostree przez
 // scraping strony HTML github.com/HackerOS-Linux-System/deb-ostree/releases.
 //
@@ -69,7 +94,7 @@ func scrapLatestTag() (string, error) {
 		return "", fmt.Errorf("budowanie zadania HTTP: %w", err)
 	}
 	// User-Agent zeby GitHub nie zablokowal jako bota bez agenta
-	req.Header.Set("User-Agent", "hackeros-builder/0.3 (+https://github.com/HackerOS-Linux-System/hackeros-builder)")
+	req.Header.Set("User-Agent", userAgent)
 
 	resp, err := httpClient.Do(req)
 	if err != nil {
@@ -100,7 +125,8 @@ func scrapLatestTag() (string, error) {
 
 // DownloadDebOstree sciaga binarke deb-ostree dla danej wersji (np. "v0.0.1")
 // z GitHub Releases, weryfikuje sume kontrolna SHA256 (jesli dostepna)
-// i zapisuje ja w destPath z uprawnieniami 0755.
+// i zapisuje ja w destPath z uprawnieniami 0755. destPath nigdy nie
+// zawiera czesciowego pliku -- patrz writeFileAtomic.
 func DownloadDebOstree(version, destPath string) error {
 	binURL := releaseAssetURL(version, releaseAssetName)
 	util.Infof("Pobieranie deb-ostree %s z %s ...", version, binURL)
@@ -118,18 +144,46 @@ func DownloadDebOstree(version, destPath string) error {
 		return fmt.Errorf("tworzenie katalogu docelowego: %w", err)
 	}
 
-	if err := os.WriteFile(destPath, data, 0o755); err != nil {
+	if err := writeFileAtomic(destPath, data, 0o755); err != nil {
 		return fmt.Errorf("zapis pobranego pliku do %s: %w", destPath, err)
 	}
 
-	if err := os.Chmod(destPath, 0o755); err != nil {
-		return fmt.Errorf("chmod a+x na %s: %w", destPath, err)
-	}
-
 	util.Infof("deb-ostree %s pobrano i zapisano do %s", version, destPath)
 	return nil
 }
 
+// writeFileAtomic zapisuje data do "<path>.partial" i dopiero po udanym
+// zapisie (fsync + chmod) zmienia nazwe na path. Przerwanie w trakcie
+// zostawia co najwyzej .partial (nadpisywany przy nastepnej probie),
+// nigdy obciety plik pod docelowa nazwa.
+func writeFileAtomic(path string, data []byte, mode os.FileMode) error {
+	partial := path + ".partial"
+	f, err := os.OpenFile(partial, os.O_WRONLY|os.O_CREATE|os.O_TRUNC, mode)
+	if err != nil {
+		return err
+	}
+	if _, err := f.Write(data); err != nil {
+		f.Close()
+		os.Remove(partial)
+		return err
+	}
+	if err := f.Sync(); err != nil {
+		f.Close()
+		os.Remove(partial)
+		return err
+	}
+	if err := f.Close(); err != nil {
+		os.Remove(partial)
+		return err
+	}
+	// Chmod osobno: mode z OpenFile podlega umask.
+	if err := os.Chmod(partial, mode); err != nil {
+		os.Remove(partial)
+		return err
+	}
+	return os.Rename(partial, path)
+}
+
 func releaseAssetURL(version, assetName string) string {
 	return fmt.Sprintf(
 		"https://github.com/HackerOS-Linux-System/deb-ostree/releases/download/%s/%s",
@@ -141,7 +195,7 @@ func fetchBytes(url string) ([]byte, error) {
 	if err != nil {
 		return nil, fmt.Errorf("budowanie zadania HTTP: %w", err)
 	}
-	req.Header.Set("User-Agent", "hackeros-builder/0.3 (+https://github.com/HackerOS-Linux-System/hackeros-builder)")
+	req.Header.Set("User-Agent", userAgent)
 
 	resp, err := httpClient.Do(req)
 	if err != nil {
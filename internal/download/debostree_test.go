@@ -1,6 +1,10 @@
 package download
 
-import "testing"
+import (
+	"os"
+	"path/filepath"
+	"testing"
+)
 
 func TestParseChecksumsFile_FindsMatchingEntry(t *testing.T) {
 	content := "abc123  deb-ostree\ndef456  checksums.txt\n"
@@ -50,3 +54,40 @@ func TestParseChecksumsFile_IgnoresMalformedLines(t *testing.T) {
 		t.Fatalf("oczekiwano hash=abc123, otrzymano %q", hash)
 	}
 }
+
+func TestWriteFileAtomic_ReplacesStalePartial(t *testing.T) {
+	dir := t.TempDir()
+	dest := filepath.Join(dir, "deb-ostree")
+	// Pozostalosc po przerwanym pobieraniu.
+	if err := os.WriteFile(dest+".partial", []byte("obciety"), 0o644); err != nil {
+		t.Fatal(err)
+	}
+
+	if err := writeFileAtomic(dest, []byte("pelna binarka"), 0o755); err != nil {
+		t.Fatalf("writeFileAtomic zwrocilo blad: %v", err)
+	}
+
+	data, err := os.ReadFile(dest)
+	if err != nil || string(data) != "pelna binarka" {
+		t.Fatalf("oczekiwano pelnej tresci w %s, otrzymano %q (%v)", dest, data, err)
+	}
+	if info, _ := os.Stat(dest); info.Mode().Perm() != 0o755 {
+		t.Errorf("oczekiwano uprawnien 0755, otrzymano %v", info.Mode().Perm())
+	}
+	if _, err := os.Stat(dest + ".partial"); !os.IsNotExist(err) {
+		t.Error("plik .partial nie powinien zostac po udanym zapisie")
+	}
+}
+
+func TestDebOstreeCompatibility(t *testing.T) {
+	if _, ok := DebOstreeCompatibility("v0.0.1", "bookworm"); !ok {
+		t.Error("v0.0.1 powinno byc zgodne z bookworm")
+	}
+	releases, ok := DebOstreeCompatibility("v0.0.1", "sid")
+	if ok || len(releases) == 0 {
+		t.Errorf("v0.0.1 na sid: oczekiwano niezgodnosci z lista wydan, otrzymano %v, %v", releases, ok)
+	}
+	if releases, ok := DebOstreeCompatibility("v9.9.9", "trixie"); ok || releases != nil {
+		t.Errorf("nieznana wersja: oczekiwano (nil, false), otrzymano %v, %v", releases, ok)
+	}
+}
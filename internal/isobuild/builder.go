@@ -1,11 +1,17 @@
 package isobuild
 
 import (
+	"crypto/sha256"
+	"encoding/hex"
 	"fmt"
 	"io"
+	"io/fs"
 	"os"
 	"path/filepath"
+	"strings"
 
+	"github.com/HackerOS-Linux-System/hackeros-builder/internal/config"
+	"github.com/HackerOS-Linux-System/hackeros-builder/internal/liveparse"
 	"github.com/HackerOS-Linux-System/hackeros-builder/internal/util"
 )
 
@@ -22,6 +28,19 @@ type BuildParams struct {
 	// kazde "build iso" produkuje gotowy do instalacji nosnik, bootujacy
 	// PROSTO w instalator (patrz installer.go), bez posredniego pulpitu live.
 	SkipInstaller bool
+
+	// Installer to sekcja [installer] z config.hk -- ustawienia Calamares.
+	// Ignorowane gdy SkipInstaller.
+	Installer config.InstallerConfig
+
+	// InstallHooks to config/hooks/install/*.hook.chroot projektu --
+	// wykonywane przez instalator w systemie docelowym. Ignorowane gdy
+	// SkipInstaller.
+	InstallHooks []liveparse.HookScript
+
+	// Timeouts to sekcja [timeouts] z config.hk -- limity apt-get przy
+	// instalacji Calamares.
+	Timeouts config.TimeoutsConfig
 }
 
 // excludeFromSquash to katalogi ktore NIE powinny trafic do squashfs
@@ -32,38 +51,46 @@ var excludeFromSquash = []string{"proc", "sys", "dev", "tmp", "run"}
 //  1. mksquashfs rootfs -> iso-tree/live/filesystem.squashfs
 //  2. kopiowanie jadra+initrd z rootfs/boot -> iso-tree/live/
 //  3. generowanie konfiguracji GRUB (BIOS+UEFI) w iso-tree/boot/grub/
-//  4. grub-mkrescue -> OutputISO, hybrid BIOS+UEFI (xorriso pod maska)
+//  4. sha256sum.txt z sumami plikow nosnika (weryfikacja przez live-boot)
+//  5. grub-mkrescue -> OutputISO, hybrid BIOS+UEFI (xorriso pod maska)
 func Build(p BuildParams) error {
 	isoTree := filepath.Join(p.WorkDir, "iso-tree")
 	if err := os.RemoveAll(isoTree); err != nil {
 		return fmt.Errorf("czyszczenie %s: %w", isoTree, err)
 	}
 
+	steps := util.NewSteps(6)
+
 	if !p.SkipInstaller {
-		util.Infof("Krok 1/5: instalator GUI (Calamares)...")
-		if err := InjectInstaller(p.RootfsDir, p.WorkDir); err != nil {
+		steps.Next("instalator GUI (Calamares)...")
+		if err := InjectInstaller(p.RootfsDir, p.WorkDir, p.Installer, p.InstallHooks, p.Timeouts); err != nil {
 			return fmt.Errorf("instalator GUI: %w", err)
 		}
 	} else {
-		util.Infof("Krok 1/5: instalator GUI pominiety (SkipInstaller)")
+		steps.Skip("instalator GUI (SkipInstaller)")
 	}
 
-	util.Infof("Krok 2/5: tworzenie squashfs z rootfs...")
+	steps.Next("tworzenie squashfs z rootfs...")
 	if err := buildSquashfs(p.RootfsDir, isoTree); err != nil {
 		return fmt.Errorf("squashfs: %w", err)
 	}
 
-	util.Infof("Krok 3/5: kopiowanie jadra i initrd...")
+	steps.Next("kopiowanie jadra i initrd...")
 	if err := copyKernelAndInitrd(p.RootfsDir, isoTree); err != nil {
 		return fmt.Errorf("kernel/initrd: %w", err)
 	}
 
-	util.Infof("Krok 4/5: generowanie konfiguracji GRUB (BIOS+UEFI)...")
+	steps.Next("generowanie konfiguracji GRUB (BIOS+UEFI)...")
 	if err := writeGrubConfig(isoTree, p.VolumeName); err != nil {
 		return fmt.Errorf("grub config: %w", err)
 	}
 
-	util.Infof("Krok 5/5: budowanie hybrydowego ISO (grub-mkrescue)...")
+	steps.Next("sumy kontrolne nosnika (sha256sum.txt)...")
+	if err := writeChecksums(isoTree); err != nil {
+		return fmt.Errorf("sumy kontrolne: %w", err)
+	}
+
+	steps.Next("budowanie hybrydowego ISO (grub-mkrescue)...")
 	if err := runGrubMkrescue(isoTree, p.OutputISO, p.VolumeName); err != nil {
 		return fmt.Errorf("grub-mkrescue: %w", err)
 	}
@@ -152,11 +179,69 @@ menuentry "%s (safe graphics)" {
     linux /live/vmlinuz boot=live quiet nomodeset
     initrd /live/initrd.img
 }
-`, volumeName, volumeName)
+
+menuentry "%s (sprawdz nosnik)" {
+    linux /live/vmlinuz boot=live verify-checksums
+    initrd /live/initrd.img
+}
+`, volumeName, volumeName, volumeName)
 
 	return os.WriteFile(filepath.Join(grubDir, "grub.cfg"), []byte(cfg), 0o644)
 }
 
+// checksumsFile to nazwa manifestu sum w korzeniu ISO -- live-boot z
+// parametrem "verify-checksums" sprawdza nim pliki nosnika przed startem
+// systemu (wykrywa uszkodzone nagranie na USB/DVD, zanim instalator
+// skopiuje uszkodzony squashfs na dysk).
+const checksumsFile = "sha256sum.txt"
+
+// writeChecksums zapisuje isoTree/sha256sum.txt w formacie sha256sum
+// ("<hex>  ./sciezka"), w porzadku leksykalnym WalkDir. Obejmuje wszystko,
+// co jest w drzewie PRZED grub-mkrescue (squashfs, jadro, initrd,
+// grub.cfg) -- pliki dokladane przez grub-mkrescue nie sa objete.
+func writeChecksums(isoTree string) error {
+	var b strings.Builder
+	err := filepath.WalkDir(isoTree, func(path string, d fs.DirEntry, err error) error {
+		if err != nil {
+			return err
+		}
+		if d.IsDir() {
+			return nil
+		}
+		rel, err := filepath.Rel(isoTree, path)
+		if err != nil {
+			return err
+		}
+		if rel == checksumsFile {
+			return nil
+		}
+		sum, err := fileSHA256(path)
+		if err != nil {
+			return fmt.Errorf("sha256 %s: %w", rel, err)
+		}
+		fmt.Fprintf(&b, "%s  ./%s\n", sum, filepath.ToSlash(rel))
+		return nil
+	})
+	if err != nil {
+		return err
+	}
+	return os.WriteFile(filepath.Join(isoTree, checksumsFile), []byte(b.String()), 0o644)
+}
+
+// fileSHA256 zwraca sume SHA256 pliku jako hex.
+func fileSHA256(path string) (string, error) {
+	f, err := os.Open(path)
+	if err != nil {
+		return "", err
+	}
+	defer f.Close()
+	h := sha256.New()
+	if _, err := io.Copy(h, f); err != nil {
+		return "", err
+	}
+	return hex.EncodeToString(h.Sum(nil)), nil
+}
+
 // runGrubMkrescue wywoluje "grub-mkrescue" do zbudowania hybrydowego ISO
 // (bootowalnego zarowno przez legacy BIOS jak i UEFI) -- grub-mkrescue
 // generuje wewnetrznie poprawna strukture El Torito + GPT/MBR hybrid przez
@@ -0,0 +1,39 @@
+package isobuild
+
+import (
+	"os"
+	"path/filepath"
+	"testing"
+)
+
+func TestWriteChecksums(t *testing.T) {
+	isoTree := t.TempDir()
+	for path, content := range map[string]string{
+		"live/vmlinuz":       "kernel",
+		"boot/grub/grub.cfg": "set timeout=5\n",
+		"sha256sum.txt":      "stary manifest",
+	} {
+		full := filepath.Join(isoTree, path)
+		if err := os.MkdirAll(filepath.Dir(full), 0o755); err != nil {
+			t.Fatal(err)
+		}
+		if err := os.WriteFile(full, []byte(content), 0o644); err != nil {
+			t.Fatal(err)
+		}
+	}
+
+	if err := writeChecksums(isoTree); err != nil {
+		t.Fatalf("writeChecksums zwrocilo blad: %v", err)
+	}
+
+	got, err := os.ReadFile(filepath.Join(isoTree, checksumsFile))
+	if err != nil {
+		t.Fatal(err)
+	}
+	// Manifest bez samego siebie, sciezki wzgledne "./", format sha256sum.
+	want := "08dd82f2276d1bf17d946235af46a697fc8382dcedfd7f590689d216a71a1211  ./boot/grub/grub.cfg\n" +
+		"6923dd1bc0460082c5d55a831908c24a282860b7f1cd6c2b79cf1bc8857c639c  ./live/vmlinuz\n"
+	if string(got) != want {
+		t.Errorf("oczekiwano:\n%s\notrzymano:\n%s", want, got)
+	}
+}
@@ -4,7 +4,11 @@ import (
 	"fmt"
 	"os"
 	"path/filepath"
+	"strings"
+	"time"
 
+	"github.com/HackerOS-Linux-System/hackeros-builder/internal/config"
+	"github.com/HackerOS-Linux-System/hackeros-builder/internal/liveparse"
 	"github.com/HackerOS-Linux-System/hackeros-builder/internal/sandbox"
 	"github.com/HackerOS-Linux-System/hackeros-builder/internal/toolchain"
 	"github.com/HackerOS-Linux-System/hackeros-builder/internal/util"
@@ -37,39 +41,383 @@ var installerPackages = []string{
 	"network-manager",
 	"parted",
 	"gdisk",
-	"dosfstools",
 	"os-prober",
+	"cryptsetup",
+	"cryptsetup-initramfs",
+}
+
+// filesystemTool to narzedzie mkfs dla systemu plikow oferowanego przez
+// instalator (availableFileSystemTypes w partition.conf + ESP).
+type filesystemTool struct {
+	fs   string
+	pkg  string
+	mkfs string
+}
+
+// installerFilesystemTools: kazdy system plikow do wyboru w Calamares musi
+// miec swoje mkfs na nosniku live -- bez niego partycjonowanie konczy sie
+// surowym "command not found" PO zapisaniu nowej tablicy partycji.
+var installerFilesystemTools = []filesystemTool{
+	{"ext4", "e2fsprogs", "mkfs.ext4"},
+	{"btrfs", "btrfs-progs", "mkfs.btrfs"},
+	{"xfs", "xfsprogs", "mkfs.xfs"},
+	{"fat32 (ESP)", "dosfstools", "mkfs.fat"},
+}
+
+// vmGuestTool to narzedzia goscia dla hypervisora zglaszanego przez
+// "systemd-detect-virt --vm" jako virt.
+type vmGuestTool struct {
+	virt string
+	pkgs []string
+}
+
+// vmGuestTools: pakiety z Debian main. VirtualBox ("oracle") pominiety --
+// virtualbox-guest-* nie ma w archiwum Debiana.
+var vmGuestTools = []vmGuestTool{
+	{"kvm", []string{"qemu-guest-agent", "spice-vdagent"}},
+	{"qemu", []string{"qemu-guest-agent", "spice-vdagent"}},
+	{"vmware", []string{"open-vm-tools"}},
+	{"microsoft", []string{"hyperv-daemons"}},
+}
+
+// vmGuestPackages zwraca pakiety wszystkich vmGuestTools bez powtorzen, w
+// kolejnosci wystapienia.
+func vmGuestPackages() []string {
+	var pkgs []string
+	seen := map[string]bool{}
+	for _, t := range vmGuestTools {
+		for _, p := range t.pkgs {
+			if !seen[p] {
+				seen[p] = true
+				pkgs = append(pkgs, p)
+			}
+		}
+	}
+	return pkgs
+}
+
+// vmGuestToolsCommand zwraca komende shellprocess wybierajaca narzedzia
+// goscia: pakiety pasujace do hypervisora zostaja (uslugi wlaczyl postinst
+// przy budowie nosnika), pozostale sa usuwane -- na sprzecie fizycznym
+// wszystkie. systemd-detect-virt wykrywa VM po CPUID, bez /sys w chroot.
+// Wynik (hypervisor i zostawione pakiety) trafia do logu instalatora.
+func vmGuestToolsCommand() string {
+	var arms strings.Builder
+	for _, t := range vmGuestTools {
+		fmt.Fprintf(&arms, `%s) keep="%s";; `, t.virt, strings.Join(t.pkgs, " "))
+	}
+	return `virt=$(systemd-detect-virt --vm 2>/dev/null); [ -n "$virt" ] || virt=none; ` +
+		`case "$virt" in ` + arms.String() + `*) keep="";; esac; ` +
+		`remove=""; for p in ` + strings.Join(vmGuestPackages(), " ") + `; do ` +
+		`case " $keep " in *" $p "*) ;; *) remove="$remove $p";; esac; done; ` +
+		`echo "wirtualizacja: $virt, narzedzia goscia: $keep"; ` +
+		`apt-get purge -y $remove`
+}
+
+// microcodeCommand zostawia w systemie mikrokod pasujacy do procesora
+// ([system] -> microcode instaluje oba pakiety): usuwa pakiet drugiego
+// producenta (trigger przebudowuje initramfs) i sprawdza, ze kazdy initrd
+// w /boot zawiera mikrokod wykrytego procesora -- brak to tylko
+// ostrzezenie w logu. Obraz bez pakietow mikrokodu: no-op. Nieznany
+// producent (np. Hygon): oba pakiety zostaja.
+const microcodeCommand = `vendor=$(sed -n 's/^vendor_id[[:space:]]*: //p' /proc/cpuinfo | head -n 1); ` +
+	`case "$vendor" in GenuineIntel) other=amd64-microcode;; AuthenticAMD) other=intel-microcode;; *) other="";; esac; ` +
+	`if dpkg -s intel-microcode >/dev/null 2>&1 || dpkg -s amd64-microcode >/dev/null 2>&1; then ` +
+	`if [ -n "$other" ] && dpkg -s "$other" >/dev/null 2>&1; then apt-get purge -y "$other" || exit 1; fi; ` +
+	`for i in /boot/initrd.img-*; do ` +
+	`if lsinitramfs "$i" | grep -q "kernel/x86/microcode/$vendor.bin"; then echo "mikrokod $vendor: $i"; ` +
+	`else echo "UWAGA: brak mikrokodu $vendor w $i" >&2; fi; done; ` +
+	`else echo "mikrokod CPU pominiety (brak pakietow w obrazie)"; fi`
+
+// nvidiaCommand zostawia sterownik NVIDIA ([system] -> nvidia, plik
+// /etc/modprobe.d/hackeros-nvidia.conf z rootfs/nvidia.go) tylko gdy
+// w komputerze jest GPU NVIDIA (PCI vendor 0x10de, klasa 0x03 --
+// kontroler grafiki). Bez niego usuwa sterownik, blokade nouveau i moduly
+// early KMS, po czym przebudowuje initramfs. Obraz bez sterownika: no-op.
+// Wykryte GPU i wybor trafiaja do logu instalatora.
+const nvidiaCommand = `conf=/etc/modprobe.d/hackeros-nvidia.conf; ` +
+	`if [ -e "$conf" ]; then ` +
+	`choice=$(sed -n 's/^# hackeros-builder: \[system\] -> nvidia => //p' "$conf"); gpu=""; ` +
+	`for d in /sys/bus/pci/devices/*; do if [ "$(cat "$d/vendor" 2>/dev/null)" = 0x10de ]; then ` +
+	`case "$(cat "$d/class")" in 0x03*) gpu="$gpu $(basename "$d")";; esac; fi; done; ` +
+	`if [ -n "$gpu" ]; then echo "GPU NVIDIA:$gpu -- sterownik: $choice"; ` +
+	`else echo "brak GPU NVIDIA: usuwam sterownik ($choice)"; remove=""; ` +
+	`for p in nvidia-driver nvidia-kernel-dkms nvidia-open-kernel-dkms; do dpkg -s "$p" >/dev/null 2>&1 && remove="$remove $p"; done; ` +
+	`apt-get purge -y --auto-remove $remove || exit 1; ` +
+	`rm -f "$conf" && sed -i -e '/^nvidia$/d' -e '/^nvidia_[a-z]*$/d' /etc/initramfs-tools/modules && ` +
+	`update-initramfs -u -k all || exit 1; fi; ` +
+	`else echo "sterownik NVIDIA pominiety (nouveau)"; fi`
+
+// languagePackApps to aplikacje z tlumaczeniami w osobnych pakietach:
+// pakiet aplikacji -> funkcja nazwy pakietu l10n. Tlumaczenie trafia na
+// nosnik tylko, gdy aplikacja jest w obrazie (firefox-esr-l10n-* zalezy od
+// firefox-esr -- bez tego pakiet jezykowy dociagnalby cala przegladarke).
+var languagePackApps = []struct {
+	app  string
+	l10n func(config.LanguagePack) string
+}{
+	{"firefox-esr", func(lp config.LanguagePack) string { return "firefox-esr-l10n-" + lp.Mozilla }},
+	{"thunderbird", func(lp config.LanguagePack) string { return "thunderbird-l10n-" + lp.Mozilla }},
+	{"libreoffice-core", func(lp config.LanguagePack) string { return "libreoffice-l10n-" + lp.LibreOffice }},
+}
+
+// languagePackages zwraca pakiety jezykowe jezyka lang: slownik hunspell i
+// tlumaczenia aplikacji, dla ktorych installed(app) == true.
+func languagePackages(lang string, installed func(string) bool) []string {
+	lp := config.LanguagePacks[lang]
+	pkgs := []string{"hunspell-" + lp.Hunspell}
+	for _, a := range languagePackApps {
+		if installed(a.app) {
+			pkgs = append(pkgs, a.l10n(lp))
+		}
+	}
+	return pkgs
+}
+
+// dpkgInstalled zwraca true, gdy pakiet jest zainstalowany w rootfsDir
+// (dpkg trzyma liste plikow kazdego zainstalowanego pakietu).
+func dpkgInstalled(rootfsDir, pkg string) bool {
+	for _, name := range []string{pkg, pkg + ":amd64"} {
+		if _, err := os.Stat(filepath.Join(rootfsDir, "var", "lib", "dpkg", "info", name+".list")); err == nil {
+			return true
+		}
+	}
+	return false
+}
+
+// languagePacksCommand zwraca komende shellprocess wybierajaca pakiety
+// jezykowe ([installer] -> language_packs): zostaja pakiety jezyka z LANG
+// w /etc/default/locale (zapisanego przez localecfg), pakiety pozostalych
+// jezykow sa usuwane. Wybrany jezyk i pakiety trafiaja do logu.
+func languagePacksCommand(langs []string) string {
+	all := func(string) bool { return true }
+	var arms strings.Builder
+	var every []string
+	for _, l := range langs {
+		pkgs := languagePackages(l, all)
+		fmt.Fprintf(&arms, `%s) keep="%s";; `, l, strings.Join(pkgs, " "))
+		every = append(every, pkgs...)
+	}
+	return `lang=$(sed -n 's/^LANG="\{0,1\}\([a-z]*\).*/\1/p' /etc/default/locale); ` +
+		`case "$lang" in ` + arms.String() + `*) keep="";; esac; ` +
+		`remove=""; for p in ` + strings.Join(every, " ") + `; do ` +
+		`case " $keep " in *" $p "*) ;; *) dpkg -s "$p" >/dev/null 2>&1 && remove="$remove $p";; esac; done; ` +
+		`if [ -n "$keep" ]; then echo "jezyk systemu: $lang, pakiety jezykowe: $keep"; ` +
+		`else echo "jezyk systemu: $lang -- brak pakietow jezykowych (na nosniku: ` + strings.Join(langs, " ") + `)"; fi; ` +
+		`[ -z "$remove" ] || apt-get purge -y --auto-remove $remove`
+}
+
+// checkFilesystemTools sprawdza, ze wszystkie installerFilesystemTools sa
+// w rootfsDir (po apt-get install) -- blad przerywa build ISO, zanim
+// powstanie nosnik z instalatorem, ktory nie sformatuje dysku.
+func checkFilesystemTools(rootfsDir string) error {
+	var missing []string
+	for _, t := range installerFilesystemTools {
+		found := false
+		for _, dir := range []string{"usr/sbin", "sbin"} {
+			if _, err := os.Stat(filepath.Join(rootfsDir, dir, t.mkfs)); err == nil {
+				found = true
+				break
+			}
+		}
+		if !found {
+			missing = append(missing, fmt.Sprintf("%s: %s (pakiet %s)", t.fs, t.mkfs, t.pkg))
+		}
+	}
+	if len(missing) > 0 {
+		return fmt.Errorf("brak narzedzi mkfs w obrazie instalatora -- %s", strings.Join(missing, "; "))
+	}
+	return nil
+}
+
+// unitDirs to katalogi jednostek systemd w rootfs (od bookworm /lib to
+// symlink do /usr/lib -- sprawdzamy obie sciezki).
+var unitDirs = []string{"etc/systemd/system", "usr/lib/systemd/system", "lib/systemd/system"}
+
+// unitSuffixes to typy jednostek obejmowane przez nazwe bez sufiksu w
+// [installer] -> disable_services/mask_services -- np. cups.socket
+// uruchomilby cups.service na zadanie mimo "systemctl disable cups".
+var unitSuffixes = []string{".service", ".socket", ".path", ".timer"}
+
+// unitTypes to sufiksy, po ktorych nazwa jest juz pelna jednostka.
+var unitTypes = map[string]bool{
+	".service": true, ".socket": true, ".path": true, ".timer": true,
+	".target": true, ".mount": true, ".automount": true, ".swap": true,
+}
+
+// resolveServiceUnits zamienia nazwy z disable_services/mask_services na
+// jednostki istniejace w rootfsDir: nazwa z sufiksem musi istniec, nazwa
+// bez sufiksu -- co najmniej jedna z unitSuffixes. Brak jednostki to blad
+// buildu (literowka albo pakiet, ktorego nie ma w obrazie).
+func resolveServiceUnits(rootfsDir string, names []string) ([]string, error) {
+	exists := func(unit string) bool {
+		for _, dir := range unitDirs {
+			if _, err := os.Lstat(filepath.Join(rootfsDir, dir, unit)); err == nil {
+				return true
+			}
+		}
+		return false
+	}
+	var units []string
+	for _, name := range names {
+		if unitTypes[filepath.Ext(name)] {
+			if !exists(name) {
+				return nil, fmt.Errorf("brak jednostki systemd %s w obrazie", name)
+			}
+			units = append(units, name)
+			continue
+		}
+		n := len(units)
+		for _, suffix := range unitSuffixes {
+			if exists(name + suffix) {
+				units = append(units, name+suffix)
+			}
+		}
+		if len(units) == n {
+			return nil, fmt.Errorf("brak jednostki systemd %s (%s) w obrazie", name, strings.Join(unitSuffixes, ", "))
+		}
+	}
+	return units, nil
+}
+
+// servicesCommand zwraca komende shellprocess wylaczajaca i maskujaca
+// jednostki ([installer] -> disable_services/mask_services, juz po
+// resolveServiceUnits) i wypisujaca do logu ich stan po zmianie.
+func servicesCommand(disable, mask []string) string {
+	quote := func(units []string) string {
+		q := make([]string, len(units))
+		for i, u := range units {
+			q[i] = sandbox.ShellQuote(u)
+		}
+		return strings.Join(q, " ")
+	}
+	var b strings.Builder
+	if len(disable) > 0 {
+		b.WriteString("systemctl disable " + quote(disable) + " && ")
+	}
+	if len(mask) > 0 {
+		b.WriteString("systemctl mask " + quote(mask) + " && ")
+	}
+	b.WriteString(`for u in ` + quote(append(append([]string{}, disable...), mask...)) + `; do ` +
+		`echo "usluga $u: $(systemctl is-enabled "$u" 2>/dev/null)"; done`)
+	return b.String()
 }
 
 // InjectInstaller wykonuje caly krok wstrzykniecia instalatora GUI do
 // rootfsDir (kopia ISO-only). workDir jest uzywany przez toolchain.Manager
 // do pobierania brakujacych narzedzi (wspoldzielony z reszta buildu --
 // narzedzia pobrane w kroku "build cloud" sa tu ponownie uzywane z cache).
-func InjectInstaller(rootfsDir, workDir string) error {
+// opts to sekcja [installer] z config.hk (wartosc zerowa -- domyslne),
+// hooks to config/hooks/install/*.hook.chroot projektu (moze byc puste),
+// timeouts to limity apt-get z [timeouts].
+func InjectInstaller(rootfsDir, workDir string, opts config.InstallerConfig, hooks []liveparse.HookScript, timeouts config.TimeoutsConfig) error {
 	// Toolchain: upewnij sie ze apt-get i dpkg-deb sa dostepne (sa zawsze,
 	// ale Manager.Env() daje nam sciezke z toolchain-bin/ na czele PATH
 	// co jest potrzebne jesli debootstrap byl pobrany tymczasowo).
 	tc := toolchain.New(workDir)
 	tcEnv := tc.Env()
 
-	util.Infof("  instalator GUI: instalacja Calamares + Xorg (%d pakietow)...", len(installerPackages))
-	if err := sandbox.ExecEnv(rootfsDir, tcEnv, "apt-get", "update"); err != nil {
-		return fmt.Errorf("apt-get update: %w", err)
+	pkgs := append([]string{}, installerPackages...)
+	for _, t := range installerFilesystemTools {
+		pkgs = append(pkgs, t.pkg)
 	}
-	installArgs := append([]string{
-		"install", "-y", "--no-install-recommends",
-		"-o", "Dpkg::Options::=--force-confdef",
-		"-o", "Dpkg::Options::=--force-confold",
-	}, installerPackages...)
-	if err := sandbox.ExecEnv(rootfsDir, tcEnv, "apt-get", installArgs...); err != nil {
+	if opts.BtrfsSnapshots {
+		pkgs = append(pkgs, "snapper")
+	}
+	if opts.UsesSystemdBoot() {
+		pkgs = append(pkgs, "systemd-boot")
+	}
+	if !opts.SkipVMGuestTools {
+		pkgs = append(pkgs, vmGuestPackages()...)
+	}
+	installed := func(pkg string) bool { return dpkgInstalled(rootfsDir, pkg) }
+	for _, l := range opts.LanguagePacks {
+		lp := languagePackages(l, installed)
+		util.Infof("  instalator GUI: pakiety jezykowe %s: %s", l, strings.Join(lp, ", "))
+		pkgs = append(pkgs, lp...)
+	}
+
+	if opts.TTYAutologin {
+		util.Warnf("[installer] -> tty_autologin: kazdy z dostepem do konsoli tty1 " +
+			"jest zalogowany bez hasla -- przy szyfrowaniu dysku jedyna bariera jest haslo LUKS")
+	}
+
+	util.Infof("  instalator GUI: instalacja Calamares + Xorg (%d pakietow)...", len(pkgs))
+	// Jak w rootfs.Builder: chwilowy blad mirrora nie przerywa buildu ISO.
+	err := util.Retry("apt-get update", 3, 10*time.Second, func() error {
+		return sandbox.ExecEnvTimeout(rootfsDir, tcEnv, timeouts.AptUpdate, "apt-get", "update")
+	})
+	if err != nil {
+		return err
+	}
+	if err := sandbox.ExecEnvTimeout(rootfsDir, tcEnv, timeouts.AptInstall, "apt-get", aptInstallArgs(pkgs...)...); err != nil {
 		return fmt.Errorf("apt-get install (instalator): %w", err)
 	}
+	if err := checkFilesystemTools(rootfsDir); err != nil {
+		return err
+	}
 
+	// Po instalacji pakietow: jednostki z disable_services/mask_services
+	// musza istniec w obrazie, a nazwy bez sufiksu obejmuja tez .socket
+	// itp. -- shellprocess dostaje gotowa liste.
+	if opts.DisableServices, err = resolveServiceUnits(rootfsDir, opts.DisableServices); err != nil {
+		return fmt.Errorf("[installer] -> disable_services: %w", err)
+	}
+	if opts.MaskServices, err = resolveServiceUnits(rootfsDir, opts.MaskServices); err != nil {
+		return fmt.Errorf("[installer] -> mask_services: %w", err)
+	}
+	for _, d := range opts.DisableServices {
+		for _, m := range opts.MaskServices {
+			if d == m {
+				return fmt.Errorf("[installer] -> %s jest w disable_services i mask_services", d)
+			}
+		}
+	}
+	if len(opts.DisableServices) > 0 || len(opts.MaskServices) > 0 {
+		util.Infof("  instalator GUI: uslugi wylaczane: %s; maskowane: %s",
+			strings.Join(opts.DisableServices, ", "), strings.Join(opts.MaskServices, ", "))
+	}
+
+	// grub-btrfs (snapshoty w menu GRUB) jest w Debianie dopiero od trixie --
+	// na starszych wydaniach brak pakietu to tylko ostrzezenie: snapshoty
+	// dalej dzialaja, rollback przez "snapper rollback" z systemu.
+	if opts.BtrfsSnapshots && !opts.UsesSystemdBoot() {
+		if err := sandbox.ExecEnvTimeout(rootfsDir, tcEnv, timeouts.AptInstall, "apt-get", aptInstallArgs("grub-btrfs", "inotify-tools")...); err != nil {
+			util.Warnf("grub-btrfs niedostepny w tym wydaniu -- snapshoty nie beda widoczne w menu GRUB: %v", err)
+		}
+	}
+
+	if opts.BtrfsSnapshots {
+		if err := ensureSnapperAptHook(rootfsDir); err != nil {
+			return fmt.Errorf("hook apt snappera: %w", err)
+		}
+	}
+
+	if sum := opts.MinStorageSummary(); sum != "" {
+		util.Infof("  instalator GUI: minimalny dysk dla ukladu automatycznego: %s", sum)
+	}
+
+	withPostInstall := len(hooks) > 0 || len(opts.PostInstall) > 0
 	util.Infof("  instalator GUI: zapis konfiguracji Calamares...")
-	if err := writeCalamaresConfig(rootfsDir); err != nil {
+	if err := writeCalamaresConfig(rootfsDir, opts, withPostInstall); err != nil {
 		return fmt.Errorf("konfiguracja calamares: %w", err)
 	}
 
+	if withPostInstall {
+		util.Infof("  instalator GUI: %d hook(ow) instalacji, %d komend post_install...",
+			len(hooks), len(opts.PostInstall))
+		if err := copyInstallHooks(rootfsDir, hooks); err != nil {
+			return fmt.Errorf("hooki instalacji: %w", err)
+		}
+		if err := writePostInstallScript(rootfsDir, opts); err != nil {
+			return fmt.Errorf("skrypt post-install: %w", err)
+		}
+	}
+
+	if err := writeTargetVerifyScript(rootfsDir); err != nil {
+		return fmt.Errorf("skrypt weryfikacji: %w", err)
+	}
+
 	util.Infof("  instalator GUI: konfiguracja autostartu na tty1...")
 	if err := writeInstallerAutostart(rootfsDir); err != nil {
 		return fmt.Errorf("autostart instalatora: %w", err)
@@ -78,15 +426,53 @@ func InjectInstaller(rootfsDir, workDir string) error {
 	return nil
 }
 
+// snapperAptHookPath to hook apt pakietu snapper w Debianie -- snapshot
+// "pre" przed i "post" po kazdej operacji dpkg, tylko gdy istnieje
+// konfiguracja "root" (na systemie bez btrfs to no-op).
+const snapperAptHookPath = "/etc/apt/apt.conf.d/80snapper"
+
+// snapperAptHook to zamiennik snapperAptHookPath dla wydan, w ktorych
+// pakiet go nie dostarcza -- ta sama logika co w Debianie.
+const snapperAptHook = `// Wygenerowane przez hackeros-builder (brak hooka w pakiecie snapper).
+DPkg::Pre-Invoke { "if [ -e /etc/default/snapper ]; then . /etc/default/snapper; fi; if [ -x /usr/bin/snapper ] && [ ! x$DISABLE_APT_SNAPSHOT = 'xyes' ] && [ -e /etc/snapper/configs/root ]; then rm -f /var/tmp/snapper-apt || true; snapper create -d apt -c number -t pre -p > /var/tmp/snapper-apt || true; snapper cleanup number || true; fi"; };
+DPkg::Post-Invoke { "if [ -e /etc/default/snapper ]; then . /etc/default/snapper; fi; if [ -x /usr/bin/snapper ] && [ ! x$DISABLE_APT_SNAPSHOT = 'xyes' ] && [ -e /var/tmp/snapper-apt ]; then if [ -s /var/tmp/snapper-apt ]; then snapper create -d apt -c number -t post --pre-number=$(cat /var/tmp/snapper-apt) || true; fi; rm -f /var/tmp/snapper-apt; fi"; };
+`
+
+// ensureSnapperAptHook zapisuje snapperAptHook, jesli zainstalowany
+// snapper nie dostarczyl wlasnego hooka. Plik trafia z live rootfs do
+// systemu docelowego (unpackfs); na nosniku live to no-op (brak
+// konfiguracji "root").
+func ensureSnapperAptHook(rootfsDir string) error {
+	path := filepath.Join(rootfsDir, strings.TrimPrefix(snapperAptHookPath, "/"))
+	if _, err := os.Stat(path); err == nil {
+		return nil
+	}
+	util.Infof("  instalator GUI: brak %s w pakiecie snapper -- zapis wlasnego hooka", snapperAptHookPath)
+	if err := os.MkdirAll(filepath.Dir(path), 0o755); err != nil {
+		return err
+	}
+	return os.WriteFile(path, []byte(snapperAptHook), 0o644)
+}
+
+// aptInstallArgs zwraca argumenty "apt-get install" bez pytan dpkg o
+// pliki konfiguracyjne.
+func aptInstallArgs(pkgs ...string) []string {
+	return append([]string{
+		"install", "-y", "--no-install-recommends",
+		"-o", "Dpkg::Options::=--force-confdef",
+		"-o", "Dpkg::Options::=--force-confold",
+	}, pkgs...)
+}
+
 // writeCalamaresConfig zapisuje pelny zestaw plikow konfiguracyjnych
 // Calamares wewnatrz rootfs (trafiaja do ISO, NIE do obrazu wypchnietego
 // "build cloud"). Sekwencja modulow odpowiada standardowemu, sprawdzonemu
 // przeplywowi distro-niezaleznemu (uzywanemu m.in. przez oficjalne obrazy
 // Debian Live z Calamares): welcome -> locale -> keyboard -> partition ->
 // users -> summary -> unpackfs (kopiowanie z /live/filesystem.squashfs) ->
-// machineid -> fstab -> localecfg -> grubcfg -> bootloader -> umountcfg ->
-// finished.
-func writeCalamaresConfig(rootfsDir string) error {
+// machineid -> fstab -> localecfg -> grubcfg -> bootloader ->
+// luksbootkeyfile -> initramfscfg -> initramfs -> umountcfg -> finished.
+func writeCalamaresConfig(rootfsDir string, opts config.InstallerConfig, withPostInstall bool) error {
 	base := filepath.Join(rootfsDir, "etc", "calamares")
 	modulesDir := filepath.Join(base, "modules")
 	brandingDir := filepath.Join(base, "branding", "hackeros")
@@ -98,20 +484,21 @@ func writeCalamaresConfig(rootfsDir string) error {
 	}
 
 	files := map[string]string{
-		filepath.Join(base, "settings.conf"): calamaresSettingsConf,
+		filepath.Join(base, "settings.conf"): calamaresSettingsConf(opts),
 
-		filepath.Join(modulesDir, "welcome.conf"):      calamaresWelcomeConf,
+		filepath.Join(modulesDir, "welcome.conf"):      calamaresWelcomeConf(opts),
 		filepath.Join(modulesDir, "locale.conf"):       calamaresLocaleConf,
 		filepath.Join(modulesDir, "keyboard.conf"):     calamaresKeyboardConf,
-		filepath.Join(modulesDir, "partition.conf"):    calamaresPartitionConf,
+		filepath.Join(modulesDir, "partition.conf"):    calamaresPartitionConf(opts),
 		filepath.Join(modulesDir, "users.conf"):        calamaresUsersConf,
 		filepath.Join(modulesDir, "unpackfs.conf"):     calamaresUnpackfsConf,
-		filepath.Join(modulesDir, "mount.conf"):        calamaresMountConf,
+		filepath.Join(modulesDir, "mount.conf"):        calamaresMountConf(opts),
 		filepath.Join(modulesDir, "machineid.conf"):    calamaresMachineidConf,
-		filepath.Join(modulesDir, "fstab.conf"):        calamaresFstabConf,
-		filepath.Join(modulesDir, "bootloader.conf"):   calamaresBootloaderConf,
+		filepath.Join(modulesDir, "fstab.conf"):        calamaresFstabConf(opts),
+		filepath.Join(modulesDir, "grubcfg.conf"):      calamaresGrubcfgConf,
+		filepath.Join(modulesDir, "bootloader.conf"):   calamaresBootloaderConf(opts),
 		filepath.Join(modulesDir, "umount.conf"):       calamaresUmountConf,
-		filepath.Join(modulesDir, "shellprocess.conf"): calamaresShellprocessConf,
+		filepath.Join(modulesDir, "shellprocess.conf"): calamaresShellprocessConf(opts, withPostInstall),
 
 		filepath.Join(brandingDir, "branding.desc"): calamaresBrandingDesc,
 	}
@@ -124,7 +511,28 @@ func writeCalamaresConfig(rootfsDir string) error {
 	return nil
 }
 
-const calamaresSettingsConf = `# Wygenerowane przez hackeros-builder -- NIE edytuj recznie w obrazie,
+// grubOnlyModules to moduly exec potrzebne tylko przy GRUB. grubcfg
+// ustawia GRUB_ENABLE_CRYPTODISK=y, gdy / jest na LUKS bez osobnego
+// niezaszyfrowanego /boot; luksbootkeyfile dodaje do kontenera
+// /crypto_keyfile.bin, ktory initramfscfg kopiuje do initramfs -- haslo
+// podaje sie raz, w GRUB. Przy systemd-boot initramfs lezy na
+// niezaszyfrowanym ESP, wiec klucz w nim oznaczalby klucz jawnym tekstem.
+var grubOnlyModules = []string{"grubcfg", "bootloader", "luksbootkeyfile"}
+
+// calamaresSettingsConf generuje settings.conf. Przy systemd-boot moduly
+// grubOnlyModules wypadaja z sekwencji -- bootctl/kernel-install wola
+// shellprocess (patrz systemdBootCommand).
+func calamaresSettingsConf(opts config.InstallerConfig) string {
+	conf := calamaresSettingsTemplate
+	if opts.UsesSystemdBoot() {
+		for _, m := range grubOnlyModules {
+			conf = strings.Replace(conf, "      - "+m+"\n", "", 1)
+		}
+	}
+	return conf
+}
+
+const calamaresSettingsTemplate = `# Wygenerowane przez hackeros-builder -- NIE edytuj recznie w obrazie,
 # zmiany rob w config/hooks/normal/*.hook.chroot wlasnego projektu.
 modules-search: [ local ]
 instances: []
@@ -146,7 +554,11 @@ sequence:
       - localecfg
       - keyboard
       - users
+      - grubcfg
       - bootloader
+      - luksbootkeyfile
+      - initramfscfg
+      - initramfs
       - shellprocess
       - umount
   - show:
@@ -160,13 +572,18 @@ disable-cancel-during-exec: true
 quit-at-end: false
 `
 
-const calamaresWelcomeConf = `---
+// calamaresWelcomeConf generuje welcome.conf. RAM jest tylko w "check"
+// (nie w "required"): za malo pamieci to ostrzezenie na ekranie
+// powitalnym, nie blokada -- system dziala, tylko wolno. Za maly dysk
+// blokuje instalacje (unpackfs i tak by sie nie zmiescil).
+func calamaresWelcomeConf(opts config.InstallerConfig) string {
+	return fmt.Sprintf(`---
 showSupportUrl:       false
 showKnownIssuesUrl:   false
 showReleaseNotesUrl:  false
 requirements:
-  requiredStorage:    4.0
-  requiredRam:        1.0
+  requiredStorage:    %d.0
+  requiredRam:        %d.0
   internetCheckUrl:   "https://deb.debian.org"
   check:
     - storage
@@ -174,8 +591,8 @@ requirements:
     - power
   required:
     - storage
-    - ram
-`
+`, opts.StorageThresholdGB(), opts.RAMThresholdGB())
+}
 
 const calamaresLocaleConf = `---
 region: "Etc"
@@ -187,19 +604,74 @@ defaultLayout: us
 defaultVariant: ""
 `
 
-const calamaresPartitionConf = `---
-efiSystemPartition: "/boot/efi"
-userSwapChoices:
-    - none
-    - small
-    - suspend
-defaultFileSystemType: "ext4"
+// calamaresPartitionConf generuje partition.conf. Rozmiar ESP jest
+// podawany w obu formatach: efiSystemPartitionSize (Calamares 3.2) i
+// efi.recommendedSize (3.3) -- kazda wersja ignoruje klucz drugiej.
+// partitionLayout (przy separate_home / root_filesystem) opisuje uklad
+// "wymaz dysk";
+// filesystem "unknown" = typ wybrany przez uzytkownika
+// (defaultFileSystemType), swap Calamares dokleja osobno wg
+// userSwapChoices -- chyba ze swap_size dodaje partycje swap do ukladu,
+// wtedy jedyny wybor to "none" (bez drugiego swapu).
+func calamaresPartitionConf(opts config.InstallerConfig) string {
+	var b strings.Builder
+	b.WriteString("---\nefiSystemPartition: \"/boot/efi\"\nuserSwapChoices:\n    - none\n")
+	if opts.SwapSize.Value == 0 {
+		b.WriteString("    - small\n    - suspend\n")
+	}
+	b.WriteString(`defaultFileSystemType: "ext4"
 availableFileSystemTypes:  [ "ext4", "btrfs", "xfs" ]
 enableLuksAutomatedPartitioning: true
 drawNestedPartitions: false
 alwaysShowPartitionLabels: true
 allowManualPartitioning: true
-`
+`)
+	// GRUB odblokowuje /boot (w kontenerze z /) tylko z LUKS1 -- LUKS2
+	// Calamares 3.3 uzywa argon2id, ktorego GRUB nie obsluguje. Calamares
+	// 3.2 (bookworm) zawsze tworzy LUKS1 i klucza nie zna.
+	if opts.UsesSystemdBoot() {
+		b.WriteString("luksGeneration: luks2\n")
+	} else {
+		b.WriteString("luksGeneration: luks1\n")
+	}
+	if opts.ESPSizeMiB != 0 {
+		fmt.Fprintf(&b, "efiSystemPartitionSize: %dMiB\n", opts.ESPSizeMiB)
+		fmt.Fprintf(&b, "efi:\n    mountPoint: \"/boot/efi\"\n    recommendedSize: %dMiB\n", opts.ESPSizeMiB)
+	}
+	if opts.UsesPartitionLayout() {
+		b.WriteString("partitionLayout:\n")
+		rootSize := "100%"
+		if opts.SeparateHome {
+			rootSize = opts.RootSize.String()
+		}
+		fmt.Fprintf(&b, `    - name: "root"
+      filesystem: "%s"
+      mountPoint: "/"
+      size: %s
+`, config.LayoutFilesystem(opts.RootFilesystem), rootSize)
+		if opts.SeparateVarLog {
+			fmt.Fprintf(&b, `    - name: "varlog"
+      filesystem: "%s"
+      mountPoint: "/var/log"
+      size: %s
+`, config.LayoutFilesystem(opts.RootFilesystem), opts.VarLogSize)
+		}
+		if opts.SeparateHome {
+			fmt.Fprintf(&b, `    - name: "home"
+      filesystem: "%s"
+      mountPoint: "/home"
+      size: %s
+`, config.LayoutFilesystem(opts.HomeFilesystem), opts.HomeSize)
+		}
+		if opts.SwapSize.Value > 0 {
+			fmt.Fprintf(&b, `    - name: "swap"
+      filesystem: "linuxswap"
+      size: %s
+`, opts.SwapSize)
+		}
+	}
+	return b.String()
+}
 
 const calamaresUsersConf = `---
 defaultGroups:
@@ -230,13 +702,112 @@ unpack:
       destination: ""
 `
 
-const calamaresMountConf = `---
-extraMounts: []
+// btrfsSubvolume to jeden wpis btrfsSubvolumes w mount.conf -- Calamares
+// tworzy podwolumen i dopisuje go do fstab z opcja subvol=.
+type btrfsSubvolume struct {
+	mountPoint string
+	subvolume  string
+}
+
+// btrfsSimpleLayout: root w @, osobno @home i @var@log -- wymagany przez
+// snapper/grub-btrfs, zeby rollback / nie cofal danych uzytkownika ani
+// logow.
+var btrfsSimpleLayout = []btrfsSubvolume{
+	{"/", "/@"},
+	{"/home", "/@home"},
+	{"/var/log", "/@var@log"},
+}
+
+// btrfsFullLayout (konwencja openSUSE/snapper): dodatkowo osobne
+// podwolumeny na dane, ktorych rollback / nie powinien cofac ani trzymac
+// w snapshotach (zagniezdzony podwolumen nie wchodzi do snapshotu @).
+// Calego /var celowo NIE wydzielamy: /var/lib/dpkg musi sie cofac razem
+// z /usr, inaczej po rollbacku dpkg widzi pakiety, ktorych nie ma.
+// @snapshots na /.snapshots -- snapshoty przezywaja rollback @ (patrz
+// komenda snappera w shellprocessCommands).
+var btrfsFullLayout = []btrfsSubvolume{
+	{"/", "/@"},
+	{"/home", "/@home"},
+	{"/root", "/@root"},
+	{"/srv", "/@srv"},
+	{"/var/log", "/@var@log"},
+	{"/var/cache", "/@var@cache"},
+	{"/var/tmp", "/@var@tmp"},
+	{"/tmp", "/@tmp"},
+	{"/.snapshots", "/@snapshots"},
+}
+
+// btrfsLayout zwraca podwolumeny dla [installer] -> btrfs_layout.
+func btrfsLayout(layout string) []btrfsSubvolume {
+	if layout == config.BtrfsLayoutFull {
+		return btrfsFullLayout
+	}
+	return btrfsSimpleLayout
+}
+
+// calamaresMountConf generuje mount.conf. mountOptions/ssdOptions to
+// opcje montowania w fstab od Calamares 3.3 (wczesniej: fstab.conf,
+// patrz calamaresFstabConf): <fs>_mount_options z [installer] i
+// discard=async dla btrfs na SSD; ESP zawsze z umask=0077.
+// btrfsSubvolumes to uklad "@" z btrfsLayout (dotyczy tylko wyboru btrfs).
+// extraMounts: /proc, /sys, /dev i /run w systemie docelowym -- bez nich
+// komendy w chroot (grub-probe i /dev/disk/by-uuid w targetVerifyScript,
+// findmnt i /sys/block przy wykrywaniu SSD) nie widza urzadzen ani
+// sprzetu. Bez opcji "bind", ktorych format rozni sie miedzy Calamares
+// 3.2 i 3.3.
+func calamaresMountConf(opts config.InstallerConfig) string {
+	var conf strings.Builder
+	conf.WriteString(`---
+extraMounts:
+    - device: "proc"
+      fs: "proc"
+      mountPoint: "/proc"
+    - device: "sys"
+      fs: "sysfs"
+      mountPoint: "/sys"
+    - device: "udev"
+      fs: "devtmpfs"
+      mountPoint: "/dev"
+    - device: "tmpfs"
+      fs: "tmpfs"
+      mountPoint: "/run"
 extraMountsEfi:
     - device: "efivarfs"
       fs: "efivarfs"
       mountPoint: "/sys/firmware/efi/efivars"
-`
+btrfsSubvolumes:
+`)
+	for _, sv := range btrfsLayout(opts.BtrfsLayout) {
+		if opts.SeparateVarLog && sv.mountPoint == "/var/log" {
+			continue // osobna partycja zamiast podwolumenu
+		}
+		fmt.Fprintf(&conf, "    - mountPoint: %s\n      subvolume: %s\n", sv.mountPoint, sv.subvolume)
+	}
+	conf.WriteString("mountOptions:\n" +
+		"    - filesystem: default\n      options: [ defaults ]\n" +
+		"    - filesystem: efi\n      options: [ defaults, umask=0077 ]\n")
+	for _, fs := range []string{"ext4", "btrfs", "xfs"} {
+		fsOpts := mountOptionsFor(opts, fs)
+		ssd := !opts.SkipSSDTrim && fs == "btrfs"
+		if len(opts.MountOptions[fs]) == 0 && !ssd {
+			continue
+		}
+		fmt.Fprintf(&conf, "    - filesystem: %s\n      options: [ %s ]\n", fs, strings.Join(fsOpts, ", "))
+		if ssd {
+			conf.WriteString("      ssdOptions: [ \"discard=async\" ]\n")
+		}
+	}
+	return conf.String()
+}
+
+// mountOptionsFor zwraca opcje montowania dla fs (<fs>_mount_options albo
+// "defaults").
+func mountOptionsFor(opts config.InstallerConfig, fs string) []string {
+	if o := opts.MountOptions[fs]; len(o) > 0 {
+		return o
+	}
+	return []string{"defaults"}
+}
 
 const calamaresMachineidConf = `---
 systemd: true
@@ -244,13 +815,47 @@ dbus: true
 symlink: false
 `
 
-const calamaresFstabConf = `---
+// calamaresFstabConf generuje fstab.conf. mountOptions i
+// ssdExtraMountOptions to odpowiedniki mountOptions/ssdOptions z
+// mount.conf dla Calamares 3.2 (bookworm) -- kazda wersja ignoruje klucze
+// drugiej.
+func calamaresFstabConf(opts config.InstallerConfig) string {
+	var conf strings.Builder
+	conf.WriteString(`---
 efiMountPoint: "/boot/efi"
 crypttabOptions: [ "luks", "keyscript=/bin/cat" ]
+mountOptions:
+    default: defaults
+    efi: "defaults,umask=0077"
+`)
+	for _, fs := range []string{"ext4", "btrfs", "xfs"} {
+		if len(opts.MountOptions[fs]) > 0 {
+			fmt.Fprintf(&conf, "    %s: %s\n", fs, strings.Join(opts.MountOptions[fs], ","))
+		}
+	}
+	if !opts.SkipSSDTrim {
+		conf.WriteString("ssdExtraMountOptions:\n    btrfs: \"discard=async\"\n")
+	}
+	return conf.String()
+}
+
+// calamaresGrubcfgConf: grubcfg poprawia istniejacy /etc/default/grub
+// (GRUB_ENABLE_CRYPTODISK, resume=), nie pisze go od nowa.
+// GRUB_DISTRIBUTOR zostaje z obrazu -- nazwa z [system] -> os_name, nie
+// z brandingu instalatora. Ustawienia [grub] (drop-in w
+// /etc/default/grub.d) i tak sa czytane pozniej.
+const calamaresGrubcfgConf = `---
+overwrite: false
+keepDistributor: true
 `
 
-const calamaresBootloaderConf = `---
+// calamaresBootloaderConf generuje bootloader.conf. efiBootloaderId to
+// --bootloader-id dla grub-install: nazwa wpisu w menu bootowania firmware
+// zamiast generycznego "debian" (kilka instalacji na jednej maszynie).
+func calamaresBootloaderConf(opts config.InstallerConfig) string {
+	return `---
 efiBootLoader:        "grub"
+efiBootloaderId:      ` + yamlQuote(opts.EFIBootloaderID()) + `
 kernel: "/boot/vmlinuz"
 img:    "/boot/initrd.img"
 grubInstall:          "grub-install"
@@ -261,21 +866,552 @@ efiBootMgr:           "efibootmgr"
 installEFIFallback:   true
 timeout: "10"
 `
+}
 
 const calamaresUmountConf = `---
 `
 
-// calamaresShellprocessConf zaszywa wpis [origin] deb-ostree w docelowym
-// systemie po skopiowaniu plikow (squashfs juz zawiera poprawny
-// /etc/deb-ostree/deb-ostree.hk wygenerowany przez "build iso" -- ten krok
-// jest tylko siatka bezpieczenstwa, no-op gdy plik juz istnieje).
-const calamaresShellprocessConf = `---
-dontChroot: false
-timeout: 30
-script:
-    - command: "mkdir -p /etc/deb-ostree"
+// calamaresShellprocessConf generuje shellprocess.conf -- komendy
+// wykonywane w docelowym systemie (chroot) po skopiowaniu plikow.
+// Zawsze: zaszycie katalogu [origin] deb-ostree (squashfs juz zawiera
+// poprawny /etc/deb-ostree/deb-ostree.hk wygenerowany przez "build iso" --
+// ten krok jest tylko siatka bezpieczenstwa, no-op gdy plik juz istnieje).
+// Reszta zalezy od sekcji [installer] (patrz shellprocessCommands).
+// timeout dotyczy KAZDEJ komendy osobno -- 600 s, bo hooki instalacji
+// projektu moga np. dociagac pakiety.
+func calamaresShellprocessConf(opts config.InstallerConfig, withPostInstall bool) string {
+	var b strings.Builder
+	b.WriteString("---\ndontChroot: false\ntimeout: 600\nscript:\n")
+	for _, cmd := range shellprocessCommands(opts, withPostInstall) {
+		fmt.Fprintf(&b, "    - command: %s\n", yamlQuote(cmd))
+	}
+	return b.String()
+}
+
+// shellprocessCommands zwraca liste komend dla shellprocess.conf.
+// ${USER} podstawia Calamares (login uzytkownika z kroku "users").
+func shellprocessCommands(opts config.InstallerConfig, withPostInstall bool) []string {
+	cmds := []string{"mkdir -p /etc/deb-ostree"}
+
+	if opts.UserUID != 0 {
+		// Calamares nie pozwala wybrac UID -- zmieniamy po utworzeniu
+		// konta. usermod/groupmod odmawiaja, gdy UID/GID jest zajety
+		// (instalacja konczy sie bledem z ich komunikatem); usermod -u
+		// sam poprawia wlasciciela plikow w katalogu domowym.
+		cmds = append(cmds, fmt.Sprintf(
+			`[ "$(id -u "${USER}")" = %[1]d ] && [ "$(id -g "${USER}")" = %[2]d ] || `+
+				`{ groupmod -g %[2]d "$(id -gn "${USER}")" && usermod -u %[1]d -g %[2]d "${USER}"; }`,
+			opts.UserUID, opts.UserGID))
+	}
+
+	if opts.ForcePasswordChange {
+		// "chage -d 0" wymusza zmiane hasla przy pierwszym logowaniu.
+		// Konto z autologinem (zaznaczone "loguj automatycznie" w
+		// Calamares -> czlonek grupy autologin) pomijamy: menedzer
+		// logowania nie pokaze monitu o zmiane hasla i uzytkownik
+		// zostalby zablokowany na wygaslym hasle (np. przy sudo/ekranie
+		// blokady), bez interaktywnej sciezki zmiany.
+		// ${USER} w cudzyslowach: Calamares podstawia tekst przed
+		// przekazaniem komendy do sh.
+		cmds = append(cmds,
+			`id -nG "${USER}" | grep -qw autologin || chage -d 0 "${USER}"`)
+	}
+
+	if opts.KeyboardSecondary != "" {
+		// Modul keyboard (przed shellprocess) zapisal uklad wybrany przez
+		// uzytkownika do /etc/default/keyboard -- dopisujemy drugi uklad
+		// (chyba ze juz jest, np. Calamares sam dodaje "us" do ukladow
+		// nielacinskich) i przelacznik, o ile nie ma juz zadnego grp:.
+		// keyboard-configuration czyta ten plik dla X11 i konsoli.
+		cmds = append(cmds, fmt.Sprintf(
+			`. /etc/default/keyboard && `+
+				`case ",$XKBLAYOUT," in *,%[1]s,*) ;; *) XKBLAYOUT="$XKBLAYOUT,%[1]s"; XKBVARIANT="$XKBVARIANT,";; esac && `+
+				`case "$XKBOPTIONS" in *grp:*) ;; "") XKBOPTIONS=%[2]s;; *) XKBOPTIONS="$XKBOPTIONS,%[2]s";; esac && `+
+				`sed -i -e "s/^XKBLAYOUT=.*/XKBLAYOUT=\"$XKBLAYOUT\"/" -e "s/^XKBVARIANT=.*/XKBVARIANT=\"$XKBVARIANT\"/" `+
+				`-e "s/^XKBOPTIONS=.*/XKBOPTIONS=\"$XKBOPTIONS\"/" /etc/default/keyboard && `+
+				`echo "klawiatura: $XKBLAYOUT ($XKBOPTIONS)"`,
+			opts.KeyboardSecondary, opts.KeyboardToggle))
+	}
+
+	if !opts.SkipSSDTrim {
+		// fstrim.timer (util-linux) tylko gdy dysk pod / jest SSD.
+		// lsblk -s idzie od urzadzenia / w gore (LUKS/LVM -> partycja ->
+		// dysk) do pierwszego wpisu TYPE=disk; findmnt -v bez sufiksu
+		// "[/@]" podwolumenu btrfs. Wynik trafia do logu.
+		cmds = append(cmds,
+			`disk=$(lsblk -rnso NAME,TYPE "$(findmnt -nvo SOURCE /)" | awk '$2=="disk"{print $1; exit}'); `+
+				`if [ "$(cat /sys/block/$disk/queue/rotational 2>/dev/null)" = 0 ]; then `+
+				`echo "SSD ($disk): wlaczam fstrim.timer"; systemctl enable fstrim.timer; `+
+				`else echo "dysk obrotowy lub nieznany ($disk): fstrim.timer pominiety"; fi`)
+	}
+
+	if !opts.SkipVMGuestTools {
+		cmds = append(cmds, vmGuestToolsCommand())
+	}
+
+	// Przed systemdBootCommand -- ten przebudowuje initramfs i kopiuje go
+	// na ESP.
+	cmds = append(cmds, nvidiaCommand, microcodeCommand)
+
+	if len(opts.LanguagePacks) > 0 {
+		cmds = append(cmds, languagePacksCommand(opts.LanguagePacks))
+	}
+
+	if opts.UsesSystemdBoot() {
+		cmds = append(cmds, systemdBootCommand)
+	}
+
+	// Po systemdBootCommand -- dopisuje console= do gotowych wpisow.
+	if opts.SerialConsole == nil || *opts.SerialConsole {
+		cmds = append(cmds, serialConsoleCommand(opts))
+	}
+
+	if opts.BtrfsSnapshots {
+		// Tylko btrfs z ukladem "@" (FSROOT / == /@, patrz
+		// calamaresMountConf) -- na ext4/xfs komenda jest no-op.
+		// snapper --no-dbus: w chroot instalatora nie ma magistrali D-Bus.
+		// Gdy /.snapshots to juz zamontowany @snapshots (btrfs_layout =
+		// full), create-config nie moze utworzyc tam podwolumenu -- odmontuj,
+		// pozwol snapperowi zalozyc konfiguracje, usun jego zagniezdzony
+		// .snapshots i zamontuj z powrotem @snapshots (wpis z fstab).
+		// Bazowy snapshot "post-install" to punkt powrotu do czystego
+		// systemu; update-grub dopisuje go do menu gdy jest grub-btrfs.
+		// Snapshoty apt (snapperAptHookPath) dzialaja od razu po
+		// create-config -- sprawdzamy hook i ze konfiguracja obejmuje /,
+		// albo wylaczamy je w /etc/default/snapper (apt_snapshots => false).
+		aptSnapshots := `[ -e ` + snapperAptHookPath + ` ] && grep -qx 'SUBVOLUME="/"' /etc/snapper/configs/root && ` +
+			`echo "snapshoty apt: wlaczone (` + snapperAptHookPath + `)"`
+		if opts.SkipAptSnapshots {
+			aptSnapshots = `echo DISABLE_APT_SNAPSHOT=yes >> /etc/default/snapper && echo "snapshoty apt: wylaczone"`
+		}
+		// Menu snapshotow (grub-btrfs + update-grub) tylko przy GRUB.
+		grubMenu := grubBtrfsMenuCommand
+		if opts.UsesSystemdBoot() {
+			grubMenu = `true; `
+		}
+		cmds = append(cmds,
+			`if [ "$(findmnt -no FSTYPE /)" = btrfs ] && [ "$(findmnt -no FSROOT /)" = /@ ]; then `+
+				`if mountpoint -q /.snapshots; then `+
+				`umount /.snapshots && rmdir /.snapshots && snapper --no-dbus -c root create-config / && `+
+				`btrfs subvolume delete /.snapshots && mkdir /.snapshots && mount /.snapshots; `+
+				`else snapper --no-dbus -c root create-config /; fi && `+
+				aptSnapshots+` && `+
+				`snapper --no-dbus -c root create -t single -c number --userdata important=yes -d post-install && `+
+				`echo "snapshot: $(snapper --no-dbus -c root list | tail -n 1)" && `+
+				grubMenu+
+				`else echo "snapshoty btrfs pominiete (system plikow / to nie btrfs z ukladem @)"; fi`)
+	}
+
+	// Przed post_install -- hooki projektu moga wlaczyc usluge z powrotem.
+	if len(opts.DisableServices) > 0 || len(opts.MaskServices) > 0 {
+		cmds = append(cmds, servicesCommand(opts.DisableServices, opts.MaskServices))
+	}
+
+	if withPostInstall {
+		// Hooki instalacji projektu i [installer] -> post_install (patrz
+		// postInstallScript). Po sukcesie katalog jest usuwany -- nie
+		// zostaje w systemie.
+		cmds = append(cmds, fmt.Sprintf(`%s "${USER}" && rm -rf %s`, postInstallPath, postInstallDir))
+	}
+
+	// Przed koncem (shellprocess jest po module bootloader): weryfikacja, ze
+	// docelowy system sie uruchomi (patrz targetVerifyScript). Niezerowy
+	// kod -> Calamares przerywa z bledem i pokazuje wypisana liste
+	// kontrolna zamiast ekranu "Gotowe".
+	if opts.UsesSystemdBoot() {
+		cmds = append(cmds, targetVerifyPath+" "+sandbox.ShellQuote(systemdBootEFIEntry)+" systemd-boot")
+	} else {
+		cmds = append(cmds, targetVerifyPath+" "+sandbox.ShellQuote(opts.EFIBootloaderID()))
+	}
+
+	// Dopiero po udanej weryfikacji: sprzatanie plikow nosnika live.
+	cmds = append(cmds, liveCleanupCommand())
+
+	// Po sprzataniu -- to usuwa autologin roota z nosnika live z tej
+	// samej sciezki.
+	if opts.TTYAutologin {
+		cmds = append(cmds, ttyAutologinCommand)
+	}
+
+	return cmds
+}
+
+// ttyAutologinCommand wlacza autologin uzytkownika z instalatora na tty1
+// ([installer] -> tty_autologin) przez override getty@tty1. Obraz z
+// menedzerem logowania (display-manager.service) jest pomijany -- tam
+// autologin ustawia krok "users" Calamares. ${USER} podstawia Calamares.
+const ttyAutologinCommand = `if [ -e /etc/systemd/system/display-manager.service ]; then ` +
+	`echo "tty_autologin pominiety: obraz ma menedzer logowania"; ` +
+	`else mkdir -p /etc/systemd/system/getty@tty1.service.d && ` +
+	`printf '[Service]\nExecStart=\nExecStart=-/sbin/agetty --autologin %s --noclear %%I $TERM\n' "${USER}" ` +
+	`> /etc/systemd/system/getty@tty1.service.d/autologin.conf && ` +
+	`echo "tty_autologin: ${USER} na tty1 (bez hasla)"; fi`
+
+// liveInstallerArtifacts to pliki nosnika live skopiowane 1:1 do systemu
+// docelowego: autostart instalatora (writeInstallerAutostart) i skrypt
+// weryfikacji. Bez sprzatania zainstalowany system logowalby roota
+// automatycznie na tty1 i uruchamial Calamares.
+var liveInstallerArtifacts = []string{
+	"/etc/systemd/system/getty@tty1.service.d/autologin.conf",
+	"/usr/local/sbin/hackeros-installer-xinit",
+	"/etc/hackeros-installer",
+	targetVerifyPath,
+}
+
+// liveCleanupCommand usuwa liveInstallerArtifacts z systemu docelowego.
+// Best-effort: brak pliku (np. usuniety przez hook projektu) i blad rm
+// trafiaja tylko do logu -- instalacja juz sie udala, sprzatanie nie moze
+// jej oznaczyc jako nieudanej. /root/.bash_profile i default.target sa
+// usuwane tylko, gdy to nadal wersje z writeInstallerAutostart; bez
+// symlinka w /etc system startuje w domyslnym target pakietu systemd.
+func liveCleanupCommand() string {
+	quoted := make([]string, len(liveInstallerArtifacts))
+	for i, p := range liveInstallerArtifacts {
+		quoted[i] = sandbox.ShellQuote(p)
+	}
+	return `for p in ` + strings.Join(quoted, " ") + `; do ` +
+		`if [ -e "$p" ]; then rm -rf "$p" && echo "usunieto: $p" || echo "nie mozna usunac: $p" >&2; ` +
+		`else echo "brak (pominiety): $p"; fi; done; ` +
+		`rmdir /etc/systemd/system/getty@tty1.service.d 2>/dev/null; ` +
+		`if grep -qF hackeros-installer-xinit /root/.bash_profile 2>/dev/null; then ` +
+		`rm -f /root/.bash_profile && echo "usunieto: /root/.bash_profile"; fi; ` +
+		`if [ "$(readlink /etc/systemd/system/default.target)" = /lib/systemd/system/multi-user.target ]; then ` +
+		`rm -f /etc/systemd/system/default.target && echo "usunieto: /etc/systemd/system/default.target"; fi; ` +
+		`true`
+}
+
+// grubBtrfsMenuCommand (fragment komendy snappera) wlacza grub-btrfsd --
+// kolejne snapshoty trafiaja do menu bez recznego update-grub -- i
+// generuje menu z bazowym snapshotem. Potem sprawdza, ze
+// 41_snapshots-btrfs wpisal snapshot do grub-btrfs.cfg (brak grub-btrfs
+// przed trixie to tylko informacja w logu). Osobna partycja /boot (reczne
+// partycjonowanie; uklady automatyczne trzymaja /boot w "@") nie jest w
+// snapshotach -- snapshot startuje wtedy z biezacym jadrem: ostrzezenie.
+const grubBtrfsMenuCommand = `{ [ ! -e /lib/systemd/system/grub-btrfsd.service ] || systemctl enable grub-btrfsd; } && update-grub && ` +
+	`if [ ! -e /etc/grub.d/41_snapshots-btrfs ]; then echo "grub-btrfs niedostepny: snapshoty bez wpisow w menu GRUB"; ` +
+	`elif grep -q 'snapshots/[0-9]*/snapshot' /boot/grub/grub-btrfs.cfg 2>/dev/null; then echo "menu GRUB: snapshoty w /boot/grub/grub-btrfs.cfg"; ` +
+	`else echo "UWAGA: grub-btrfs nie dodal snapshotow do menu GRUB" >&2; fi; ` +
+	`if mountpoint -q /boot; then echo "UWAGA: /boot to osobna partycja -- snapshot startuje z biezacym jadrem i initrd" >&2; fi; `
+
+// systemdBootEFIEntry to etykieta wpisu NVRAM tworzonego przez "bootctl
+// install" (bootloader_id dotyczy tylko GRUB).
+const systemdBootEFIEntry = "Linux Boot Manager"
+
+// systemdBootCommand instaluje systemd-boot zamiast modulu bootloader
+// Calamares (ten szuka jader w ukladzie Arch/Fedory, /usr/lib/modules/<v>/
+// vmlinuz, ktorego Debian nie ma). Kolejno:
+//   - tylko UEFI -- systemd-boot nie obsluguje BIOS; instalacja z nosnika
+//     uruchomionego w trybie BIOS konczy sie czytelnym bledem
+//   - /etc/kernel/cmdline: root=UUID systemu plikow (+ subvol przy btrfs
+//     "@"); kernel-install bez tego pliku wzialby /proc/cmdline nosnika live
+//   - bootctl install na ESP (/boot/efi, wpis NVRAM systemdBootEFIEntry)
+//   - update-initramfs: initramfs z /etc/crypttab zapisanym przez modul
+//     fstab -- przy LUKS cryptsetup-initramfs odblokowuje root sam. fstab
+//     wpisuje tam /crypto_keyfile.bin, ktorego bez luksbootkeyfile nie ma
+//     (patrz grubOnlyModules) -- wpis dostaje "none", czyli pytanie o haslo
+//   - kernel-install add dla kazdego jadra z /boot (wpis w loader/entries);
+//     pozniejsze aktualizacje jadra obsluguja hooki pakietu systemd-boot
+const systemdBootCommand = `[ -d /sys/firmware/efi ] || { echo "systemd-boot wymaga rozruchu UEFI -- uruchom nosnik w trybie UEFI albo wybierz bootloader grub" >&2; exit 1; }; ` +
+	`cmdline="root=UUID=$(findmnt -no UUID /) rw quiet"; ` +
+	`if [ "$(findmnt -no FSTYPE /)" = btrfs ]; then cmdline="$cmdline rootflags=subvol=$(findmnt -no FSROOT / | sed 's|^/||')"; fi; ` +
+	`echo "$cmdline" > /etc/kernel/cmdline && ` +
+	`if [ -f /etc/crypttab ]; then sed -i 's| /crypto_keyfile\.bin luks,keyscript=/bin/cat$| none luks|' /etc/crypttab; fi && ` +
+	`bootctl install --esp-path=/boot/efi && ` +
+	`update-initramfs -u -k all && ` +
+	`for k in /boot/vmlinuz-*; do v=$(basename "$k" | sed 's/^vmlinuz-//'); ` +
+	`kernel-install add "$v" "$k" "/boot/initrd.img-$v" || exit 1; done && ` +
+	`echo "systemd-boot: $(ls /boot/efi/loader/entries)"`
+
+// serialConsoleArgs to konsola jadra przy [installer] -> serial_console:
+// komunikaty i login na ttyS0, ostatnia konsola (ttyS0) to /dev/console.
+const serialConsoleArgs = "console=tty0 console=ttyS0,115200"
+
+// serialConsoleCommand zwraca komende shellprocess wlaczajaca konsole
+// szeregowa: serial-getty@ttyS0, serialConsoleArgs w wierszu polecen jadra
+// i terminal szeregowy GRUB (drop-in hackeros-serial.cfg + update-grub) albo
+// wpisy systemd-boot i /etc/kernel/cmdline. Przy serial_console => auto
+// (nil) tylko gdy nosnik dziala z konsola na ttyS0
+// (/sys/class/tty/console/active). Wynik trafia do logu.
+func serialConsoleCommand(opts config.InstallerConfig) string {
+	detect := `serial=1; `
+	if opts.SerialConsole == nil {
+		detect = `case " $(cat /sys/class/tty/console/active 2>/dev/null) " in *" ttyS0 "*) serial=1;; *) serial=0;; esac; `
+	}
+	bootloader := `mkdir -p /etc/default/grub.d && printf '%s\n' 'GRUB_CMDLINE_LINUX="$GRUB_CMDLINE_LINUX ` + serialConsoleArgs + `"' ` +
+		`'GRUB_TERMINAL="console serial"' 'GRUB_SERIAL_COMMAND="serial --unit=0 --speed=115200"' ` +
+		`> /etc/default/grub.d/hackeros-serial.cfg && update-grub`
+	if opts.UsesSystemdBoot() {
+		bootloader = `sed -i 's/$/ ` + serialConsoleArgs + `/' /etc/kernel/cmdline && ` +
+			`sed -i '/^options /s/$/ ` + serialConsoleArgs + `/' /boot/efi/loader/entries/*.conf`
+	}
+	return detect + `if [ "$serial" = 1 ]; then ` +
+		`systemctl enable serial-getty@ttyS0.service && ` + bootloader + ` && ` +
+		`echo "konsola szeregowa: ttyS0 115200 (getty, ` + serialConsoleArgs + `)"; ` +
+		`else echo "konsola szeregowa pominieta (nosnik bez konsoli na ttyS0)"; fi`
+}
+
+// yamlQuote zwraca s jako skalar YAML w podwojnych cudzyslowach
+// (escapowane \\ i \").
+func yamlQuote(s string) string {
+	s = strings.ReplaceAll(s, `\`, `\\`)
+	s = strings.ReplaceAll(s, `"`, `\"`)
+	return `"` + s + `"`
+}
+
+// postInstallDir to katalog (w live rootfs, a wiec i w systemie docelowym)
+// ze skryptem post-install i hookami instalacji projektu
+// (config/hooks/install/*.hook.chroot w installHooksDir).
+const (
+	postInstallDir  = "/usr/lib/hackeros-installer"
+	postInstallPath = postInstallDir + "/post-install"
+	installHooksDir = postInstallDir + "/hooks"
+
+	// postInstallLog to log hookow i komend post_install w systemie
+	// docelowym -- to samo wyjscie trafia do logu Calamares.
+	postInstallLog = "/var/log/hackeros-post-install.log"
+)
+
+// copyInstallHooks kopiuje hooki instalacji do installHooksDir w rootfsDir.
+func copyInstallHooks(rootfsDir string, hooks []liveparse.HookScript) error {
+	dir := filepath.Join(rootfsDir, strings.TrimPrefix(installHooksDir, "/"))
+	if err := os.MkdirAll(dir, 0o755); err != nil {
+		return err
+	}
+	for _, h := range hooks {
+		data, err := os.ReadFile(h.Path)
+		if err != nil {
+			return fmt.Errorf("odczyt %s: %w", h.Path, err)
+		}
+		if err := os.WriteFile(filepath.Join(dir, h.Name), data, 0o755); err != nil {
+			return fmt.Errorf("zapis hooka %s: %w", h.Name, err)
+		}
+	}
+	return nil
+}
+
+// postInstallScriptHead to poczatek skryptu post-install: srodowisko
+// komend, funkcja run i petla hookow instalacji. run wypisuje wyjscie
+// komendy na biezaco (log Calamares) i dopisuje je do postInstallLog;
+// kod wyjscia przechodzi przez plik, bo sh nie ma pipefail.
+const postInstallScriptHead = `#!/bin/sh
+# Wygenerowane przez hackeros-builder: hooki instalacji (config/hooks/install)
+# i komendy [installer] -> post_install, w chroot systemu docelowego.
+# Uzycie: post-install <login uzytkownika>
+log=%s
+fail_fast=%d
+failed=0
+rc_file=$(mktemp)
+
+export PATH=/usr/local/sbin:/usr/local/bin:/usr/sbin:/usr/bin:/sbin:/bin
+export HOME=/root LANG=C.UTF-8 DEBIAN_FRONTEND=noninteractive
+export HACKEROS_USER="$1"
+
+run() {
+    name=$1
+    shift
+    echo "== $name ($(date '+%%F %%T'))" | tee -a "$log"
+    { "$@"; echo $? >"$rc_file"; } 2>&1 | tee -a "$log"
+    rc=$(cat "$rc_file")
+    [ "$rc" = 0 ] && return 0
+    echo "$name zakonczony kodem $rc" | tee -a "$log" >&2
+    failed=1
+    if [ "$fail_fast" = 1 ]; then
+        rm -f "$rc_file"
+        exit 1
+    fi
+}
+
+for h in %s/*.hook.chroot; do
+    [ -e "$h" ] || continue
+    run "hook $h" "$h"
+done
+`
+
+// postInstallScript generuje skrypt post-install: hooki instalacji
+// (kolejnosc nazw), potem komendy post_install (kolejnosc z config.hk),
+// kazda przez "sh -c". Przy PostInstallContinueOnError bledy sa tylko
+// logowane i skrypt konczy sie kodem 0.
+func postInstallScript(opts config.InstallerConfig) string {
+	failFast := 1
+	if opts.PostInstallContinueOnError {
+		failFast = 0
+	}
+	var b strings.Builder
+	fmt.Fprintf(&b, postInstallScriptHead, postInstallLog, failFast, installHooksDir)
+	for i, cmd := range opts.PostInstall {
+		fmt.Fprintf(&b, "run 'post_install %d' sh -c %s\n", i+1, sandbox.ShellQuote(cmd))
+	}
+	b.WriteString(`rm -f "$rc_file"
+if [ "$failed" = 1 ]; then
+    echo "UWAGA: czesc komend po instalacji zakonczyla sie bledem -- patrz $log" >&2
+fi
+exit 0
+`)
+	return b.String()
+}
+
+// writePostInstallScript zapisuje postInstallScript do rootfsDir.
+func writePostInstallScript(rootfsDir string, opts config.InstallerConfig) error {
+	path := filepath.Join(rootfsDir, strings.TrimPrefix(postInstallPath, "/"))
+	if err := os.MkdirAll(filepath.Dir(path), 0o755); err != nil {
+		return err
+	}
+	return os.WriteFile(path, []byte(postInstallScript(opts)), 0o755)
+}
+
+// targetVerifyPath to sciezka skryptu weryfikacji docelowego systemu.
+// Calamares kopiuje live rootfs 1:1, wiec skrypt jest tez w systemie
+// docelowym i shellprocess uruchamia go w chroot.
+const targetVerifyPath = "/usr/local/sbin/hackeros-verify-target"
+
+// targetVerifyScript sprawdza najczestsze "ciche" awarie instalacji, po
+// ktorych Calamares melduje sukces, a system nie wstaje po restarcie:
+// brak initramfs, grub.cfg bez UUID partycji root, wpisy fstab wskazujace
+// na nieistniejace UUID, menedzer logowania bez binarki lub bez sesji,
+// sterownik NVIDIA bez modulu dla jadra, katalogi domowe z prawami innymi
+// niz HOME_MODE (UWAGA), konsola szeregowa bez console= w bootloaderze.
+// Wypisuje liste [OK]/[BLAD] (trafia do logu Calamares i do okna bledu).
+// Brak wpisu EFI w NVRAM to tylko UWAGA -- installEFIFallback zapisuje
+// EFI/BOOT/BOOTX64.EFI, czesc plyt ignoruje zapis do NVRAM.
+const targetVerifyScript = `#!/bin/sh
+# Wygenerowane przez hackeros-builder: weryfikacja systemu docelowego.
+# Uzycie: hackeros-verify-target <wpis-efi> [grub|systemd-boot]
+efi_id="$1"
+bootloader="${2:-grub}"
+failed=0
+
+ok()   { echo "[OK]    $*"; }
+bad()  { echo "[BLAD]  $*"; failed=1; }
+warn() { echo "[UWAGA] $*"; }
+
+initrd_found=0
+for f in /boot/initrd.img-*; do
+    [ -s "$f" ] && initrd_found=1
+done
+if [ "$initrd_found" = 1 ]; then ok "initramfs w /boot"; else bad "brak (lub pusty) /boot/initrd.img-*"; fi
+
+if [ "$bootloader" = systemd-boot ]; then
+    root_uuid=$(findmnt -no UUID /)
+    if [ -z "$root_uuid" ]; then
+        bad "nie mozna ustalic UUID partycji root (findmnt)"
+    elif grep -qs "root=UUID=$root_uuid" /boot/efi/loader/entries/*.conf; then
+        ok "wpisy systemd-boot wskazuja root UUID=$root_uuid"
+    else
+        bad "brak wpisu w /boot/efi/loader/entries z root UUID=$root_uuid"
+    fi
+else
+    root_uuid=$(grub-probe --target=fs_uuid / 2>/dev/null)
+    if [ -z "$root_uuid" ]; then
+        bad "nie mozna ustalic UUID partycji root (grub-probe)"
+    elif grep -q "$root_uuid" /boot/grub/grub.cfg 2>/dev/null; then
+        ok "grub.cfg wskazuje root UUID=$root_uuid"
+    else
+        bad "/boot/grub/grub.cfg nie zawiera root UUID=$root_uuid"
+    fi
+    # [grub] -> recovery => true (drop-in z rootfs/grub.go).
+    if grep -qs '^GRUB_DISABLE_RECOVERY=false' /etc/default/grub.d/hackeros.cfg; then
+        n=$(grep -c "^[[:space:]]*menuentry .*(recovery mode)" /boot/grub/grub.cfg 2>/dev/null)
+        if [ "${n:-0}" -gt 0 ]; then
+            ok "grub.cfg: pozycje recovery mode: $n (Advanced options)"
+        else
+            warn "grub.cfg bez pozycji recovery mode mimo [grub] -> recovery"
+        fi
+    fi
+fi
+
+# [system] -> nvidia (rootfs/nvidia.go; bez GPU NVIDIA instalator usuwa
+# plik razem ze sterownikiem).
+nvidia_conf=/etc/modprobe.d/hackeros-nvidia.conf
+if [ -e "$nvidia_conf" ]; then
+    choice=$(sed -n 's/^# hackeros-builder: \[system\] -> nvidia => //p' "$nvidia_conf")
+    missing=""
+    for k in /lib/modules/*; do
+        [ -e "$k/kernel" ] || continue
+        ls "$k"/updates/dkms/nvidia.ko* >/dev/null 2>&1 || missing="$missing $(basename "$k")"
+    done
+    if [ -n "$missing" ]; then
+        bad "sterownik NVIDIA ($choice): brak modulu nvidia dla jader:$missing"
+    else
+        ok "sterownik NVIDIA: $choice (nvidia-drm modeset=1, early KMS)"
+    fi
+fi
+
+# [system] -> login_defs: HOME_MODE z presetu musi obowiazywac juz dla
+# konta z instalatora (useradd czyta login.defs).
+home_mode=$(sed -n 's/^HOME_MODE[[:space:]]*0*\([0-7]\{3\}\).*/\1/p' /etc/login.defs)
+if [ -n "$home_mode" ]; then
+    for h in /home/*; do
+        [ -d "$h" ] || continue
+        mode=$(stat -c %a "$h")
+        if [ "$mode" = "$home_mode" ]; then
+            ok "$h: prawa $mode (HOME_MODE, UMASK $(sed -n 's/^UMASK[[:space:]]*//p' /etc/login.defs))"
+        else
+            warn "$h: prawa $mode, login.defs HOME_MODE $home_mode"
+        fi
+    done
+fi
+
+# [installer] -> serial_console (serialConsoleCommand).
+if [ -e /etc/systemd/system/getty.target.wants/serial-getty@ttyS0.service ]; then
+    if grep -qs 'console=ttyS0' /boot/grub/grub.cfg /boot/efi/loader/entries/*.conf; then
+        ok "konsola szeregowa: serial-getty@ttyS0, console=ttyS0 w bootloaderze"
+    else
+        bad "serial-getty@ttyS0 wlaczony, ale bootloader nie ma console=ttyS0"
+    fi
+fi
+
+fstab_ok=1
+for uuid in $(sed -n 's/^[[:space:]]*UUID=\([^[:space:]]*\).*/\1/p' /etc/fstab); do
+    if [ ! -e "/dev/disk/by-uuid/$uuid" ]; then
+        bad "fstab: UUID=$uuid nie istnieje"
+        fstab_ok=0
+    fi
+done
+[ "$fstab_ok" = 1 ] && ok "wpisy UUID w /etc/fstab istnieja"
+
+# Sesja graficzna: tylko gdy obraz ma menedzer logowania
+# (display-manager.service). Sprawdzamy binarke z ExecStart (istnieje,
+# wykonywalna, niepusta -- lapie np. strone HTML 404 zapisana zamiast
+# pliku) i co najmniej jedna sesje X11/Wayland do wyboru.
+dm_unit=/etc/systemd/system/display-manager.service
+if [ -e "$dm_unit" ]; then
+    dm_bin=$(sed -n 's/^ExecStart=-\{0,1\}\([^[:space:]]*\).*/\1/p' "$dm_unit" | head -n 1)
+    if [ -n "$dm_bin" ] && [ -x "$dm_bin" ] && [ -s "$dm_bin" ]; then
+        ok "menedzer logowania: $dm_bin"
+    else
+        bad "display-manager.service wskazuje na brakujacy/pusty plik: ${dm_bin:-?}"
+    fi
+    sessions=0
+    for f in /usr/share/xsessions/*.desktop /usr/share/wayland-sessions/*.desktop; do
+        [ -e "$f" ] && sessions=$((sessions + 1))
+    done
+    if [ "$sessions" -gt 0 ]; then
+        ok "sesje graficzne: $sessions"
+    else
+        bad "brak plikow sesji w /usr/share/xsessions i /usr/share/wayland-sessions"
+    fi
+fi
+
+if [ -d /sys/firmware/efi ]; then
+    if efibootmgr 2>/dev/null | grep -qF "$efi_id"; then
+        ok "wpis EFI $efi_id w efibootmgr"
+    else
+        warn "brak wpisu EFI $efi_id w efibootmgr (zostaje fallback EFI/BOOT/BOOTX64.EFI)"
+    fi
+fi
+
+exit "$failed"
 `
 
+// writeTargetVerifyScript zapisuje targetVerifyScript do rootfsDir.
+func writeTargetVerifyScript(rootfsDir string) error {
+	path := filepath.Join(rootfsDir, strings.TrimPrefix(targetVerifyPath, "/"))
+	if err := os.MkdirAll(filepath.Dir(path), 0o755); err != nil {
+		return err
+	}
+	return os.WriteFile(path, []byte(targetVerifyScript), 0o755)
+}
+
 const calamaresBrandingDesc = `---
 componentName:  hackeros
 
@@ -0,0 +1,508 @@
+package isobuild
+
+import (
+	"os"
+	"path/filepath"
+	"strings"
+	"testing"
+
+	"github.com/HackerOS-Linux-System/hackeros-builder/internal/config"
+)
+
+func TestShellprocessCommands_VerifyThenCleanup(t *testing.T) {
+	cmds := shellprocessCommands(config.InstallerConfig{ForcePasswordChange: true}, true)
+	verify := cmds[len(cmds)-2]
+	if verify != targetVerifyPath+" 'HackerOS'" {
+		t.Errorf("oczekiwano weryfikacji jako przedostatniej komendy, otrzymano %q", verify)
+	}
+	if last := cmds[len(cmds)-1]; last != liveCleanupCommand() {
+		t.Errorf("oczekiwano sprzatania nosnika live jako ostatniej komendy, otrzymano %q", last)
+	}
+	for _, want := range []string{"chage -d 0", postInstallPath + ` "${USER}"`} {
+		found := false
+		for _, c := range cmds {
+			if strings.Contains(c, want) {
+				found = true
+			}
+		}
+		if !found {
+			t.Errorf("brak komendy zawierajacej %q", want)
+		}
+	}
+}
+
+// Calamares podstawia ${...} w komendach shellprocess przed przekazaniem ich
+// do sh -- poza zamierzonym ${USER} zadna komenda nie moze tego zawierac.
+func TestShellprocessCommands_NoCalamaresSubstitution(t *testing.T) {
+	on := true
+	all := config.InstallerConfig{
+		UserUID:             1500,
+		UserGID:             1500,
+		ForcePasswordChange: true,
+		KeyboardSecondary:   "us",
+		KeyboardToggle:      config.DefaultKeyboardToggle,
+		LanguagePacks:       []string{"pl", "de"},
+		BtrfsSnapshots:      true,
+		DisableServices:     []string{"cups.service"},
+		MaskServices:        []string{"avahi-daemon.socket"},
+		TTYAutologin:        true,
+	}
+	sdb := all
+	sdb.Bootloader = config.BootloaderSystemdBoot
+	sdb.SerialConsole = &on
+	sdb.SkipAptSnapshots = true
+
+	for _, opts := range []config.InstallerConfig{all, sdb} {
+		for _, c := range shellprocessCommands(opts, true) {
+			if strings.Contains(strings.ReplaceAll(c, "${USER}", ""), "${") {
+				t.Errorf("Calamares podstawia ${...} -- komenda nie moze go zawierac: %q", c)
+			}
+		}
+	}
+}
+
+func TestPostInstallScript(t *testing.T) {
+	script := postInstallScript(config.InstallerConfig{
+		PostInstall: []string{"systemctl enable ssh", "echo 'witaj' > /etc/motd"},
+	})
+	for _, want := range []string{
+		"fail_fast=1\n",
+		"for h in " + installHooksDir + "/*.hook.chroot; do",
+		"run 'post_install 1' sh -c 'systemctl enable ssh'\n",
+		`run 'post_install 2' sh -c 'echo '\''witaj'\'' > /etc/motd'` + "\n",
+	} {
+		if !strings.Contains(script, want) {
+			t.Errorf("brak %q w:\n%s", want, script)
+		}
+	}
+	if strings.Index(script, "for h in") > strings.Index(script, "post_install 1") {
+		t.Error("hooki instalacji powinny byc przed komendami post_install")
+	}
+
+	script = postInstallScript(config.InstallerConfig{PostInstallContinueOnError: true})
+	if !strings.Contains(script, "fail_fast=0\n") {
+		t.Errorf("post_install_fail_fast => false: oczekiwano fail_fast=0:\n%s", script)
+	}
+}
+
+func TestLiveCleanupCommand(t *testing.T) {
+	cmd := liveCleanupCommand()
+	for _, want := range []string{
+		"'/etc/systemd/system/getty@tty1.service.d/autologin.conf'",
+		"'" + targetVerifyPath + "'",
+		"/root/.bash_profile",
+		"/etc/systemd/system/default.target",
+	} {
+		if !strings.Contains(cmd, want) {
+			t.Errorf("brak %q w komendzie sprzatania: %q", want, cmd)
+		}
+	}
+	if !strings.HasSuffix(cmd, "; true") {
+		t.Errorf("sprzatanie musi byc best-effort (konczyc sie kodem 0): %q", cmd)
+	}
+}
+
+func TestShellprocessCommands_TTYAutologinAfterCleanup(t *testing.T) {
+	cmds := shellprocessCommands(config.InstallerConfig{TTYAutologin: true}, false)
+	if last := cmds[len(cmds)-1]; last != ttyAutologinCommand {
+		t.Errorf("oczekiwano autologinu tty1 po sprzataniu nosnika, otrzymano %q", last)
+	}
+	if cleanup := cmds[len(cmds)-2]; cleanup != liveCleanupCommand() {
+		t.Errorf("oczekiwano sprzatania przed autologinem, otrzymano %q", cleanup)
+	}
+	for _, c := range shellprocessCommands(config.InstallerConfig{}, false) {
+		if c == ttyAutologinCommand {
+			t.Error("autologin tty1 bez tty_autologin => true")
+		}
+	}
+}
+
+func TestShellprocessCommands_VMGuestTools(t *testing.T) {
+	has := func(opts config.InstallerConfig) bool {
+		for _, c := range shellprocessCommands(opts, false) {
+			if strings.Contains(c, "systemd-detect-virt --vm") {
+				return true
+			}
+		}
+		return false
+	}
+	if !has(config.InstallerConfig{}) {
+		t.Error("brak wyboru narzedzi goscia VM przy domyslnej konfiguracji")
+	}
+	if has(config.InstallerConfig{SkipVMGuestTools: true}) {
+		t.Error("vm_guest_tools => false nie powinno generowac komendy")
+	}
+
+	cmd := vmGuestToolsCommand()
+	if !strings.Contains(cmd, `vmware) keep="open-vm-tools";;`) ||
+		!strings.Contains(cmd, "for p in qemu-guest-agent spice-vdagent open-vm-tools hyperv-daemons;") {
+		t.Errorf("niepoprawna komenda narzedzi goscia: %q", cmd)
+	}
+}
+
+func TestShellprocessCommands_KeyboardSecondary(t *testing.T) {
+	cmds := shellprocessCommands(config.InstallerConfig{
+		KeyboardSecondary: "us", KeyboardToggle: "grp:win_space_toggle",
+	}, false)
+	for _, c := range cmds {
+		if strings.Contains(c, "/etc/default/keyboard") {
+			if !strings.Contains(c, `XKBLAYOUT="$XKBLAYOUT,us"`) || !strings.Contains(c, "XKBOPTIONS=grp:win_space_toggle") {
+				t.Errorf("niepoprawna komenda klawiatury: %q", c)
+			}
+			return
+		}
+	}
+	t.Error("brak komendy ustawiajacej drugi uklad klawiatury")
+}
+
+func TestShellprocessCommands_AptSnapshots(t *testing.T) {
+	snapperCmd := func(opts config.InstallerConfig) string {
+		for _, c := range shellprocessCommands(opts, false) {
+			if strings.Contains(c, "create-config") {
+				return c
+			}
+		}
+		t.Fatal("brak komendy snappera")
+		return ""
+	}
+
+	on := snapperCmd(config.InstallerConfig{BtrfsSnapshots: true})
+	if !strings.Contains(on, snapperAptHookPath) || !strings.Contains(on, `SUBVOLUME="/"`) {
+		t.Errorf("brak weryfikacji hooka apt: %q", on)
+	}
+	off := snapperCmd(config.InstallerConfig{BtrfsSnapshots: true, SkipAptSnapshots: true})
+	if !strings.Contains(off, "DISABLE_APT_SNAPSHOT=yes") {
+		t.Errorf("apt_snapshots => false nie wylacza hooka: %q", off)
+	}
+}
+
+func TestShellprocessCommands_GrubBtrfsMenu(t *testing.T) {
+	for _, c := range shellprocessCommands(config.InstallerConfig{BtrfsSnapshots: true}, false) {
+		if strings.Contains(c, "create-config") {
+			if !strings.Contains(c, "systemctl enable grub-btrfsd") || !strings.Contains(c, "/boot/grub/grub-btrfs.cfg") {
+				t.Errorf("brak grub-btrfsd/sprawdzenia menu snapshotow: %q", c)
+			}
+			return
+		}
+	}
+	t.Fatal("brak komendy snappera")
+}
+
+func TestShellprocessCommands_SystemdBoot(t *testing.T) {
+	opts := config.InstallerConfig{Bootloader: config.BootloaderSystemdBoot, BtrfsSnapshots: true}
+	cmds := shellprocessCommands(opts, false)
+	if verify := cmds[len(cmds)-2]; verify != targetVerifyPath+" 'Linux Boot Manager' systemd-boot" {
+		t.Errorf("weryfikacja systemd-boot: otrzymano %q", verify)
+	}
+	found := false
+	for _, c := range cmds {
+		if strings.Contains(c, "update-grub") {
+			t.Errorf("update-grub przy systemd-boot: %q", c)
+		}
+		if strings.Contains(c, "bootctl install") && strings.Contains(c, "kernel-install add") {
+			found = true
+		}
+	}
+	if !found {
+		t.Error("brak komendy bootctl/kernel-install")
+	}
+	for _, m := range grubOnlyModules {
+		if conf := calamaresSettingsConf(opts); strings.Contains(conf, "- "+m+"\n") {
+			t.Errorf("modul %s w sekwencji przy systemd-boot:\n%s", m, conf)
+		}
+		if conf := calamaresSettingsConf(config.InstallerConfig{}); !strings.Contains(conf, "      - "+m+"\n") {
+			t.Errorf("brak modulu %s przy GRUB:\n%s", m, conf)
+		}
+	}
+}
+
+func TestCalamaresConf_EncryptedBoot(t *testing.T) {
+	conf := calamaresSettingsConf(config.InstallerConfig{})
+	want := "      - grubcfg\n      - bootloader\n      - luksbootkeyfile\n      - initramfscfg\n      - initramfs\n"
+	if !strings.Contains(conf, want) {
+		t.Errorf("oczekiwano sekwencji %q w:\n%s", want, conf)
+	}
+	if p := calamaresPartitionConf(config.InstallerConfig{}); !strings.Contains(p, "luksGeneration: luks1\n") {
+		t.Errorf("GRUB wymaga LUKS1:\n%s", p)
+	}
+	if p := calamaresPartitionConf(config.InstallerConfig{Bootloader: config.BootloaderSystemdBoot}); !strings.Contains(p, "luksGeneration: luks2\n") {
+		t.Errorf("przy systemd-boot oczekiwano LUKS2:\n%s", p)
+	}
+}
+
+func TestCalamaresShellprocessConf_QuotesCommands(t *testing.T) {
+	conf := calamaresShellprocessConf(config.InstallerConfig{}, false)
+	want := `    - command: "` + targetVerifyPath + ` 'HackerOS'"`
+	if !strings.Contains(conf, want) {
+		t.Errorf("oczekiwano linii %q w:\n%s", want, conf)
+	}
+	if strings.Contains(conf, postInstallPath) {
+		t.Error("skrypt post-install nie powinien byc uruchamiany bez hookow i post_install")
+	}
+	if got := yamlQuote(`a "b" \c`); got != `"a \"b\" \\c"` {
+		t.Errorf("yamlQuote: otrzymano %s", got)
+	}
+}
+
+func TestCalamaresMountConf_PerFilesystemOptions(t *testing.T) {
+	conf := calamaresMountConf(config.InstallerConfig{
+		MountOptions: map[string][]string{"ext4": {"noatime", "commit=60"}},
+	})
+	for _, want := range []string{
+		"    - filesystem: ext4\n      options: [ noatime, commit=60 ]\n",
+		"    - filesystem: btrfs\n      options: [ defaults ]\n      ssdOptions: [ \"discard=async\" ]\n",
+	} {
+		if !strings.Contains(conf, want) {
+			t.Errorf("brak fragmentu %q w:\n%s", want, conf)
+		}
+	}
+	if strings.Contains(conf, "filesystem: xfs") {
+		t.Errorf("xfs bez opcji nie powinien miec wpisu:\n%s", conf)
+	}
+}
+
+func TestShellprocessCommands_MicrocodeBeforeSystemdBoot(t *testing.T) {
+	cmds := shellprocessCommands(config.InstallerConfig{Bootloader: config.BootloaderSystemdBoot}, false)
+	mc, sdb := -1, -1
+	for i, c := range cmds {
+		switch c {
+		case microcodeCommand:
+			mc = i
+		case systemdBootCommand:
+			sdb = i
+		}
+	}
+	if mc < 0 || sdb < 0 || mc > sdb {
+		t.Errorf("oczekiwano mikrokodu przed systemd-boot (mikrokod %d, systemd-boot %d)", mc, sdb)
+	}
+}
+
+func TestShellprocessCommands_NvidiaBeforeSystemdBoot(t *testing.T) {
+	cmds := shellprocessCommands(config.InstallerConfig{Bootloader: config.BootloaderSystemdBoot}, false)
+	nv, sdb := -1, -1
+	for i, c := range cmds {
+		switch c {
+		case nvidiaCommand:
+			nv = i
+		case systemdBootCommand:
+			sdb = i
+		}
+	}
+	if nv < 0 || sdb < 0 || nv > sdb {
+		t.Errorf("oczekiwano sterownika NVIDIA przed systemd-boot (NVIDIA %d, systemd-boot %d)", nv, sdb)
+	}
+}
+
+func TestSerialConsoleCommand(t *testing.T) {
+	on, off := true, false
+	has := func(opts config.InstallerConfig) bool {
+		for _, c := range shellprocessCommands(opts, false) {
+			if strings.Contains(c, "serial-getty@ttyS0") {
+				return true
+			}
+		}
+		return false
+	}
+	if !has(config.InstallerConfig{}) || !has(config.InstallerConfig{SerialConsole: &on}) {
+		t.Error("oczekiwano komendy konsoli szeregowej dla auto i true")
+	}
+	if has(config.InstallerConfig{SerialConsole: &off}) {
+		t.Error("serial_console => false: komenda konsoli szeregowej nie powinna byc dodana")
+	}
+
+	auto := serialConsoleCommand(config.InstallerConfig{})
+	if !strings.Contains(auto, "/sys/class/tty/console/active") || !strings.Contains(auto, "grub.d/hackeros-serial.cfg") {
+		t.Errorf("auto + GRUB: oczekiwano wykrywania ttyS0 i drop-inu GRUB: %q", auto)
+	}
+	sdb := serialConsoleCommand(config.InstallerConfig{SerialConsole: &on, Bootloader: config.BootloaderSystemdBoot})
+	if strings.Contains(sdb, "console/active") || !strings.Contains(sdb, "/boot/efi/loader/entries/*.conf") {
+		t.Errorf("true + systemd-boot: oczekiwano wpisow systemd-boot bez wykrywania: %q", sdb)
+	}
+}
+
+func TestResolveServiceUnits(t *testing.T) {
+	rootfs := t.TempDir()
+	unitDir := filepath.Join(rootfs, "usr", "lib", "systemd", "system")
+	if err := os.MkdirAll(unitDir, 0o755); err != nil {
+		t.Fatal(err)
+	}
+	for _, u := range []string{"cups.service", "cups.socket", "cups.path", "bluetooth.service", "avahi-daemon.socket"} {
+		if err := os.WriteFile(filepath.Join(unitDir, u), []byte("[Unit]\n"), 0o644); err != nil {
+			t.Fatal(err)
+		}
+	}
+
+	got, err := resolveServiceUnits(rootfs, []string{"cups", "bluetooth.service", "avahi-daemon.socket"})
+	if err != nil {
+		t.Fatal(err)
+	}
+	want := []string{"cups.service", "cups.socket", "cups.path", "bluetooth.service", "avahi-daemon.socket"}
+	if strings.Join(got, " ") != strings.Join(want, " ") {
+		t.Errorf("oczekiwano %v, otrzymano %v", want, got)
+	}
+
+	for _, bad := range []string{"avahi-daemon.service", "modemmanager"} {
+		if _, err := resolveServiceUnits(rootfs, []string{bad}); err == nil {
+			t.Errorf("%s: brak jednostki w obrazie, oczekiwano bledu", bad)
+		}
+	}
+}
+
+func TestServicesCommand(t *testing.T) {
+	opts := config.InstallerConfig{DisableServices: []string{"cups.service", "cups.socket"}, MaskServices: []string{"avahi-daemon.socket"}}
+	cmds := shellprocessCommands(opts, true)
+	svc, post := -1, -1
+	for i, c := range cmds {
+		if strings.HasPrefix(c, "systemctl disable") {
+			svc = i
+		}
+		if strings.HasPrefix(c, postInstallPath) {
+			post = i
+		}
+	}
+	if svc < 0 || post < 0 || svc > post {
+		t.Fatalf("oczekiwano uslug przed post-install (uslugi %d, post-install %d)", svc, post)
+	}
+	want := "systemctl disable 'cups.service' 'cups.socket' && systemctl mask 'avahi-daemon.socket' && " +
+		"for u in 'cups.service' 'cups.socket' 'avahi-daemon.socket'; do "
+	if !strings.HasPrefix(cmds[svc], want) {
+		t.Errorf("oczekiwano prefiksu %q, otrzymano %q", want, cmds[svc])
+	}
+}
+
+func TestCalamaresMountConf_BtrfsLayout(t *testing.T) {
+	simple := calamaresMountConf(config.InstallerConfig{})
+	if strings.Contains(simple, "/@snapshots") {
+		t.Errorf("uklad domyslny nie powinien miec @snapshots:\n%s", simple)
+	}
+
+	full := calamaresMountConf(config.InstallerConfig{BtrfsLayout: config.BtrfsLayoutFull})
+	want := "btrfsSubvolumes:\n" +
+		"    - mountPoint: /\n      subvolume: /@\n" +
+		"    - mountPoint: /home\n      subvolume: /@home\n" +
+		"    - mountPoint: /root\n      subvolume: /@root\n" +
+		"    - mountPoint: /srv\n      subvolume: /@srv\n" +
+		"    - mountPoint: /var/log\n      subvolume: /@var@log\n" +
+		"    - mountPoint: /var/cache\n      subvolume: /@var@cache\n" +
+		"    - mountPoint: /var/tmp\n      subvolume: /@var@tmp\n" +
+		"    - mountPoint: /tmp\n      subvolume: /@tmp\n" +
+		"    - mountPoint: /.snapshots\n      subvolume: /@snapshots\n" +
+		"mountOptions:\n"
+	if !strings.Contains(full, want) {
+		t.Errorf("brak pelnego ukladu podwolumenow w:\n%s", full)
+	}
+	if strings.Contains(full, "mountPoint: /var\n") {
+		t.Errorf("/var nie moze byc osobnym podwolumenem (dpkg):\n%s", full)
+	}
+}
+
+func TestCalamaresPartitionConf_Layout(t *testing.T) {
+	conf := calamaresPartitionConf(config.InstallerConfig{RootFilesystem: "btrfs"})
+	want := "partitionLayout:\n    - name: \"root\"\n      filesystem: \"btrfs\"\n      mountPoint: \"/\"\n      size: 100%\n"
+	if !strings.Contains(conf, want) {
+		t.Errorf("oczekiwano ukladu %q w:\n%s", want, conf)
+	}
+	if strings.Contains(conf, "/home") {
+		t.Errorf("bez separate_home nie powinno byc /home:\n%s", conf)
+	}
+}
+
+func TestCalamaresMountConf_ExtraMounts(t *testing.T) {
+	conf := calamaresMountConf(config.InstallerConfig{})
+	for _, want := range []string{`mountPoint: "/proc"`, `mountPoint: "/sys"`, `mountPoint: "/dev"`} {
+		if !strings.Contains(conf, want) {
+			t.Errorf("brak %s w extraMounts:\n%s", want, conf)
+		}
+	}
+}
+
+func TestCalamaresPartitionConf_SeparateVarLog(t *testing.T) {
+	opts := config.InstallerConfig{SeparateVarLog: true, VarLogSize: config.PartitionSize{Value: 8, Unit: "GiB"}}
+	conf := calamaresPartitionConf(opts)
+	want := "    - name: \"varlog\"\n      filesystem: \"unknown\"\n      mountPoint: \"/var/log\"\n      size: 8GiB\n"
+	if !strings.Contains(conf, want) {
+		t.Errorf("oczekiwano partycji /var/log %q w:\n%s", want, conf)
+	}
+	if mount := calamaresMountConf(opts); strings.Contains(mount, "@var@log") {
+		t.Errorf("przy osobnej partycji /var/log nie powinno byc podwolumenu @var@log:\n%s", mount)
+	}
+}
+
+func TestCalamaresPartitionConf_Swap(t *testing.T) {
+	conf := calamaresPartitionConf(config.InstallerConfig{SwapSize: config.PartitionSize{Value: 4, Unit: "GiB"}})
+	want := "    - name: \"swap\"\n      filesystem: \"linuxswap\"\n      size: 4GiB\n"
+	if !strings.Contains(conf, want) {
+		t.Errorf("oczekiwano partycji swap %q w:\n%s", want, conf)
+	}
+	if !strings.Contains(conf, "userSwapChoices:\n    - none\ndefaultFileSystemType") {
+		t.Errorf("przy swap_size jedyny wybor swapu to none:\n%s", conf)
+	}
+	if conf := calamaresPartitionConf(config.InstallerConfig{}); !strings.Contains(conf, "    - suspend\n") {
+		t.Errorf("bez swap_size oczekiwano wyborow swapu Calamares:\n%s", conf)
+	}
+}
+
+func TestLanguagePackages(t *testing.T) {
+	root := t.TempDir()
+	info := filepath.Join(root, "var", "lib", "dpkg", "info")
+	if err := os.MkdirAll(info, 0o755); err != nil {
+		t.Fatal(err)
+	}
+	if err := os.WriteFile(filepath.Join(info, "firefox-esr.list"), nil, 0o644); err != nil {
+		t.Fatal(err)
+	}
+	installed := func(pkg string) bool { return dpkgInstalled(root, pkg) }
+
+	got := strings.Join(languagePackages("de", installed), " ")
+	if want := "hunspell-de-de firefox-esr-l10n-de"; got != want {
+		t.Errorf("oczekiwano %q (bez thunderbird/libreoffice), otrzymano %q", want, got)
+	}
+}
+
+func TestShellprocessCommands_LanguagePacks(t *testing.T) {
+	if cmds := shellprocessCommands(config.InstallerConfig{}, false); strings.Contains(strings.Join(cmds, "\n"), "/etc/default/locale") {
+		t.Error("bez language_packs komenda pakietow jezykowych nie powinna byc dodana")
+	}
+	cmds := shellprocessCommands(config.InstallerConfig{LanguagePacks: []string{"pl", "es"}}, false)
+	cmd := ""
+	for _, c := range cmds {
+		if strings.Contains(c, "/etc/default/locale") {
+			cmd = c
+		}
+	}
+	for _, want := range []string{
+		`pl) keep="hunspell-pl firefox-esr-l10n-pl thunderbird-l10n-pl libreoffice-l10n-pl";;`,
+		`es) keep="hunspell-es firefox-esr-l10n-es-es thunderbird-l10n-es-es libreoffice-l10n-es";;`,
+		"apt-get purge -y --auto-remove $remove",
+	} {
+		if !strings.Contains(cmd, want) {
+			t.Errorf("brak %q w komendzie: %q", want, cmd)
+		}
+	}
+}
+
+func TestCheckFilesystemTools(t *testing.T) {
+	root := t.TempDir()
+	for _, dir := range []string{"usr/sbin", "sbin"} {
+		if err := os.MkdirAll(filepath.Join(root, dir), 0o755); err != nil {
+			t.Fatal(err)
+		}
+	}
+	for _, name := range []string{"usr/sbin/mkfs.ext4", "sbin/mkfs.fat", "usr/sbin/mkfs.btrfs"} {
+		if err := os.WriteFile(filepath.Join(root, name), nil, 0o755); err != nil {
+			t.Fatal(err)
+		}
+	}
+
+	err := checkFilesystemTools(root)
+	if err == nil || !strings.Contains(err.Error(), "xfsprogs") || strings.Contains(err.Error(), "btrfs-progs") {
+		t.Errorf("oczekiwano bledu tylko dla xfsprogs, otrzymano %v", err)
+	}
+
+	if err := os.WriteFile(filepath.Join(root, "usr/sbin/mkfs.xfs"), nil, 0o755); err != nil {
+		t.Fatal(err)
+	}
+	if err := checkFilesystemTools(root); err != nil {
+		t.Errorf("komplet narzedzi: nieoczekiwany blad %v", err)
+	}
+}
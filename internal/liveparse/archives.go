@@ -0,0 +1,237 @@
+package liveparse
+
+import (
+	"bytes"
+	"crypto/sha1"
+	"crypto/sha256"
+	"encoding/base64"
+	"encoding/binary"
+	"encoding/hex"
+	"errors"
+	"fmt"
+	"hash"
+	"os"
+	"strings"
+)
+
+// Archive to jedno dodatkowe repozytorium apt z config/archives/.
+type Archive struct {
+	Name        string   // wspolna nazwa plikow, np. "docker"
+	Lines       []string // linie sources.list z <Name>.list.chroot
+	KeyPath     string   // <Name>.key.chroot (binarny albo ASCII-armored), "" gdy brak
+	Fingerprint string   // odcisk z <Name>.fingerprint.chroot, "" gdy brak
+
+	// KeyFingerprints to odciski kluczy (glownych i podkluczy) z KeyPath,
+	// wielkie litery hex bez spacji -- wypelniane przez checkKey.
+	KeyFingerprints []string
+}
+
+// KeyIsArmored zwraca true dla klucza ASCII-armored -- apt rozpoznaje
+// format po rozszerzeniu pliku w signed-by (.asc / .gpg).
+func (a Archive) KeyIsArmored() bool {
+	data, err := os.ReadFile(a.KeyPath)
+	return err == nil && bytes.Contains(data, []byte(armorBegin))
+}
+
+// checkSourceLine sprawdza skladnie jednowierszowej linii sources.list:
+// "deb|deb-src [opcje] URI suite [komponenty...]". Suite zakonczone "/"
+// (repo plaskie) nie ma komponentow, kazde inne wymaga co najmniej jednego.
+func checkSourceLine(line string) error {
+	fields := strings.Fields(line)
+	if len(fields) == 0 || (fields[0] != "deb" && fields[0] != "deb-src") {
+		return fmt.Errorf("linia %q: oczekiwano \"deb\" albo \"deb-src\" na poczatku", line)
+	}
+	rest := fields[1:]
+	if len(rest) > 0 && strings.HasPrefix(rest[0], "[") {
+		end := -1
+		for i, f := range rest {
+			if strings.HasSuffix(f, "]") {
+				end = i
+				break
+			}
+		}
+		if end < 0 {
+			return fmt.Errorf("linia %q: niezamkniety nawias opcji [ ... ]", line)
+		}
+		rest = rest[end+1:]
+	}
+	if len(rest) < 2 {
+		return fmt.Errorf("linia %q: oczekiwano URI i suite", line)
+	}
+	if !strings.Contains(rest[0], ":") {
+		return fmt.Errorf("linia %q: %q to nie URI (np. https://...)", line, rest[0])
+	}
+	flat := strings.HasSuffix(rest[1], "/")
+	if flat && len(rest) > 2 {
+		return fmt.Errorf("linia %q: suite %q konczy sie \"/\" (repo plaskie) -- bez komponentow", line, rest[1])
+	}
+	if !flat && len(rest) < 3 {
+		return fmt.Errorf("linia %q: brak komponentow po suite %q (np. main)", line, rest[1])
+	}
+	return nil
+}
+
+// checkKey odczytuje klucz repo (jesli jest) i wypelnia KeyFingerprints.
+// Plik bez zadnego klucza publicznego (np. zapisana strona HTML) to blad;
+// gdy podano Fingerprint, klucz musi go miec.
+func (a *Archive) checkKey() error {
+	if a.KeyPath == "" {
+		if a.Fingerprint != "" {
+			return fmt.Errorf("config/archives/%s.fingerprint.chroot bez %s.key.chroot", a.Name, a.Name)
+		}
+		return nil
+	}
+	data, err := os.ReadFile(a.KeyPath)
+	if err != nil {
+		return fmt.Errorf("blad odczytu %s: %w", a.KeyPath, err)
+	}
+	fps, err := keyFingerprints(data)
+	if err != nil {
+		return fmt.Errorf("%s: %w", a.KeyPath, err)
+	}
+	if len(fps) == 0 {
+		return fmt.Errorf("%s: brak klucza publicznego OpenPGP", a.KeyPath)
+	}
+	a.KeyFingerprints = fps
+
+	if a.Fingerprint == "" {
+		return nil
+	}
+	want := strings.ToUpper(strings.ReplaceAll(a.Fingerprint, " ", ""))
+	for _, fp := range fps {
+		if fp == want {
+			return nil
+		}
+	}
+	return fmt.Errorf("%s: klucz nie ma odcisku %s z %s.fingerprint.chroot (odciski w pliku: %s)",
+		a.KeyPath, want, a.Name, strings.Join(fps, ", "))
+}
+
+const armorBegin = "-----BEGIN PGP PUBLIC KEY BLOCK-----"
+
+// dearmor zdejmuje ASCII armor (RFC 4880 6.2): naglowki do pustej linii,
+// dalej base64 do sumy kontrolnej "=XXXX" albo linii END.
+func dearmor(text string) ([]byte, error) {
+	_, block, ok := strings.Cut(text, armorBegin)
+	if !ok {
+		return nil, fmt.Errorf("brak %s", armorBegin)
+	}
+	lines := strings.Split(strings.TrimLeft(block, "\r\n"), "\n")
+	i := 0
+	for i < len(lines) && strings.Contains(lines[i], ": ") {
+		i++
+	}
+	var b64 strings.Builder
+	for _, l := range lines[i:] {
+		l = strings.TrimSpace(l)
+		if strings.HasPrefix(l, "=") || strings.HasPrefix(l, "-----END") {
+			break
+		}
+		b64.WriteString(l)
+	}
+	return base64.StdEncoding.DecodeString(b64.String())
+}
+
+// keyFingerprints zwraca odciski wszystkich kluczy publicznych i
+// podkluczy (pakiety 6 i 14) w kluczu binarnym albo ASCII-armored: v4 --
+// SHA-1 (40 znakow hex), v5/v6 -- SHA-256 (64 znaki).
+func keyFingerprints(data []byte) ([]string, error) {
+	if bytes.Contains(data, []byte(armorBegin)) {
+		raw, err := dearmor(string(data))
+		if err != nil {
+			return nil, fmt.Errorf("niepoprawny ASCII armor: %w", err)
+		}
+		data = raw
+	}
+
+	var fps []string
+	for len(data) > 0 {
+		tag, body, rest, err := nextPacket(data)
+		if err != nil {
+			return nil, err
+		}
+		data = rest
+		if (tag != 6 && tag != 14) || len(body) == 0 {
+			continue
+		}
+		// Odcisk to skrot prefiksu (bajt + dlugosc tresci) i tresci pakietu.
+		var h hash.Hash
+		var prefix []byte
+		switch body[0] {
+		case 4:
+			h, prefix = sha1.New(), []byte{0x99, 0, 0}
+			binary.BigEndian.PutUint16(prefix[1:], uint16(len(body)))
+		case 5, 6:
+			h, prefix = sha256.New(), []byte{0x9a, 0, 0, 0, 0}
+			if body[0] == 6 {
+				prefix[0] = 0x9b
+			}
+			binary.BigEndian.PutUint32(prefix[1:], uint32(len(body)))
+		default:
+			continue
+		}
+		h.Write(prefix)
+		h.Write(body)
+		fps = append(fps, strings.ToUpper(hex.EncodeToString(h.Sum(nil))))
+	}
+	return fps, nil
+}
+
+// nextPacket rozbiera naglowek pakietu OpenPGP (stary i nowy format, RFC
+// 4880 4.2) i zwraca jego tag, tresc i reszte danych.
+func nextPacket(data []byte) (tag byte, body, rest []byte, err error) {
+	truncated := errors.New("uciety pakiet OpenPGP")
+	if data[0]&0x80 == 0 {
+		return 0, nil, nil, errors.New("to nie jest klucz OpenPGP")
+	}
+	var n, hdr int
+	if data[0]&0x40 != 0 {
+		tag = data[0] & 0x3f
+		if len(data) < 2 {
+			return 0, nil, nil, truncated
+		}
+		switch l := data[1]; {
+		case l < 192:
+			n, hdr = int(l), 2
+		case l < 224:
+			if len(data) < 3 {
+				return 0, nil, nil, truncated
+			}
+			hi := int(l) - 192
+			n = hi*256 + int(data[2]) + 192
+			hdr = 3
+		case l == 255:
+			if len(data) < 6 {
+				return 0, nil, nil, truncated
+			}
+			n, hdr = int(binary.BigEndian.Uint32(data[2:6])), 6
+		default:
+			return 0, nil, nil, errors.New("dlugosc czesciowa w kluczu OpenPGP")
+		}
+	} else {
+		tag = (data[0] >> 2) & 0x0f
+		switch data[0] & 3 {
+		case 0:
+			if len(data) < 2 {
+				return 0, nil, nil, truncated
+			}
+			n, hdr = int(data[1]), 2
+		case 1:
+			if len(data) < 3 {
+				return 0, nil, nil, truncated
+			}
+			n, hdr = int(binary.BigEndian.Uint16(data[1:3])), 3
+		case 2:
+			if len(data) < 5 {
+				return 0, nil, nil, truncated
+			}
+			n, hdr = int(binary.BigEndian.Uint32(data[1:5])), 5
+		default:
+			n, hdr = len(data)-1, 1
+		}
+	}
+	if n < 0 || hdr+n > len(data) {
+		return 0, nil, nil, truncated
+	}
+	return tag, data[hdr : hdr+n], data[hdr+n:], nil
+}
@@ -0,0 +1,105 @@
+package liveparse
+
+import (
+	"os"
+	"path/filepath"
+	"strings"
+	"testing"
+)
+
+// testKey to klucz ed25519 (v4) wygenerowany przez gpg na potrzeby testow;
+// testKeyFingerprint -- odcisk wg "gpg --show-keys".
+const (
+	testKey = `-----BEGIN PGP PUBLIC KEY BLOCK-----
+
+mDMEatCZHhYJKwYBBAHaRw8BAQdAQ2xf/irNwJ0aCnEoWTKBN0k0Sj8zjcN1qhPX
+Ai1gLtm0JUhhY2tlck9TIFRlc3QgUmVwbyA8dGVzdEBleGFtcGxlLmNvbT6IkAQT
+FggAOBYhBLWoGaZ0L2fSeLGCPziR7Q/i/X+eBQJq0JkeAhsDBQsJCAcCBhUKCQgL
+AgQWAgMBAh4BAheAAAoJEDiR7Q/i/X+e8SYBAPzkUkNwqdWEtn+zj+ilEHbcxiuL
+bElmXxVKoaKotWe3AQDdtZxip0iZoAGw5PfivSz9rsbmp/frmKEeBTGevxIXBw==
+=R8bz
+-----END PGP PUBLIC KEY BLOCK-----
+`
+	testKeyFingerprint = "B5A819A6742F67D278B1823F3891ED0FE2FD7F9E"
+)
+
+func TestCheckSourceLine(t *testing.T) {
+	for _, ok := range []string{
+		"deb https://download.docker.com/linux/debian bookworm stable",
+		"deb [arch=amd64 signed-by=/etc/apt/keyrings/docker.asc] https://download.docker.com/linux/debian bookworm stable",
+		"deb-src http://deb.example.org/debian trixie main contrib",
+		"deb https://repo.example.org/flat ./",
+	} {
+		if err := checkSourceLine(ok); err != nil {
+			t.Errorf("%q: nieoczekiwany blad: %v", ok, err)
+		}
+	}
+	for _, bad := range []string{
+		"https://download.docker.com/linux/debian bookworm stable",
+		"deb [arch=amd64 https://download.docker.com/linux/debian bookworm stable",
+		"deb https://download.docker.com/linux/debian bookworm",
+		"deb download.docker.com bookworm stable",
+		"deb https://repo.example.org/flat ./ main",
+	} {
+		if err := checkSourceLine(bad); err == nil {
+			t.Errorf("%q: oczekiwano bledu skladni", bad)
+		}
+	}
+}
+
+func TestKeyFingerprints(t *testing.T) {
+	fps, err := keyFingerprints([]byte(testKey))
+	if err != nil {
+		t.Fatal(err)
+	}
+	if len(fps) != 1 || fps[0] != testKeyFingerprint {
+		t.Errorf("armored: oczekiwano [%s], otrzymano %v", testKeyFingerprint, fps)
+	}
+
+	raw, err := dearmor(testKey)
+	if err != nil {
+		t.Fatal(err)
+	}
+	if fps, err := keyFingerprints(raw); err != nil || len(fps) != 1 || fps[0] != testKeyFingerprint {
+		t.Errorf("binarny: oczekiwano [%s], otrzymano %v (%v)", testKeyFingerprint, fps, err)
+	}
+
+	if _, err := keyFingerprints([]byte("<html>404</html>")); err == nil {
+		t.Error("oczekiwano bledu dla pliku, ktory nie jest kluczem")
+	}
+}
+
+func TestParseArchives(t *testing.T) {
+	configDir := t.TempDir()
+	dir := filepath.Join(configDir, "archives")
+	if err := os.MkdirAll(dir, 0o755); err != nil {
+		t.Fatal(err)
+	}
+	write := func(name, content string) {
+		t.Helper()
+		if err := os.WriteFile(filepath.Join(dir, name), []byte(content), 0o644); err != nil {
+			t.Fatal(err)
+		}
+	}
+	write("docker.list.chroot", "# repo Dockera\ndeb https://download.docker.com/linux/debian bookworm stable\n")
+	write("docker.key.chroot", testKey)
+	write("docker.fingerprint.chroot", "B5A8 19A6 742F 67D2 78B1  823F 3891 ED0F E2FD 7F9E\n")
+	write("extra.list.chroot", "deb http://deb.example.org/debian bookworm main\n")
+
+	p := &Project{}
+	if err := p.parseArchives(configDir); err != nil {
+		t.Fatalf("parseArchives zwrocilo blad: %v", err)
+	}
+	if len(p.Archives) != 2 || p.Archives[0].Name != "docker" || p.Archives[1].Name != "extra" {
+		t.Fatalf("oczekiwano archiwow docker i extra, otrzymano %+v", p.Archives)
+	}
+	d := p.Archives[0]
+	if len(d.Lines) != 1 || !strings.HasSuffix(d.KeyPath, "docker.key.chroot") || !d.KeyIsArmored() {
+		t.Errorf("docker: niepelne archiwum %+v", d)
+	}
+
+	write("docker.fingerprint.chroot", "0000000000000000000000000000000000000000\n")
+	if err := (&Project{}).parseArchives(configDir); err == nil {
+		t.Error("oczekiwano bledu przy niezgodnym odcisku klucza")
+	}
+}
@@ -20,18 +20,20 @@ type Project struct {
 	// alfabetycznym nazwy pliku (tak jak live-build sortuje hooks/normal/).
 	Hooks []HookScript
 
+	// InstallHooks to skrypty z config/hooks/install/*.hook.chroot -- NIE sa
+	// wykonywane podczas budowy, tylko przez instalator (Calamares) w
+	// systemie docelowym, po skopiowaniu plikow. Ten sam porzadek co Hooks.
+	InstallHooks []HookScript
+
 	// IncludesChroot to sciezka do config/includes.chroot (lub "" jesli
 	// katalog nie istnieje) -- cala jego zawartosc jest kopiowana 1:1
 	// do korzenia rootfs PO instalacji pakietow, PRZED hooks.
 	IncludesChroot string
 
-	// ExtraSources to dodatkowe linie sources.list z config/archives/*.list.chroot.
-	ExtraSources []string
-
-	// ExtraKeys to sciezki do plikow kluczy GPG z config/archives/*.key.chroot,
-	// ktore trzeba zaimportowac przed apt-get update jesli ExtraSources
-	// odwoluje sie do repo spoza domyslnych kluczy Debiana.
-	ExtraKeys []string
+	// Archives to dodatkowe repozytoria apt z config/archives/ -- pliki
+	// <nazwa>.list.chroot, <nazwa>.key.chroot i <nazwa>.fingerprint.chroot
+	// grupowane po nazwie, w porzadku alfabetycznym.
+	Archives []Archive
 }
 
 // HookScript to pojedynczy skrypt hook.chroot do wykonania wewnatrz chroot.
@@ -60,6 +62,9 @@ func Parse(root string) (*Project, error) {
 	if err := p.parseHooks(configDir); err != nil {
 		return nil, err
 	}
+	if err := p.parseInstallHooks(configDir); err != nil {
+		return nil, err
+	}
 	p.parseIncludesChroot(configDir)
 	if err := p.parseArchives(configDir); err != nil {
 		return nil, err
@@ -132,13 +137,34 @@ func readPackageListFile(path string) ([]string, error) {
 // alfabetycznie po nazwie pliku -- live-build wykonuje je w tym porzadku,
 // stad konwencja numerowania prefiksow (0100-..., 0200-...).
 func (p *Project) parseHooks(configDir string) error {
-	dir := filepath.Join(configDir, "hooks", "normal")
+	hooks, err := collectHooks(filepath.Join(configDir, "hooks", "normal"))
+	if err != nil {
+		return err
+	}
+	p.Hooks = hooks
+	return nil
+}
+
+// parseInstallHooks zbiera skrypty z config/hooks/install/*.hook.chroot
+// (wlasne rozszerzenie hackeros-builder, live-build go nie zna).
+func (p *Project) parseInstallHooks(configDir string) error {
+	hooks, err := collectHooks(filepath.Join(configDir, "hooks", "install"))
+	if err != nil {
+		return err
+	}
+	p.InstallHooks = hooks
+	return nil
+}
+
+// collectHooks zwraca *.hook.chroot z katalogu dir posortowane po nazwie.
+// Brak katalogu -> pusta lista, brak bledu.
+func collectHooks(dir string) ([]HookScript, error) {
 	entries, err := os.ReadDir(dir)
 	if os.IsNotExist(err) {
-		return nil
+		return nil, nil
 	}
 	if err != nil {
-		return fmt.Errorf("nie mozna odczytac %s: %w", dir, err)
+		return nil, fmt.Errorf("nie mozna odczytac %s: %w", dir, err)
 	}
 
 	var hooks []HookScript
@@ -153,8 +179,7 @@ func (p *Project) parseHooks(configDir string) error {
 	}
 
 	sort.Slice(hooks, func(i, j int) bool { return hooks[i].Name < hooks[j].Name })
-	p.Hooks = hooks
-	return nil
+	return hooks, nil
 }
 
 // parseIncludesChroot ustawia sciezke do config/includes.chroot jesli istnieje.
@@ -167,8 +192,10 @@ func (p *Project) parseIncludesChroot(configDir string) {
 	}
 }
 
-// parseArchives czyta config/archives/*.list.chroot (dodatkowe linie
-// sources.list) i config/archives/*.key.chroot (klucze GPG do zaimportowania).
+// parseArchives czyta config/archives/: *.list.chroot (linie sources.list,
+// sprawdzane przez checkSourceLine), *.key.chroot (klucz OpenPGP repo) i
+// *.fingerprint.chroot (odcisk, ktory klucz musi miec). Pliki o tej samej
+// nazwie tworza jedno Archive.
 func (p *Project) parseArchives(configDir string) error {
 	dir := filepath.Join(configDir, "archives")
 	entries, err := os.ReadDir(dir)
@@ -179,6 +206,16 @@ func (p *Project) parseArchives(configDir string) error {
 		return fmt.Errorf("nie mozna odczytac %s: %w", dir, err)
 	}
 
+	byName := map[string]*Archive{}
+	var names []string
+	archive := func(name string) *Archive {
+		if byName[name] == nil {
+			byName[name] = &Archive{Name: name}
+			names = append(names, name)
+		}
+		return byName[name]
+	}
+
 	for _, e := range entries {
 		if e.IsDir() {
 			continue
@@ -190,10 +227,34 @@ func (p *Project) parseArchives(configDir string) error {
 			if err != nil {
 				return fmt.Errorf("blad odczytu %s: %w", path, err)
 			}
-			p.ExtraSources = append(p.ExtraSources, lines...)
+			for _, line := range lines {
+				if err := checkSourceLine(line); err != nil {
+					return fmt.Errorf("%s: %w", path, err)
+				}
+			}
+			a := archive(strings.TrimSuffix(e.Name(), ".list.chroot"))
+			a.Lines = lines
 		case strings.HasSuffix(e.Name(), ".key.chroot"):
-			p.ExtraKeys = append(p.ExtraKeys, path)
+			archive(strings.TrimSuffix(e.Name(), ".key.chroot")).KeyPath = path
+		case strings.HasSuffix(e.Name(), ".fingerprint.chroot"):
+			lines, err := readLines(path)
+			if err != nil {
+				return fmt.Errorf("blad odczytu %s: %w", path, err)
+			}
+			if len(lines) != 1 {
+				return fmt.Errorf("%s: oczekiwano jednej linii z odciskiem klucza", path)
+			}
+			archive(strings.TrimSuffix(e.Name(), ".fingerprint.chroot")).Fingerprint = lines[0]
+		}
+	}
+
+	sort.Strings(names)
+	for _, name := range names {
+		a := byName[name]
+		if err := a.checkKey(); err != nil {
+			return err
 		}
+		p.Archives = append(p.Archives, *a)
 	}
 	return nil
 }
@@ -221,12 +282,18 @@ func (p *Project) Summary() string {
 	var b strings.Builder
 	fmt.Fprintf(&b, "Pakietow do instalacji:  %d\n", len(p.Packages))
 	fmt.Fprintf(&b, "Hookow do wykonania:     %d\n", len(p.Hooks))
+	fmt.Fprintf(&b, "Hookow instalatora:      %d\n", len(p.InstallHooks))
 	if p.IncludesChroot != "" {
 		fmt.Fprintf(&b, "includes.chroot:         %s\n", p.IncludesChroot)
 	} else {
 		fmt.Fprintf(&b, "includes.chroot:         (brak)\n")
 	}
-	fmt.Fprintf(&b, "Dodatkowych zrodel apt:  %d\n", len(p.ExtraSources))
-	fmt.Fprintf(&b, "Dodatkowych kluczy GPG:  %d\n", len(p.ExtraKeys))
+	keys := 0
+	for _, a := range p.Archives {
+		if a.KeyPath != "" {
+			keys++
+		}
+	}
+	fmt.Fprintf(&b, "Dodatkowych repozytoriow apt: %d (kluczy: %d)\n", len(p.Archives), keys)
 	return b.String()
 }
@@ -21,6 +21,11 @@ import (
 	"github.com/HackerOS-Linux-System/hackeros-builder/internal/util"
 )
 
+// SnapshotAnnotation to adnotacja manifestu OCI ze znacznikiem
+// snapshot.debian.org, z ktorego zbudowano rootfs ([release] -> snapshot).
+// To samo trafia do HACKEROS_SNAPSHOT w os-release obrazu.
+const SnapshotAnnotation = "io.github.hackeros-linux-system.debian-snapshot"
+
 // BuildParams to dane potrzebne do zbudowania i wypchniecia obrazu OCI.
 type BuildParams struct {
 	RootfsDir  string // katalog z gotowym rootfs (po rootfs.Builder.Build())
@@ -28,6 +33,7 @@ type BuildParams struct {
 	Tag        string // tag obrazu, np. "trixie" lub "latest"
 	Token      string // token autoryzacyjny (z config.hk -> [auth] -> token)
 	WorkDir    string // katalog tymczasowy na warstwe tar (np. /tmp/hackeros-build)
+	Snapshot   string // [release] -> snapshot; puste -- bez adnotacji SnapshotAnnotation
 
 	// Insecure wylacza weryfikacje certyfikatu TLS przy polaczeniu z
 	// registry -- przeznaczone WYLACZNIE dla self-signed/wewnetrznych
@@ -54,6 +60,7 @@ func BuildAndPush(p BuildParams) (string, error) {
 	if err != nil {
 		return "", fmt.Errorf("budowanie obrazu OCI: %w", err)
 	}
+	img = annotateImage(img, p.Snapshot)
 
 	refStr := fmt.Sprintf("%s:%s", p.Repository, p.Tag)
 	ref, err := name.ParseReference(refStr)
@@ -79,6 +86,15 @@ func BuildAndPush(p BuildParams) (string, error) {
 	return refStr, nil
 }
 
+// annotateImage dopisuje do manifestu SnapshotAnnotation, gdy build uzywal
+// [release] -> snapshot.
+func annotateImage(img v1.Image, snapshot string) v1.Image {
+	if snapshot == "" {
+		return img
+	}
+	return mutate.Annotations(img, map[string]string{SnapshotAnnotation: snapshot}).(v1.Image)
+}
+
 // createLayerTarball pakuje cala zawartosc rootfsDir do pojedynczego pliku
 // tar.gz, zachowujac uprawnienia i symlinki -- kluczowe dla poprawnosci
 // systemowych binarek (setuid root, etc.) po stronie deb-ostree, ktore te
@@ -0,0 +1,25 @@
+package ociimage
+
+import (
+	"testing"
+
+	"github.com/google/go-containerregistry/pkg/v1/empty"
+)
+
+func TestAnnotateImage_Snapshot(t *testing.T) {
+	m, err := annotateImage(empty.Image, "20250701T000000Z").Manifest()
+	if err != nil {
+		t.Fatal(err)
+	}
+	if got := m.Annotations[SnapshotAnnotation]; got != "20250701T000000Z" {
+		t.Errorf("oczekiwano adnotacji %s=20250701T000000Z, otrzymano %q", SnapshotAnnotation, got)
+	}
+
+	m, err = annotateImage(empty.Image, "").Manifest()
+	if err != nil {
+		t.Fatal(err)
+	}
+	if _, ok := m.Annotations[SnapshotAnnotation]; ok {
+		t.Error("bez [release] -> snapshot obraz nie powinien miec adnotacji snapshotu")
+	}
+}
@@ -0,0 +1,49 @@
+package rootfs
+
+import (
+	"os"
+	"path/filepath"
+
+	"github.com/HackerOS-Linux-System/hackeros-builder/internal/util"
+)
+
+// buildAptConfPath to drop-in apt obowiazujacy tylko w trakcie buildu --
+// usuwany przed zakonczeniem Build(), nie trafia do obrazu.
+const buildAptConfPath = "etc/apt/apt.conf.d/90hackeros-build"
+
+// buildAptConf zwraca tresc drop-inu z [project] -> apt_parallel. apt
+// otwiera juz osobne polaczenie do kazdego hosta (Queue-Mode "host" --
+// deb.debian.org i security.debian.org ida rownolegle), ale w obrebie
+// jednego hosta pobiera plik po pliku. Pipeline-Depth wysyla kilka zadan
+// naraz na jednym polaczeniu -- przy setkach malych .deb i duzym RTT to
+// glowny zysk. Bez plikow Translation "apt-get update" pobiera mniej.
+func buildAptConf() string {
+	return "// Wygenerowane przez hackeros-builder na czas buildu ([project] -> apt_parallel).\n" +
+		"Acquire::Queue-Mode \"host\";\n" +
+		"Acquire::http::Pipeline-Depth \"10\";\n" +
+		"Acquire::Languages \"none\";\n" +
+		"Acquire::Retries \"3\";\n"
+}
+
+// writeBuildAptConf zapisuje drop-in z buildAptConf, gdy [project] ->
+// apt_parallel jest wlaczone.
+func (b *Builder) writeBuildAptConf() error {
+	if !b.Config.Project.AptParallel {
+		return nil
+	}
+	path := filepath.Join(b.RootfsDir, buildAptConfPath)
+	if err := os.MkdirAll(filepath.Dir(path), 0o755); err != nil {
+		return err
+	}
+	util.Infof("  apt: rownolegle pobieranie (pipelining) na czas buildu")
+	return os.WriteFile(path, []byte(buildAptConf()), 0o644)
+}
+
+// removeBuildAptConf usuwa drop-in z writeBuildAptConf -- zainstalowany
+// system uzywa domyslnych ustawien apt.
+func (b *Builder) removeBuildAptConf() {
+	path := filepath.Join(b.RootfsDir, buildAptConfPath)
+	if err := os.Remove(path); err != nil && !os.IsNotExist(err) {
+		util.Warnf("Nie mozna usunac %s: %v", path, err)
+	}
+}
@@ -0,0 +1,40 @@
+package rootfs
+
+import (
+	"os"
+	"path/filepath"
+	"strings"
+	"testing"
+
+	"github.com/HackerOS-Linux-System/hackeros-builder/internal/config"
+)
+
+func TestBuildAptConf_WrittenOnlyWhenEnabledAndRemoved(t *testing.T) {
+	root := t.TempDir()
+	path := filepath.Join(root, buildAptConfPath)
+
+	b := &Builder{Config: &config.Config{}, RootfsDir: root}
+	if err := b.writeBuildAptConf(); err != nil {
+		t.Fatalf("writeBuildAptConf: %v", err)
+	}
+	if _, err := os.Stat(path); !os.IsNotExist(err) {
+		t.Fatalf("bez apt_parallel drop-in nie powinien powstac (err=%v)", err)
+	}
+
+	b.Config.Project.AptParallel = true
+	if err := b.writeBuildAptConf(); err != nil {
+		t.Fatalf("writeBuildAptConf: %v", err)
+	}
+	data, err := os.ReadFile(path)
+	if err != nil {
+		t.Fatalf("brak drop-inu apt: %v", err)
+	}
+	if !strings.Contains(string(data), `Acquire::http::Pipeline-Depth "10";`) {
+		t.Errorf("brak Pipeline-Depth w drop-inie:\n%s", data)
+	}
+
+	b.removeBuildAptConf()
+	if _, err := os.Stat(path); !os.IsNotExist(err) {
+		t.Errorf("drop-in powinien byc usuniety po buildzie (err=%v)", err)
+	}
+}
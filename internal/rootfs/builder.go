@@ -1,10 +1,13 @@
 package rootfs
 
 import (
+	"errors"
 	"fmt"
 	"io"
 	"os"
 	"path/filepath"
+	"strings"
+	"time"
 
 	"github.com/HackerOS-Linux-System/hackeros-builder/internal/config"
 	"github.com/HackerOS-Linux-System/hackeros-builder/internal/download"
@@ -20,6 +23,13 @@ import (
 // mirror per-projekt w config.hk).
 const defaultMirror = "http://deb.debian.org/debian"
 
+// aptUpdateAttempts i aptUpdateDelay: "apt-get update" probowane 3 razy,
+// z przerwami 10 s i 20 s.
+const (
+	aptUpdateAttempts = 3
+	aptUpdateDelay    = 10 * time.Second
+)
+
 // Builder buduje rootfs na podstawie sparsowanego projektu i konfiguracji.
 type Builder struct {
 	Project   *liveparse.Project
@@ -29,6 +39,10 @@ type Builder struct {
 
 	// OriginRefspec to refspec obrazu OCI wpisywany do /etc/deb-ostree/deb-ostree.hk
 	OriginRefspec string
+
+	// stagedDebOstree to sciezka binarki deb-ostree pobranej (i
+	// zweryfikowanej) do WorkDir/staging/ na poczatku buildu.
+	stagedDebOstree string
 }
 
 // New tworzy nowy Builder.
@@ -88,7 +102,7 @@ func (b *Builder) installMACPackages() error {
 		"-o", "Dpkg::Options::=--force-confdef",
 		"-o", "Dpkg::Options::=--force-confold",
 	}, pkgs...)
-	if err := b.sandboxExec("apt-get", args...); err != nil {
+	if err := b.aptGet(args...); err != nil {
 		return fmt.Errorf("instalacja pakietow %s (%v): %w", macName, pkgs, err)
 	}
 	return nil
@@ -102,8 +116,10 @@ func (b *Builder) Build() error {
 		return err
 	}
 
+	steps := util.NewSteps(13)
+
 	// --- toolchain: przygotuj narzedzia build-time ---
-	util.Infof("Krok 0/7: sprawdzanie/pobieranie narzedzi build-time...")
+	steps.Next("sprawdzanie/pobieranie narzedzi build-time...")
 	tc := toolchain.New(b.WorkDir)
 	if err := tc.PrepareAll(); err != nil {
 		return fmt.Errorf("toolchain: %w", err)
@@ -114,12 +130,35 @@ func (b *Builder) Build() error {
 		return fmt.Errorf("ustawienie PATH toolchain: %w", err)
 	}
 
-	util.Infof("Krok 1/7: debootstrap (%s)...", b.Config.Release)
+	// Wszystkie artefakty spoza apt sa pobierane i weryfikowane PRZED
+	// debootstrap -- zerwanie sieci pod koniec (po kilkunastu minutach
+	// instalacji pakietow) nie marnuje juz calego buildu, a dalsze kroki
+	// uzywaja tylko kopii lokalnej. staging/ jest usuwany na koniec.
+	steps.Next("pobieranie artefaktow do %s...", b.stagingDir())
+	defer os.RemoveAll(b.stagingDir())
+	if err := b.stageDebOstree(); err != nil {
+		return fmt.Errorf("deb-ostree: %w", err)
+	}
+	if b.Config.Snapshot != "" {
+		if err := b.checkSnapshot(); err != nil {
+			return fmt.Errorf("snapshot archiwum %s: %w", b.Config.Snapshot, err)
+		}
+	}
+
+	steps.Next("debootstrap (%s, komponenty: %s)...",
+		b.Config.Release, b.Config.ComponentsArg())
 	if err := b.runDebootstrap(); err != nil {
 		return fmt.Errorf("debootstrap: %w", err)
 	}
+	if err := b.writeSourcesList(); err != nil {
+		return fmt.Errorf("sources.list: %w", err)
+	}
+	defer b.removeBuildAptConf()
+	if err := b.writeBuildAptConf(); err != nil {
+		return fmt.Errorf("konfiguracja apt: %w", err)
+	}
 
-	util.Infof("Krok 2/7: preseed debconf + sudo-stub...")
+	steps.Next("preseed debconf + sudo-stub...")
 	if err := b.seedDebconf(); err != nil {
 		return fmt.Errorf("preseed debconf: %w", err)
 	}
@@ -127,41 +166,53 @@ func (b *Builder) Build() error {
 		return fmt.Errorf("sudo stub: %w", err)
 	}
 
-	if len(b.Project.ExtraSources) > 0 {
-		util.Infof("Krok 3/8: dodatkowe zrodla apt (%d)...", len(b.Project.ExtraSources))
+	if len(b.Project.Archives) > 0 {
+		steps.Next("dodatkowe repozytoria apt (%d)...", len(b.Project.Archives))
 		if err := b.applyExtraSources(); err != nil {
 			return fmt.Errorf("extra sources: %w", err)
 		}
 	} else {
-		util.Infof("Krok 3/8: brak dodatkowych zrodel apt -- pominieto")
+		steps.Skip("brak dodatkowych zrodel apt")
 	}
 
-	util.Infof("Krok 4/8: instalacja systemu MAC ([project] -> selinux=%v)...",
+	steps.Next("instalacja systemu MAC ([project] -> selinux=%v)...",
 		b.Config.Project.MAC == config.MACSELinux)
 	if err := b.installMACPackages(); err != nil {
 		return fmt.Errorf("instalacja MAC: %w", err)
 	}
 
-	util.Infof("Krok 5/8: instalacja %d pakiet(ow)...", len(b.Project.Packages))
+	steps.Next("instalacja %d pakiet(ow)...", len(b.Project.Packages))
+	start := time.Now()
 	if err := b.installPackages(); err != nil {
 		return fmt.Errorf("instalacja pakietow: %w", err)
 	}
+	// Czas kroku -- do porownania buildow z i bez [project] -> apt_parallel.
+	util.Infof("  pakiety zainstalowane w %s (apt_parallel=%v)",
+		time.Since(start).Round(time.Second), b.Config.Project.AptParallel)
+
+	steps.Next("konfiguracja [system] + [grub]...")
+	if err := b.applySystemConfig(); err != nil {
+		return fmt.Errorf("konfiguracja [system]: %w", err)
+	}
+	if err := b.applyGrubConfig(); err != nil {
+		return fmt.Errorf("konfiguracja [grub]: %w", err)
+	}
 
 	if b.Project.IncludesChroot != "" {
-		util.Infof("Krok 6/8: kopiowanie includes.chroot...")
+		steps.Next("kopiowanie includes.chroot...")
 		if err := b.copyIncludesChroot(); err != nil {
 			return fmt.Errorf("includes.chroot: %w", err)
 		}
 	} else {
-		util.Infof("Krok 6/8: brak includes.chroot -- pominieto")
+		steps.Skip("brak includes.chroot")
 	}
 
-	util.Infof("Krok 7/8: wykonywanie %d hook(ow)...", len(b.Project.Hooks))
+	steps.Next("wykonywanie %d hook(ow)...", len(b.Project.Hooks))
 	if err := b.runHooks(); err != nil {
 		return fmt.Errorf("hooks: %w", err)
 	}
 
-	util.Infof("Krok 8/8: wstrzykiwanie deb-ostree + generowanie deb-ostree.hk...")
+	steps.Next("instalacja deb-ostree + generowanie deb-ostree.hk...")
 	if err := b.injectDebOstree(); err != nil {
 		return fmt.Errorf("deb-ostree injection: %w", err)
 	}
@@ -172,7 +223,34 @@ func (b *Builder) Build() error {
 		return fmt.Errorf("generowanie deb-ostree.hk: %w", err)
 	}
 
+	// Po ostatniej instalacji pakietow (deb-ostree deps), przed czyszczeniem
+	// stanu -- ono obcina tez dpkg.log z instalacji debsums.
+	var verifyProblems []string
+	if b.Config.Project.VerifyPackages {
+		steps.Next("weryfikacja pakietow (apt-get check, debsums)...")
+		problems, err := b.verifyPackages()
+		if err != nil {
+			return fmt.Errorf("weryfikacja pakietow: %w", err)
+		}
+		verifyProblems = problems
+	} else {
+		steps.Skip("weryfikacja pakietow ([project] -> verify_packages)")
+	}
+
+	if b.Config.System.Generalize {
+		steps.Next("czyszczenie stanu maszyny (machine-id, klucze SSH, logi)...")
+		if err := b.generalizeImage(); err != nil {
+			return fmt.Errorf("czyszczenie stanu maszyny: %w", err)
+		}
+	} else {
+		steps.Skip("czyszczenie stanu maszyny ([system] -> generalize)")
+	}
+
 	util.Infof("Rootfs zbudowany: %s", b.RootfsDir)
+	if len(verifyProblems) > 0 {
+		util.Warnf("Weryfikacja pakietow: %d zmienionych/brakujacych plikow -- lista w kroku weryfikacji wyzej",
+			len(verifyProblems))
+	}
 	return nil
 }
 
@@ -186,17 +264,31 @@ func (b *Builder) prepareDir() error {
 	return nil
 }
 
-// runDebootstrap wywoluje "debootstrap <suite> <target> <mirror>". To jest
+// runDebootstrap wywoluje "debootstrap --components=<...> <suite> <target>
+// <mirror>" (komponenty z [release] -> components, mirror z
+// archiveMirrors). To jest
 // JEDYNA czesc procesu ktora delegujemy do istniejacego narzedzia Debiana --
 // reimplementacja debootstrap (rozwiazywanie zaleznosci bazowego systemu od
 // zera) wykraczalaby daleko poza zakres hackeros-builder.
 func (b *Builder) runDebootstrap() error {
-	return util.RunStreaming("", "debootstrap",
+	mirror, _ := archiveMirrors(b.Config.Snapshot)
+	// Wlasny namespace mount+PID: po przekroczeniu limitu ginie debootstrap
+	// razem z wget/dpkg, a montowania w rootfs (proc, sys) znikaja z
+	// namespace zamiast zostac na hoscie.
+	err := util.RunStreamingTimeout("", b.Config.Timeouts.Debootstrap,
+		"unshare", "--mount", "--pid", "--fork", "--kill-child",
+		"debootstrap",
 		"--arch=amd64",
+		"--components="+b.Config.ComponentsArg(),
 		b.Config.Release,
 		b.RootfsDir,
-		defaultMirror,
+		mirror,
 	)
+	if errors.Is(err, util.ErrTimeout) {
+		return fmt.Errorf("debootstrap przerwany po %s ([timeouts] -> debootstrap) -- "+
+			"sprawdz mirror i polaczenie: %w", b.Config.Timeouts.Debootstrap, err)
+	}
+	return err
 }
 
 // installSudoStub instaluje /usr/local/sbin/sudo wewnatrz rootfs jako
@@ -242,8 +334,8 @@ func (b *Builder) removeSudoStub() {
 // izolowanego kontenera nspawn (nie plain chroot -- patrz Build() i
 // util.RunNspawnStreaming dla uzasadnienia).
 func (b *Builder) installPackages() error {
-	if err := b.sandboxExec("apt-get", "update"); err != nil {
-		return fmt.Errorf("apt-get update: %w", err)
+	if err := b.aptUpdate(); err != nil {
+		return err
 	}
 
 	if len(b.Project.Packages) == 0 {
@@ -256,40 +348,80 @@ func (b *Builder) installPackages() error {
 		"-o", "Dpkg::Options::=--force-confold",
 		"-o", "APT::Get::Assume-Yes=true",
 	}, b.Project.Packages...)
-	if err := b.sandboxExec("apt-get", args...); err != nil {
+	if err := b.aptGet(args...); err != nil {
 		return fmt.Errorf("apt-get install: %w", err)
 	}
 	return nil
 }
 
-// applyExtraSources dopisuje config/archives/*.list.chroot do
-// rootfs/etc/apt/sources.list.d/hackeros-extra.list i importuje klucze GPG.
+// aptKeyringsDir to katalog kluczy repozytoriow spoza Debiana, wskazywanych
+// przez signed-by -- klucz podpisuje tylko swoje repo, nie cale apt (jak
+// klucz w trusted.gpg.d).
+const aptKeyringsDir = "etc/apt/keyrings"
+
+// applyExtraSources zapisuje kazde archiwum z config/archives/ jako
+// /etc/apt/sources.list.d/<nazwa>.list, a jego klucz jako
+// /etc/apt/keyrings/<nazwa>.asc|.gpg, dopisujac signed-by do linii bez
+// niego. Klucz bez listy (repo podpisywane nim trafia do obrazu inaczej,
+// np. z hooka) zostaje zaufany globalnie w trusted.gpg.d -- jak w
+// live-build. apt-get update wola nastepny krok (installPackages).
 func (b *Builder) applyExtraSources() error {
 	sourcesDir := filepath.Join(b.RootfsDir, "etc", "apt", "sources.list.d")
 	if err := os.MkdirAll(sourcesDir, 0o755); err != nil {
 		return err
 	}
-	listPath := filepath.Join(sourcesDir, "hackeros-extra.list")
-	f, err := os.Create(listPath)
-	if err != nil {
-		return fmt.Errorf("tworzenie %s: %w", listPath, err)
-	}
-	defer f.Close()
-	for _, line := range b.Project.ExtraSources {
-		if _, err := fmt.Fprintln(f, line); err != nil {
-			return err
+	for _, a := range b.Project.Archives {
+		lines := a.Lines
+		if a.KeyPath != "" {
+			ext := ".gpg"
+			if a.KeyIsArmored() {
+				ext = ".asc"
+			}
+			dir := aptKeyringsDir
+			if len(a.Lines) == 0 {
+				dir = "etc/apt/trusted.gpg.d"
+				util.Warnf("config/archives/%s.key.chroot bez %s.list.chroot -- klucz zaufany dla wszystkich repozytoriow (trusted.gpg.d)", a.Name, a.Name)
+			}
+			keyring := "/" + dir + "/" + a.Name + ext
+			if err := copyFile(a.KeyPath, filepath.Join(b.RootfsDir, keyring), 0o644); err != nil {
+				return fmt.Errorf("kopiowanie klucza %s: %w", a.KeyPath, err)
+			}
+			util.Infof("  %s: klucz %s (%s)", a.Name, keyring, strings.Join(a.KeyFingerprints, ", "))
+			if a.Fingerprint == "" {
+				util.Warnf("config/archives/%s: brak %s.fingerprint.chroot -- odcisk klucza nie jest sprawdzany", a.Name, a.Name)
+			}
+			lines = nil
+			for _, l := range a.Lines {
+				lines = append(lines, withSignedBy(l, keyring))
+			}
 		}
-	}
-	for _, keyPath := range b.Project.ExtraKeys {
-		destName := filepath.Base(keyPath)
-		destPath := filepath.Join(b.RootfsDir, "etc", "apt", "trusted.gpg.d", destName)
-		if err := copyFile(keyPath, destPath, 0o644); err != nil {
-			return fmt.Errorf("kopiowanie klucza GPG %s: %w", keyPath, err)
+		if len(lines) == 0 {
+			continue
+		}
+		listPath := filepath.Join(sourcesDir, a.Name+".list")
+		content := "# Wygenerowane przez hackeros-builder z config/archives/" + a.Name + ".list.chroot.\n" +
+			strings.Join(lines, "\n") + "\n"
+		if err := os.WriteFile(listPath, []byte(content), 0o644); err != nil {
+			return fmt.Errorf("zapis %s: %w", listPath, err)
 		}
 	}
 	return nil
 }
 
+// withSignedBy dopisuje opcje signed-by=keyring do linii sources.list,
+// ktora jej nie ma (linia ma juz poprawna skladnie -- liveparse).
+func withSignedBy(line, keyring string) string {
+	if strings.Contains(line, "signed-by=") {
+		return line
+	}
+	fields := strings.Fields(line)
+	if strings.HasPrefix(fields[1], "[") {
+		i := strings.Index(line, "]")
+		return line[:i] + " signed-by=" + keyring + line[i:]
+	}
+	return fields[0] + " [signed-by=" + keyring + "] " + strings.Join(fields[1:], " ")
+}
+
 // copyIncludesChroot kopiuje rekurencyjnie config/includes.chroot/* do
 // korzenia rootfs, zachowujac uprawnienia plikow (1:1 jak live-build).
 func (b *Builder) copyIncludesChroot() error {
@@ -320,8 +452,8 @@ func (b *Builder) copyIncludesChroot() error {
 }
 
 // runHooks wykonuje kazdy skrypt hooks wewnatrz izolowanego kontenera nspawn.
-// Sudo-stub instalowany w kroku 2 jest USUWANY po wykonaniu WSZYSTKICH hookow
-// (patrz installSudoStub / removeSudoStub).
+// Sudo-stub instalowany w kroku "preseed debconf + sudo-stub" jest USUWANY
+// po wykonaniu WSZYSTKICH hookow (patrz installSudoStub / removeSudoStub).
 func (b *Builder) runHooks() error {
 	defer b.removeSudoStub()
 	for _, h := range b.Project.Hooks {
@@ -331,7 +463,7 @@ func (b *Builder) runHooks() error {
 		if err := copyFile(h.Path, destOnHost, 0o755); err != nil {
 			return fmt.Errorf("kopiowanie hooka %s: %w", h.Name, err)
 		}
-		if err := b.sandboxExec(tmpName); err != nil {
+		if err := sandbox.ExecTimeout(b.RootfsDir, b.Config.Timeouts.Hooks, tmpName); err != nil {
 			os.Remove(destOnHost)
 			return fmt.Errorf("wykonanie hooka %s: %w", h.Name, err)
 		}
@@ -342,11 +474,16 @@ func (b *Builder) runHooks() error {
 	return nil
 }
 
-// injectDebOstree sciaga najnowsza wersje deb-ostree z GitHub Releases
+// stagingDir to katalog na artefakty pobrane na poczatku buildu.
+func (b *Builder) stagingDir() string {
+	return filepath.Join(b.WorkDir, "staging")
+}
+
+// stageDebOstree sciaga najnowsza wersje deb-ostree z GitHub Releases
 // (lub wersje wskazana przez DEBOSTREE_VERSION jesli ustawiona -- przydatne
-// do pinowania konkretnej wersji / testow offline) i umieszcza w
-// rootfs/usr/bin/deb-ostree z uprawnieniami a+x.
-func (b *Builder) injectDebOstree() error {
+// do pinowania konkretnej wersji / testow offline) do stagingDir, ze
+// sprawdzeniem sumy kontrolnej.
+func (b *Builder) stageDebOstree() error {
 	version := os.Getenv("DEBOSTREE_VERSION")
 	if version == "" {
 		v, err := download.LatestDebOstreeVersion()
@@ -356,15 +493,35 @@ func (b *Builder) injectDebOstree() error {
 		version = v
 	}
 
-	destPath := filepath.Join(b.RootfsDir, "usr", "bin", "deb-ostree")
+	destPath := filepath.Join(b.stagingDir(), "deb-ostree")
 	util.Infof("  deb-ostree %s -> %s", version, destPath)
+	switch releases, ok := download.DebOstreeCompatibility(version, b.Config.Release); {
+	case ok:
+		util.Infof("  deb-ostree %s: zgodne z %s", version, b.Config.Release)
+	case releases == nil:
+		util.Warnf("deb-ostree %s: zgodnosc z %s nieznana (wersji nie ma w macierzy zgodnosci) -- "+
+			"sprawdz system po instalacji albo przypnij wersje przez DEBOSTREE_VERSION", version, b.Config.Release)
+	default:
+		util.Warnf("deb-ostree %s nie jest oznaczone jako zgodne z %s (zgodne: %s) -- "+
+			"mozliwe bledy aktualizacji atomowych; przypnij inna wersje przez DEBOSTREE_VERSION",
+			version, b.Config.Release, strings.Join(releases, ", "))
+	}
 
 	if err := download.DownloadDebOstree(version, destPath); err != nil {
 		return err
 	}
+	b.stagedDebOstree = destPath
 	return nil
 }
 
+// injectDebOstree kopiuje binarke z stagingDir (stageDebOstree) do
+// rootfs/usr/bin/deb-ostree z uprawnieniami a+x -- bez dostepu do sieci.
+func (b *Builder) injectDebOstree() error {
+	destPath := filepath.Join(b.RootfsDir, "usr", "bin", "deb-ostree")
+	util.Infof("  deb-ostree -> %s", destPath)
+	return copyFile(b.stagedDebOstree, destPath, 0o755)
+}
+
 // generateDebOstreeConfig wywoluje hkgen, by wygenerowac kompletny plik
 // /etc/deb-ostree/deb-ostree.hk wewnatrz rootfs, gotowy do uzycia przez
 // deb-ostree natychmiast po pierwszym boocie zbudowanego systemu.
@@ -421,6 +578,33 @@ func (b *Builder) sandboxExec(command string, args ...string) error {
 	return sandbox.Exec(b.RootfsDir, command, args...)
 }
 
+// aptUpdate wykonuje "apt-get update" z ponowieniami (aptUpdateAttempts,
+// backoff od aptUpdateDelay) -- chwilowa czkawka mirrora (timeout, 5xx,
+// Release w trakcie synchronizacji) nie przerywa calego buildu. Proba
+// dluzsza niz [timeouts] -> apt_update jest zabijana i ponawiana.
+func (b *Builder) aptUpdate() error {
+	return util.Retry("apt-get update", aptUpdateAttempts, aptUpdateDelay, func() error {
+		return sandbox.ExecTimeout(b.RootfsDir, b.Config.Timeouts.AptUpdate, "apt-get", "update")
+	})
+}
+
+// aptGet wykonuje apt-get z limitem [timeouts] -> apt_install. Zabity
+// apt-get (zwykle pobieranie zawieszone na martwym mirrorze) jest
+// ponawiany raz, po "dpkg --configure -a" konczacym konfiguracje pakietow
+// rozpakowanych przez przerwana probe. Inne bledy wracaja od razu.
+func (b *Builder) aptGet(args ...string) error {
+	timeout := b.Config.Timeouts.AptInstall
+	err := sandbox.ExecTimeout(b.RootfsDir, timeout, "apt-get", args...)
+	if !errors.Is(err, util.ErrTimeout) {
+		return err
+	}
+	util.Warnf("apt-get przerwany po %s ([timeouts] -> apt_install) -- ponowienie", timeout)
+	if err := b.sandboxExec("dpkg", "--configure", "-a"); err != nil {
+		return fmt.Errorf("dpkg --configure -a po przerwanym apt-get: %w", err)
+	}
+	return sandbox.ExecTimeout(b.RootfsDir, timeout, "apt-get", args...)
+}
+
 // sandboxExecWithStdin jak sandboxExec ale z danymi na stdin.
 func (b *Builder) sandboxExecWithStdin(data []byte, command string, args ...string) error {
 	return sandbox.ExecWithStdin(b.RootfsDir, data, command, args...)
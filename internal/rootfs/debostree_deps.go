@@ -50,12 +50,12 @@ var debOstreeDeps = []string{
 //
 // Musi byc wywolane PO injectDebOstree (zeby binarka byla juz w rootfs)
 // i PO tym jak rootfs ma skonfigurowane zrodla apt (debootstrap juz to
-// zapewnia -- /etc/apt/sources.list jest gotowy po kroku 1).
+// zapewnia -- /etc/apt/sources.list jest gotowy po kroku debootstrap).
 func (b *Builder) installDebOstreeDeps() error {
 	util.Infof("  deb-ostree: instalacja %d bibliotek dynamicznych...", len(debOstreeDeps))
 
-	if err := b.sandboxExec("apt-get", "update"); err != nil {
-		return fmt.Errorf("apt-get update przed instalacja deb-ostree deps: %w", err)
+	if err := b.aptUpdate(); err != nil {
+		return fmt.Errorf("przed instalacja deb-ostree deps: %w", err)
 	}
 
 	args := append([]string{
@@ -64,7 +64,7 @@ func (b *Builder) installDebOstreeDeps() error {
 		"-o", "Dpkg::Options::=--force-confold",
 	}, debOstreeDeps...)
 
-	if err := b.sandboxExec("apt-get", args...); err != nil {
+	if err := b.aptGet(args...); err != nil {
 		return fmt.Errorf("instalacja bibliotek deb-ostree (%v): %w", debOstreeDeps, err)
 	}
 
@@ -0,0 +1,89 @@
+package rootfs
+
+import (
+	"fmt"
+	"os"
+	"path/filepath"
+	"strings"
+
+	"github.com/HackerOS-Linux-System/hackeros-builder/internal/config"
+	"github.com/HackerOS-Linux-System/hackeros-builder/internal/util"
+)
+
+const (
+	resolvConfPath = "etc/resolv.conf"
+
+	// resolvedStubLink to cel symlinka /etc/resolv.conf przy
+	// systemd-resolved (lokalny stub 127.0.0.53).
+	resolvedStubLink = "../run/systemd/resolve/stub-resolv.conf"
+
+	resolvedDropInPath = "etc/systemd/resolved.conf.d/hackeros.conf"
+
+	// nmDNSNonePath: NetworkManager domyslnie przepisuje /etc/resolv.conf
+	// serwerami z DHCP -- przy dns => static ma go zostawic.
+	nmDNSNonePath = "etc/NetworkManager/conf.d/90-hackeros-dns.conf"
+)
+
+// configureDNS ustawia DNS obrazu wg [system] -> dns. debootstrap kopiuje
+// do rootfs /etc/resolv.conf hosta buildu -- bez tej opcji obraz zostaje
+// z nim, dopoki stos sieciowy go nie nadpisze.
+//
+//   - resolved: systemd-resolved (wlaczony), /etc/resolv.conf -> stub,
+//     drop-in z DNS= gdy podano serwery. Kolejne komendy buildu maja DNS
+//     hosta mimo wiszacego symlinka -- patrz sandbox.
+//   - static: /etc/resolv.conf z serwerami, NetworkManager z dns=none
+func (b *Builder) configureDNS(mode string, servers []string) error {
+	util.Infof("  [system] DNS: %s %s", mode, strings.Join(servers, " "))
+	if mode == config.DNSStatic {
+		if err := b.writeRootfsFile(resolvConfPath, staticResolvConf(servers)); err != nil {
+			return err
+		}
+		return b.writeRootfsFile(nmDNSNonePath,
+			"# Wygenerowane przez hackeros-builder ([system] -> dns => static).\n"+
+				"[main]\ndns=none\nrc-manager=unmanaged\n")
+	}
+
+	if err := b.aptInstall("systemd-resolved"); err != nil {
+		return err
+	}
+	if len(servers) > 0 {
+		if err := b.writeRootfsFile(resolvedDropInPath,
+			"# Wygenerowane przez hackeros-builder z [system] -> dns_servers.\n"+
+				"[Resolve]\nDNS="+strings.Join(servers, " ")+"\n"); err != nil {
+			return err
+		}
+	}
+	if err := b.sandboxExec("systemctl", "enable", "systemd-resolved.service"); err != nil {
+		return fmt.Errorf("systemctl enable systemd-resolved: %w", err)
+	}
+	// postinst pakietu robi to samo -- ale tylko gdy resolv.conf nie byl
+	// zmieniony recznie, a kopia hosta z debootstrap sie do tego zalicza.
+	link := filepath.Join(b.RootfsDir, resolvConfPath)
+	if err := os.Remove(link); err != nil && !os.IsNotExist(err) {
+		return err
+	}
+	return os.Symlink(resolvedStubLink, link)
+}
+
+// staticResolvConf zwraca /etc/resolv.conf z podanymi serwerami.
+func staticResolvConf(servers []string) string {
+	var sb strings.Builder
+	sb.WriteString("# Wygenerowane przez hackeros-builder z [system] -> dns_servers.\n")
+	for _, s := range servers {
+		fmt.Fprintf(&sb, "nameserver %s\n", s)
+	}
+	return sb.String()
+}
+
+// writeRootfsFile zapisuje plik (0644) pod sciezka wzgledna do rootfs,
+// tworzac katalogi. Istniejacy symlink jest zastepowany zwyklym plikiem.
+func (b *Builder) writeRootfsFile(rel, content string) error {
+	path := filepath.Join(b.RootfsDir, rel)
+	if err := os.MkdirAll(filepath.Dir(path), 0o755); err != nil {
+		return err
+	}
+	if err := os.Remove(path); err != nil && !os.IsNotExist(err) {
+		return err
+	}
+	return os.WriteFile(path, []byte(content), 0o644)
+}
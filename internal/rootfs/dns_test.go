@@ -0,0 +1,40 @@
+package rootfs
+
+import (
+	"os"
+	"path/filepath"
+	"strings"
+	"testing"
+
+	"github.com/HackerOS-Linux-System/hackeros-builder/internal/config"
+)
+
+func TestConfigureDNS_StaticReplacesHostResolvConf(t *testing.T) {
+	root := t.TempDir()
+	etc := filepath.Join(root, "etc")
+	if err := os.MkdirAll(etc, 0o755); err != nil {
+		t.Fatal(err)
+	}
+	// resolv.conf jako symlink (np. kopia hosta z systemd-resolved).
+	if err := os.Symlink(resolvedStubLink, filepath.Join(etc, "resolv.conf")); err != nil {
+		t.Fatal(err)
+	}
+
+	b := &Builder{RootfsDir: root}
+	if err := b.configureDNS(config.DNSStatic, []string{"9.9.9.9", "2620:fe::fe"}); err != nil {
+		t.Fatalf("configureDNS: %v", err)
+	}
+
+	fi, err := os.Lstat(filepath.Join(root, resolvConfPath))
+	if err != nil || !fi.Mode().IsRegular() {
+		t.Fatalf("resolv.conf powinien byc zwyklym plikiem (err=%v)", err)
+	}
+	data, _ := os.ReadFile(filepath.Join(root, resolvConfPath))
+	if !strings.Contains(string(data), "nameserver 9.9.9.9\nnameserver 2620:fe::fe\n") {
+		t.Errorf("niepoprawny resolv.conf:\n%s", data)
+	}
+	nm, err := os.ReadFile(filepath.Join(root, nmDNSNonePath))
+	if err != nil || !strings.Contains(string(nm), "dns=none") {
+		t.Errorf("brak dns=none dla NetworkManager (err=%v):\n%s", err, nm)
+	}
+}
@@ -0,0 +1,152 @@
+package rootfs
+
+import (
+	"fmt"
+	"io/fs"
+	"os"
+	"path/filepath"
+	"strings"
+
+	"github.com/HackerOS-Linux-System/hackeros-builder/internal/util"
+)
+
+// sshKeygenUnit to nazwa uslugi generujacej klucze hosta SSH przy
+// pierwszym starcie (patrz generalizeImage).
+const sshKeygenUnit = "hackeros-ssh-keygen.service"
+
+// sshKeygenUnitContent: "ssh-keygen -A" tworzy brakujace klucze hosta
+// wszystkich typow. Warunek na kluczu ed25519 -- przy kolejnych startach
+// usluga jest pomijana.
+const sshKeygenUnitContent = `[Unit]
+Description=Generowanie kluczy hosta SSH (hackeros-builder)
+ConditionPathExists=!/etc/ssh/ssh_host_ed25519_key
+Before=ssh.service ssh.socket
+
+[Service]
+Type=oneshot
+ExecStart=/usr/bin/ssh-keygen -A
+
+[Install]
+WantedBy=multi-user.target
+`
+
+// generalizeImage usuwa z rootfs stan specyficzny dla jednej maszyny --
+// ten sam obraz trafia (przez OCI albo instalator z ISO) na wiele
+// komputerow i kazdy musi miec wlasna tozsamosc. Ostatni krok buildu, po
+// hookach: sprzata takze to, co wygenerowaly pakiety instalowane w hookach.
+//
+//   - /etc/machine-id -- pusty plik: systemd generuje nowy ID przy
+//     pierwszym starcie (pusty, nie brak -- to sygnal "first boot");
+//     /var/lib/dbus/machine-id -- usuwany, gdy to kopia, nie symlink
+//   - /etc/ssh/ssh_host_* -- usuwane; gdy jest sshd, sshKeygenUnit tworzy
+//     nowe klucze przy pierwszym starcie
+//   - dzierzawy DHCP (/var/lib/dhcp, /var/lib/NetworkManager) i
+//     /etc/udev/rules.d/70-persistent-net.rules -- usuwane
+//   - /var/log -- pliki obcinane do zera, zrotowane (*.gz, *.1 ...) usuwane
+func (b *Builder) generalizeImage() error {
+	root := b.RootfsDir
+
+	util.Infof("  machine-id: /etc/machine-id wyczyszczony")
+	if err := os.WriteFile(filepath.Join(root, "etc", "machine-id"), nil, 0o444); err != nil {
+		return fmt.Errorf("machine-id: %w", err)
+	}
+	dbusID := filepath.Join(root, "var", "lib", "dbus", "machine-id")
+	if fi, err := os.Lstat(dbusID); err == nil && fi.Mode().IsRegular() {
+		if err := os.Remove(dbusID); err != nil {
+			return fmt.Errorf("dbus machine-id: %w", err)
+		}
+	}
+
+	hostKeys, err := filepath.Glob(filepath.Join(root, "etc", "ssh", "ssh_host_*"))
+	if err != nil {
+		return err
+	}
+	for _, k := range hostKeys {
+		if err := os.Remove(k); err != nil {
+			return fmt.Errorf("klucz hosta SSH: %w", err)
+		}
+	}
+	if _, err := os.Stat(filepath.Join(root, "usr", "sbin", "sshd")); err == nil {
+		util.Infof("  SSH: usunieto %d klucz(y) hosta, %s wygeneruje nowe przy pierwszym starcie",
+			len(hostKeys), sshKeygenUnit)
+		if err := enableUnit(root, sshKeygenUnit, sshKeygenUnitContent, "multi-user.target"); err != nil {
+			return fmt.Errorf("%s: %w", sshKeygenUnit, err)
+		}
+	}
+
+	var leases []string
+	for _, pattern := range []string{
+		"var/lib/dhcp/*.leases",
+		"var/lib/NetworkManager/*.lease",
+		"etc/udev/rules.d/70-persistent-net.rules",
+	} {
+		m, err := filepath.Glob(filepath.Join(root, pattern))
+		if err != nil {
+			return err
+		}
+		leases = append(leases, m...)
+	}
+	for _, p := range leases {
+		if err := os.Remove(p); err != nil {
+			return fmt.Errorf("stan sieci: %w", err)
+		}
+	}
+
+	return truncateLogs(filepath.Join(root, "var", "log"))
+}
+
+// truncateLogs obcina pliki w logDir do zera i usuwa logi zrotowane --
+// katalogi i uprawnienia zostaja (czesc demonow nie tworzy ich sama).
+func truncateLogs(logDir string) error {
+	err := filepath.WalkDir(logDir, func(path string, d fs.DirEntry, err error) error {
+		if err != nil {
+			return err
+		}
+		if !d.Type().IsRegular() {
+			return nil
+		}
+		if isRotatedLog(d.Name()) {
+			return os.Remove(path)
+		}
+		return os.Truncate(path, 0)
+	})
+	if os.IsNotExist(err) {
+		return nil
+	}
+	return err
+}
+
+// isRotatedLog rozpoznaje logi zrotowane przez logrotate/savelog:
+// "syslog.1", "dpkg.log.2.gz", "wtmp.0".
+func isRotatedLog(name string) bool {
+	name = strings.TrimSuffix(name, ".gz")
+	i := strings.LastIndexByte(name, '.')
+	if i < 0 || i == len(name)-1 {
+		return false
+	}
+	for _, c := range name[i+1:] {
+		if c < '0' || c > '9' {
+			return false
+		}
+	}
+	return true
+}
+
+// enableUnit zapisuje unit systemd do /etc/systemd/system i wlacza go
+// symlinkiem w <target>.wants -- to samo co "systemctl enable", bez
+// uruchamiania systemctl w chroot.
+func enableUnit(root, name, content, target string) error {
+	unitDir := filepath.Join(root, "etc", "systemd", "system")
+	wantsDir := filepath.Join(unitDir, target+".wants")
+	if err := os.MkdirAll(wantsDir, 0o755); err != nil {
+		return err
+	}
+	if err := os.WriteFile(filepath.Join(unitDir, name), []byte(content), 0o644); err != nil {
+		return err
+	}
+	link := filepath.Join(wantsDir, name)
+	if err := os.Remove(link); err != nil && !os.IsNotExist(err) {
+		return err
+	}
+	return os.Symlink("/etc/systemd/system/"+name, link)
+}
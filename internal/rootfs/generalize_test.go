@@ -0,0 +1,62 @@
+package rootfs
+
+import (
+	"os"
+	"path/filepath"
+	"testing"
+)
+
+func TestGeneralizeImage(t *testing.T) {
+	root := t.TempDir()
+	files := map[string]string{
+		"etc/machine-id":                    "0123456789abcdef\n",
+		"var/lib/dbus/machine-id":           "0123456789abcdef\n",
+		"etc/ssh/ssh_host_ed25519_key":      "klucz",
+		"etc/ssh/ssh_host_ed25519_key.pub":  "klucz",
+		"etc/ssh/sshd_config":               "PermitRootLogin no\n",
+		"usr/sbin/sshd":                     "",
+		"var/lib/dhcp/dhclient.eth0.leases": "lease",
+		"var/log/dpkg.log":                  "wpis\n",
+		"var/log/dpkg.log.2.gz":             "stary",
+		"var/log/apt/history.log.1":         "stary",
+	}
+	for name, content := range files {
+		path := filepath.Join(root, name)
+		if err := os.MkdirAll(filepath.Dir(path), 0o755); err != nil {
+			t.Fatal(err)
+		}
+		if err := os.WriteFile(path, []byte(content), 0o644); err != nil {
+			t.Fatal(err)
+		}
+	}
+
+	b := &Builder{RootfsDir: root}
+	if err := b.generalizeImage(); err != nil {
+		t.Fatalf("generalizeImage zwrocilo blad: %v", err)
+	}
+
+	for _, gone := range []string{
+		"var/lib/dbus/machine-id",
+		"etc/ssh/ssh_host_ed25519_key",
+		"etc/ssh/ssh_host_ed25519_key.pub",
+		"var/lib/dhcp/dhclient.eth0.leases",
+		"var/log/dpkg.log.2.gz",
+		"var/log/apt/history.log.1",
+	} {
+		if _, err := os.Lstat(filepath.Join(root, gone)); !os.IsNotExist(err) {
+			t.Errorf("%s powinien zostac usuniety", gone)
+		}
+	}
+	for _, empty := range []string{"etc/machine-id", "var/log/dpkg.log"} {
+		if fi, err := os.Stat(filepath.Join(root, empty)); err != nil || fi.Size() != 0 {
+			t.Errorf("%s powinien istniec i byc pusty (err=%v)", empty, err)
+		}
+	}
+	if _, err := os.Stat(filepath.Join(root, "etc/ssh/sshd_config")); err != nil {
+		t.Errorf("sshd_config nie powinien byc ruszany: %v", err)
+	}
+	link := filepath.Join(root, "etc/systemd/system/multi-user.target.wants", sshKeygenUnit)
+	if target, err := os.Readlink(link); err != nil || target != "/etc/systemd/system/"+sshKeygenUnit {
+		t.Errorf("oczekiwano wlaczonego %s, otrzymano %q (err=%v)", sshKeygenUnit, target, err)
+	}
+}
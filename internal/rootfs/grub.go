@@ -0,0 +1,61 @@
+package rootfs
+
+import (
+	"fmt"
+	"os"
+	"path/filepath"
+	"strings"
+
+	"github.com/HackerOS-Linux-System/hackeros-builder/internal/config"
+	"github.com/HackerOS-Linux-System/hackeros-builder/internal/util"
+)
+
+// grubDropInPath to plik nadpisan /etc/default/grub -- grub-mkconfig
+// wczytuje /etc/default/grub.d/*.cfg PO /etc/default/grub, wiec nie
+// trzeba edytowac pliku pakietu (brak konfliktow dpkg przy aktualizacji).
+const grubDropInPath = "etc/default/grub.d/hackeros.cfg"
+
+// applyGrubConfig zapisuje sekcje [grub] config.hk jako drop-in GRUB.
+// Brak ustawien -- plik nie jest tworzony. Konfiguracje generuje dopiero
+// update-grub w instalatorze (modul bootloader Calamares).
+func (b *Builder) applyGrubConfig() error {
+	g := b.Config.Grub
+	if g.IsEmpty() {
+		return nil
+	}
+
+	path := filepath.Join(b.RootfsDir, grubDropInPath)
+	util.Infof("  [grub] /%s", grubDropInPath)
+	if err := os.MkdirAll(filepath.Dir(path), 0o755); err != nil {
+		return err
+	}
+	return os.WriteFile(path, []byte(grubDropIn(g)), 0o644)
+}
+
+// grubDropIn zwraca tresc /etc/default/grub.d/hackeros.cfg dla g.
+func grubDropIn(g config.GrubConfig) string {
+	var b strings.Builder
+	b.WriteString("# Wygenerowane przez hackeros-builder z sekcji [grub] config.hk.\n")
+	if g.Timeout != nil {
+		fmt.Fprintf(&b, "GRUB_TIMEOUT=%d\n", *g.Timeout)
+	}
+	if g.HiddenMenu {
+		b.WriteString("GRUB_TIMEOUT_STYLE=hidden\n")
+	}
+	if g.Default != "" {
+		fmt.Fprintf(&b, "GRUB_DEFAULT=%s\n", g.Default)
+		if g.Default == "saved" {
+			b.WriteString("GRUB_SAVEDEFAULT=true\n")
+		}
+	}
+	if g.OSProber != nil {
+		fmt.Fprintf(&b, "GRUB_DISABLE_OS_PROBER=%t\n", !*g.OSProber)
+	}
+	if len(g.OSProberSkip) > 0 {
+		fmt.Fprintf(&b, "GRUB_OS_PROBER_SKIP_LIST=\"%s\"\n", strings.Join(g.OSProberSkip, " "))
+	}
+	if g.Recovery != nil {
+		fmt.Fprintf(&b, "GRUB_DISABLE_RECOVERY=%t\n", !*g.Recovery)
+	}
+	return b.String()
+}
@@ -0,0 +1,44 @@
+package rootfs
+
+import (
+	"testing"
+
+	"github.com/HackerOS-Linux-System/hackeros-builder/internal/config"
+)
+
+func TestGrubDropIn(t *testing.T) {
+	timeout := 2
+	got := grubDropIn(config.GrubConfig{Timeout: &timeout, Default: "saved", HiddenMenu: true})
+	want := "# Wygenerowane przez hackeros-builder z sekcji [grub] config.hk.\n" +
+		"GRUB_TIMEOUT=2\n" +
+		"GRUB_TIMEOUT_STYLE=hidden\n" +
+		"GRUB_DEFAULT=saved\n" +
+		"GRUB_SAVEDEFAULT=true\n"
+	if got != want {
+		t.Errorf("oczekiwano:\n%s\notrzymano:\n%s", want, got)
+	}
+}
+
+func TestGrubDropIn_OSProber(t *testing.T) {
+	enabled := true
+	got := grubDropIn(config.GrubConfig{
+		OSProber:     &enabled,
+		OSProberSkip: []string{"1234-ABCD", "5678-EF01@/dev/sdb1"},
+	})
+	want := "# Wygenerowane przez hackeros-builder z sekcji [grub] config.hk.\n" +
+		"GRUB_DISABLE_OS_PROBER=false\n" +
+		"GRUB_OS_PROBER_SKIP_LIST=\"1234-ABCD 5678-EF01@/dev/sdb1\"\n"
+	if got != want {
+		t.Errorf("oczekiwano:\n%s\notrzymano:\n%s", want, got)
+	}
+}
+
+func TestGrubDropIn_Recovery(t *testing.T) {
+	enabled := true
+	got := grubDropIn(config.GrubConfig{Recovery: &enabled})
+	want := "# Wygenerowane przez hackeros-builder z sekcji [grub] config.hk.\n" +
+		"GRUB_DISABLE_RECOVERY=false\n"
+	if got != want {
+		t.Errorf("oczekiwano:\n%s\notrzymano:\n%s", want, got)
+	}
+}
@@ -0,0 +1,94 @@
+package rootfs
+
+import (
+	"fmt"
+	"os"
+	"path/filepath"
+	"strings"
+
+	"github.com/HackerOS-Linux-System/hackeros-builder/internal/config"
+	"github.com/HackerOS-Linux-System/hackeros-builder/internal/util"
+)
+
+const (
+	// nvidiaModprobePath: pierwsza linia zapisuje wybor z [system] ->
+	// nvidia -- instalator (isobuild.nvidiaCommand) i skrypt weryfikacji
+	// wypisuja go do logu.
+	nvidiaModprobePath = "etc/modprobe.d/hackeros-nvidia.conf"
+
+	initramfsModulesPath = "etc/initramfs-tools/modules"
+)
+
+// nvidiaEarlyKMSModules to moduly ladowane z initramfs: KMS (modeset) od
+// pierwszej klatki, bez przelaczania z simpledrm/nouveau -- sesje Wayland
+// wymagaja nvidia-drm modeset=1.
+var nvidiaEarlyKMSModules = []string{"nvidia", "nvidia_modeset", "nvidia_uvm", "nvidia_drm"}
+
+// configureNvidia instaluje sterownik NVIDIA wybrany w [system] -> nvidia
+// (nouveau -- nic do instalowania), blokuje nouveau, wlacza modeset
+// nvidia-drm i early KMS. Moduly buduje DKMS dla jader obrazu -- brak
+// nvidia.ko po instalacji to blad buildu, nie czarny ekran po instalacji.
+func (b *Builder) configureNvidia(choice string) error {
+	if choice == config.NvidiaNouveau {
+		util.Infof("  [system] NVIDIA: nouveau (bez sterownika NVIDIA)")
+		return nil
+	}
+	pkgs := config.NvidiaDrivers[choice]
+	util.Infof("  [system] NVIDIA: %s (%s)", choice, strings.Join(pkgs, ", "))
+	if err := b.aptInstall(pkgs...); err != nil {
+		return err
+	}
+
+	modules, _ := filepath.Glob(filepath.Join(b.RootfsDir, "lib", "modules", "*", "updates", "dkms", "nvidia.ko*"))
+	if len(modules) == 0 {
+		return fmt.Errorf("DKMS nie zbudowal modulu nvidia (brak /lib/modules/*/updates/dkms/nvidia.ko) -- " +
+			"sprawdz, czy naglowki jadra (linux-headers-*) pasuja do jadra obrazu")
+	}
+	for _, m := range modules {
+		util.Infof("    %s", strings.TrimPrefix(m, b.RootfsDir))
+	}
+
+	if err := b.writeRootfsFile(nvidiaModprobePath, nvidiaModprobeConf(choice)); err != nil {
+		return err
+	}
+	path := filepath.Join(b.RootfsDir, initramfsModulesPath)
+	data, err := os.ReadFile(path)
+	if err != nil && !os.IsNotExist(err) {
+		return err
+	}
+	if err := os.WriteFile(path, []byte(withModules(string(data), nvidiaEarlyKMSModules)), 0o644); err != nil {
+		return err
+	}
+	if err := b.sandboxExec("update-initramfs", "-u", "-k", "all"); err != nil {
+		return fmt.Errorf("update-initramfs: %w", err)
+	}
+	return nil
+}
+
+// nvidiaModprobeConf zwraca tresc nvidiaModprobePath.
+func nvidiaModprobeConf(choice string) string {
+	return fmt.Sprintf("# hackeros-builder: [system] -> nvidia => %s\n", choice) +
+		"blacklist nouveau\n" +
+		"options nouveau modeset=0\n" +
+		"options nvidia-drm modeset=1\n"
+}
+
+// withModules dopisuje do /etc/initramfs-tools/modules moduly, ktorych
+// jeszcze tam nie ma (jeden na linie).
+func withModules(content string, modules []string) string {
+	have := map[string]bool{}
+	for _, line := range strings.Split(content, "\n") {
+		if fields := strings.Fields(line); len(fields) > 0 {
+			have[fields[0]] = true
+		}
+	}
+	if content != "" && !strings.HasSuffix(content, "\n") {
+		content += "\n"
+	}
+	for _, m := range modules {
+		if !have[m] {
+			content += m + "\n"
+		}
+	}
+	return content
+}
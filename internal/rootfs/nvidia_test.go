@@ -0,0 +1,34 @@
+package rootfs
+
+import (
+	"strings"
+	"testing"
+)
+
+func TestNvidiaModprobeConf(t *testing.T) {
+	got := nvidiaModprobeConf("open")
+	for _, want := range []string{
+		"# hackeros-builder: [system] -> nvidia => open\n",
+		"blacklist nouveau\n",
+		"options nvidia-drm modeset=1\n",
+	} {
+		if !strings.Contains(got, want) {
+			t.Errorf("brak %q w:\n%s", want, got)
+		}
+	}
+}
+
+func TestWithModules(t *testing.T) {
+	in := "# List of modules\nloop\nnvidia\n"
+	got := withModules(in, nvidiaEarlyKMSModules)
+	want := in + "nvidia_modeset\nnvidia_uvm\nnvidia_drm\n"
+	if got != want {
+		t.Errorf("oczekiwano:\n%s\notrzymano:\n%s", want, got)
+	}
+	if again := withModules(got, nvidiaEarlyKMSModules); again != got {
+		t.Errorf("ponowne dopisanie zmienilo plik:\n%s", again)
+	}
+	if got := withModules("loop", []string{"nvidia"}); got != "loop\nnvidia\n" {
+		t.Errorf("plik bez koncowego \\n: %q", got)
+	}
+}
@@ -0,0 +1,100 @@
+package rootfs
+
+import (
+	"fmt"
+	"os"
+	"path/filepath"
+	"regexp"
+	"strings"
+
+	"github.com/HackerOS-Linux-System/hackeros-builder/internal/config"
+	"github.com/HackerOS-Linux-System/hackeros-builder/internal/util"
+)
+
+// osReleasePath to plik os-release pakietu base-files; /etc/os-release
+// to symlink na niego. Wersja Debiana zostaje obok (dpkg-divert), zeby
+// aktualizacja base-files nie przywrocila tozsamosci Debiana.
+const (
+	osReleasePath       = "/usr/lib/os-release"
+	osReleaseDivertedTo = "/usr/lib/os-release.debian"
+	hackerosVersionPath = "etc/hackeros-version"
+	osReleaseHomeURL    = "https://github.com/HackerOS-Linux-System"
+)
+
+// osVersionIDPattern: VERSION_ID wg os-release(5) -- tylko gdy tag
+// projektu wyglada na numer wersji ("1.2", "2025.07"), nie dla "latest".
+var osVersionIDPattern = regexp.MustCompile(`^[0-9][0-9a-z._~-]*$`)
+
+// osIDInvalidChars: ID w os-release to tylko [a-z0-9._-].
+var osIDInvalidChars = regexp.MustCompile(`[^a-z0-9._-]+`)
+
+// osReleaseParams to dane wpisywane do os-release obrazu.
+type osReleaseParams struct {
+	Name     string // NAME, np. "HackerOS"
+	Version  string // VERSION_ID / czesc VERSION -- [system] -> os_version albo tag projektu
+	Release  string // wydanie Debiana (VERSION_CODENAME, DEBIAN_CODENAME)
+	Edition  string // [project] -> name (HACKEROS_EDITION), moze byc puste
+	Snapshot string // [release] -> snapshot (HACKEROS_SNAPSHOT), moze byc puste
+}
+
+// osRelease zwraca tresc os-release. ID_LIKE=debian -- narzedzia
+// sprawdzajace rodzine dystrybucji (skrypty instalacyjne, ansible) dalej
+// traktuja system jak Debiana.
+func osRelease(p osReleaseParams) string {
+	id := osIDInvalidChars.ReplaceAllString(strings.ToLower(p.Name), "-")
+	version := p.Version + " (" + p.Release + ")"
+
+	var b strings.Builder
+	fmt.Fprintf(&b, "PRETTY_NAME=%q\n", p.Name+" "+version)
+	fmt.Fprintf(&b, "NAME=%q\n", p.Name)
+	fmt.Fprintf(&b, "VERSION=%q\n", version)
+	if osVersionIDPattern.MatchString(p.Version) {
+		fmt.Fprintf(&b, "VERSION_ID=%q\n", p.Version)
+	}
+	fmt.Fprintf(&b, "VERSION_CODENAME=%s\n", p.Release)
+	fmt.Fprintf(&b, "DEBIAN_CODENAME=%s\n", p.Release)
+	fmt.Fprintf(&b, "ID=%s\n", id)
+	b.WriteString("ID_LIKE=debian\n")
+	fmt.Fprintf(&b, "HOME_URL=%q\n", osReleaseHomeURL)
+	if p.Edition != "" {
+		fmt.Fprintf(&b, "HACKEROS_EDITION=%q\n", p.Edition)
+	}
+	// Stan archiwum, z ktorego pochodza pakiety -- bez niego z gotowego
+	// systemu nie da sie odtworzyc, ktore wersje zainstalowal build.
+	if p.Snapshot != "" {
+		fmt.Fprintf(&b, "HACKEROS_SNAPSHOT=%s\n", p.Snapshot)
+	}
+	return b.String()
+}
+
+// writeOSRelease zastepuje os-release Debiana wlasnym (przez dpkg-divert)
+// i zapisuje /etc/hackeros-version. Plik z includes.chroot projektu
+// (kopiowany pozniej) ma pierwszenstwo.
+func (b *Builder) writeOSRelease() error {
+	sys := b.Config.System
+	p := osReleaseParams{
+		Name:     sys.OSName,
+		Version:  sys.OSVersion,
+		Release:  b.Config.Release,
+		Edition:  b.Config.Project.Name,
+		Snapshot: b.Config.Snapshot,
+	}
+	if p.Name == "" {
+		p.Name = config.DefaultOSName
+	}
+	if p.Version == "" {
+		p.Version = b.Config.Project.ImageTag()
+	}
+
+	util.Infof("  [system] os-release: %s %s", p.Name, p.Version)
+	if err := b.sandboxExec("dpkg-divert", "--local", "--rename",
+		"--divert", osReleaseDivertedTo, "--add", osReleasePath); err != nil {
+		return fmt.Errorf("dpkg-divert %s: %w", osReleasePath, err)
+	}
+
+	path := filepath.Join(b.RootfsDir, strings.TrimPrefix(osReleasePath, "/"))
+	if err := os.WriteFile(path, []byte(osRelease(p)), 0o644); err != nil {
+		return err
+	}
+	return os.WriteFile(filepath.Join(b.RootfsDir, hackerosVersionPath), []byte(p.Version+"\n"), 0o644)
+}
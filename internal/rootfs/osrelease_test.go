@@ -0,0 +1,37 @@
+package rootfs
+
+import (
+	"strings"
+	"testing"
+)
+
+func TestOSRelease(t *testing.T) {
+	got := osRelease(osReleaseParams{Name: "HackerOS", Version: "1.2", Release: "trixie", Edition: "blue",
+		Snapshot: "20250701T000000Z"})
+	want := `PRETTY_NAME="HackerOS 1.2 (trixie)"
+NAME="HackerOS"
+VERSION="1.2 (trixie)"
+VERSION_ID="1.2"
+VERSION_CODENAME=trixie
+DEBIAN_CODENAME=trixie
+ID=hackeros
+ID_LIKE=debian
+HOME_URL="https://github.com/HackerOS-Linux-System"
+HACKEROS_EDITION="blue"
+HACKEROS_SNAPSHOT=20250701T000000Z
+`
+	if got != want {
+		t.Errorf("oczekiwano:\n%s\notrzymano:\n%s", want, got)
+	}
+}
+
+func TestOSRelease_NonNumericVersion(t *testing.T) {
+	got := osRelease(osReleaseParams{Name: "Moj System+", Version: "latest", Release: "sid"})
+	if strings.Contains(got, "VERSION_ID") || strings.Contains(got, "HACKEROS_EDITION") ||
+		strings.Contains(got, "HACKEROS_SNAPSHOT") {
+		t.Errorf("bez VERSION_ID/HACKEROS_EDITION/HACKEROS_SNAPSHOT dla %q:\n%s", "latest", got)
+	}
+	if !strings.Contains(got, "\nID=moj-system-\n") {
+		t.Errorf("ID nie jest znormalizowane:\n%s", got)
+	}
+}
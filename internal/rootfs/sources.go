@@ -0,0 +1,107 @@
+package rootfs
+
+import (
+	"fmt"
+	"os"
+	"path/filepath"
+	"strings"
+
+	"github.com/HackerOS-Linux-System/hackeros-builder/internal/download"
+	"github.com/HackerOS-Linux-System/hackeros-builder/internal/util"
+)
+
+// securityMirror to archiwum poprawek bezpieczenstwa Debiana -- osobny
+// host od defaultMirror, z suite "<release>-security".
+const securityMirror = "http://security.debian.org/debian-security"
+
+// snapshotArchive to baza snapshot.debian.org -- dalej nazwa archiwum
+// ("debian", "debian-security") i znacznik czasu z [release] -> snapshot.
+const snapshotArchive = "http://snapshot.debian.org/archive"
+
+// snapshotAptConfPath: pliki Release w snapshocie maja Valid-Until z
+// przeszlosci, apt odrzucilby je jako przeterminowane. Zostaje w obrazie
+// razem z sources.list wskazujacym na snapshot.
+const snapshotAptConfPath = "etc/apt/apt.conf.d/80hackeros-snapshot"
+
+// archiveMirrors zwraca mirror glowny i mirror poprawek bezpieczenstwa --
+// dla niepustego snapshot archiwa z snapshot.debian.org w tej chwili.
+func archiveMirrors(snapshot string) (mirror, security string) {
+	if snapshot == "" {
+		return defaultMirror, securityMirror
+	}
+	return snapshotArchive + "/debian/" + snapshot, snapshotArchive + "/debian-security/" + snapshot
+}
+
+// hasStableSuites zwraca false dla galezi rozwojowych (sid/unstable/
+// experimental), ktore nie maja suite "-updates" ani "-security" --
+// wpisanie ich do sources.list konczy sie bledem 404 przy apt-get update.
+func hasStableSuites(release string) bool {
+	switch release {
+	case "sid", "unstable", "experimental":
+		return false
+	}
+	return true
+}
+
+// securitySuite zwraca suite archiwum poprawek bezpieczenstwa: od bullseye
+// "<release>-security", w starszych wydaniach (buster i wczesniej)
+// "<release>/updates". "" dla galezi bez tego archiwum (hasStableSuites).
+func securitySuite(release string) string {
+	switch {
+	case !hasStableSuites(release):
+		return ""
+	case release == "buster" || release == "stretch" || release == "jessie":
+		return release + "/updates"
+	}
+	return release + "-security"
+}
+
+// sourcesList buduje zawartosc /etc/apt/sources.list dla danego mirrora,
+// wydania i komponentow. debootstrap sam zapisuje tylko jedna linie (bez
+// -updates/-security i z komponentami z --components), wiec nadpisujemy
+// ja pelna lista zaraz po debootstrap -- tak by kazde kolejne apt-get
+// (pakiety projektu, MAC, deb-ostree) widzialo contrib/non-free/firmware.
+func sourcesList(mirror, security, release string, components []string) string {
+	comps := strings.Join(components, " ")
+
+	var b strings.Builder
+	b.WriteString("# Wygenerowane przez hackeros-builder ([release] w config/config.hk).\n")
+	fmt.Fprintf(&b, "deb %s %s %s\n", mirror, release, comps)
+	if hasStableSuites(release) {
+		fmt.Fprintf(&b, "deb %s %s-updates %s\n", mirror, release, comps)
+	}
+	if suite := securitySuite(release); suite != "" {
+		fmt.Fprintf(&b, "deb %s %s %s\n", security, suite, comps)
+	}
+	return b.String()
+}
+
+// writeSourcesList zapisuje rootfs/etc/apt/sources.list z komponentami
+// z [release] -> components (i archiwami z [release] -> snapshot).
+func (b *Builder) writeSourcesList() error {
+	path := filepath.Join(b.RootfsDir, "etc", "apt", "sources.list")
+	if err := os.MkdirAll(filepath.Dir(path), 0o755); err != nil {
+		return err
+	}
+	mirror, security := archiveMirrors(b.Config.Snapshot)
+	content := sourcesList(mirror, security, b.Config.Release, b.Config.Components)
+	if err := os.WriteFile(path, []byte(content), 0o644); err != nil {
+		return fmt.Errorf("zapis %s: %w", path, err)
+	}
+	if b.Config.Snapshot != "" {
+		conf := "// Wygenerowane przez hackeros-builder ([release] -> snapshot).\n" +
+			"Acquire::Check-Valid-Until \"false\";\n"
+		if err := os.WriteFile(filepath.Join(b.RootfsDir, snapshotAptConfPath), []byte(conf), 0o644); err != nil {
+			return fmt.Errorf("zapis %s: %w", snapshotAptConfPath, err)
+		}
+	}
+	return nil
+}
+
+// checkSnapshot sprawdza przed debootstrap, ze snapshot.debian.org ma plik
+// Release wydania w chwili z [release] -> snapshot.
+func (b *Builder) checkSnapshot() error {
+	mirror, _ := archiveMirrors(b.Config.Snapshot)
+	util.Infof("  snapshot archiwum: %s", mirror)
+	return download.CheckURL(mirror + "/dists/" + b.Config.Release + "/Release")
+}
@@ -0,0 +1,85 @@
+package rootfs
+
+import (
+	"strings"
+	"testing"
+)
+
+func TestSourcesList_StableReleaseHasUpdatesAndSecurity(t *testing.T) {
+	got := sourcesList("http://deb.debian.org/debian", securityMirror, "trixie",
+		[]string{"main", "contrib", "non-free", "non-free-firmware"})
+
+	for _, want := range []string{
+		"deb http://deb.debian.org/debian trixie main contrib non-free non-free-firmware\n",
+		"deb http://deb.debian.org/debian trixie-updates main contrib non-free non-free-firmware\n",
+		"deb http://security.debian.org/debian-security trixie-security main contrib non-free non-free-firmware\n",
+	} {
+		if !strings.Contains(got, want) {
+			t.Errorf("oczekiwano linii %q w sources.list, otrzymano:\n%s", want, got)
+		}
+	}
+}
+
+func TestSourcesList_SidHasNoSecuritySuite(t *testing.T) {
+	got := sourcesList("http://deb.debian.org/debian", securityMirror, "sid", []string{"main"})
+
+	if !strings.Contains(got, "deb http://deb.debian.org/debian sid main\n") {
+		t.Errorf("brak glownej linii sid w sources.list:\n%s", got)
+	}
+	if strings.Contains(got, "-security") || strings.Contains(got, "-updates") {
+		t.Errorf("sid nie ma suite -security/-updates, otrzymano:\n%s", got)
+	}
+}
+
+func TestSourcesList_BusterSecurityLayout(t *testing.T) {
+	got := sourcesList("http://deb.debian.org/debian", securityMirror, "buster", []string{"main"})
+
+	want := "deb http://security.debian.org/debian-security buster/updates main\n"
+	if !strings.Contains(got, want) {
+		t.Errorf("oczekiwano linii %q (stary uklad archiwum), otrzymano:\n%s", want, got)
+	}
+	if strings.Contains(got, "buster-security") {
+		t.Errorf("buster nie ma suite buster-security:\n%s", got)
+	}
+}
+
+func TestArchiveMirrors_Snapshot(t *testing.T) {
+	mirror, security := archiveMirrors("")
+	if mirror != defaultMirror || security != securityMirror {
+		t.Errorf("bez snapshotu oczekiwano %s / %s, otrzymano %s / %s",
+			defaultMirror, securityMirror, mirror, security)
+	}
+
+	mirror, security = archiveMirrors("20250701T000000Z")
+	if mirror != "http://snapshot.debian.org/archive/debian/20250701T000000Z" ||
+		security != "http://snapshot.debian.org/archive/debian-security/20250701T000000Z" {
+		t.Errorf("niepoprawne archiwa snapshotu: %s / %s", mirror, security)
+	}
+	got := sourcesList(mirror, security, "trixie", []string{"main"})
+	if !strings.Contains(got, "deb "+security+" trixie-security main\n") {
+		t.Errorf("brak linii -security ze snapshotu:\n%s", got)
+	}
+}
+
+func TestWithSignedBy(t *testing.T) {
+	const keyring = "/etc/apt/keyrings/docker.asc"
+	cases := []struct{ line, want string }{
+		{
+			"deb https://download.docker.com/linux/debian bookworm stable",
+			"deb [signed-by=/etc/apt/keyrings/docker.asc] https://download.docker.com/linux/debian bookworm stable",
+		},
+		{
+			"deb [arch=amd64] https://download.docker.com/linux/debian bookworm stable",
+			"deb [arch=amd64 signed-by=/etc/apt/keyrings/docker.asc] https://download.docker.com/linux/debian bookworm stable",
+		},
+		{
+			"deb [signed-by=/usr/share/keyrings/x.gpg] https://x.example.org/ stable main",
+			"deb [signed-by=/usr/share/keyrings/x.gpg] https://x.example.org/ stable main",
+		},
+	}
+	for _, c := range cases {
+		if got := withSignedBy(c.line, keyring); got != c.want {
+			t.Errorf("withSignedBy(%q)\n  otrzymano %q\n  oczekiwano %q", c.line, got, c.want)
+		}
+	}
+}
@@ -0,0 +1,378 @@
+package rootfs
+
+import (
+	"fmt"
+	"os"
+	"path/filepath"
+	"strings"
+
+	"github.com/HackerOS-Linux-System/hackeros-builder/internal/config"
+	"github.com/HackerOS-Linux-System/hackeros-builder/internal/util"
+)
+
+// applySystemConfig wpisuje do rootfs ustawienia z sekcji [system]
+// config.hk. Wykonywane PO instalacji pakietow projektu (zeby pakiety
+// dociagane tutaj nie dublowaly package-lists) i PRZED includes.chroot/
+// hookami -- pliki z includes.chroot i hooki uzytkownika maja ostatnie
+// slowo i moga nadpisac to, co zapisal builder.
+func (b *Builder) applySystemConfig() error {
+	sys := b.Config.System
+
+	if err := b.writeOSRelease(); err != nil {
+		return fmt.Errorf("os-release: %w", err)
+	}
+
+	if sys.ConsoleFont != "" && sys.ConsoleFont != config.ConsoleFontNone {
+		if err := b.configureConsoleFont(sys.ConsoleFont); err != nil {
+			return fmt.Errorf("font konsoli: %w", err)
+		}
+	}
+
+	if sys.LoginDefs != "" || sys.Umask != "" {
+		if err := b.configureLoginDefs(sys.LoginDefs, sys.Umask); err != nil {
+			return fmt.Errorf("login.defs: %w", err)
+		}
+	}
+
+	// Po presecie login.defs -- jawne password_* maja pierwszenstwo.
+	if sys.PasswordMaxDays != 0 || sys.PasswordWarnDaysSet {
+		if err := b.configurePasswordPolicy(sys.PasswordMaxDays, sys.PasswordWarnDays, sys.PasswordWarnDaysSet); err != nil {
+			return fmt.Errorf("polityka hasel: %w", err)
+		}
+	}
+
+	if err := b.configureTimeSync(sys.TimeSync, sys.NTPServers); err != nil {
+		return fmt.Errorf("synchronizacja czasu: %w", err)
+	}
+
+	if sys.DNS != "" {
+		if err := b.configureDNS(sys.DNS, sys.DNSServers); err != nil {
+			return fmt.Errorf("DNS: %w", err)
+		}
+	}
+
+	if sys.SecurityUpdates {
+		if err := b.configureSecurityUpdates(); err != nil {
+			return fmt.Errorf("poprawki bezpieczenstwa: %w", err)
+		}
+	}
+
+	if sys.Editor != "" {
+		if err := b.configureEditor(sys.Editor); err != nil {
+			return fmt.Errorf("edytor: %w", err)
+		}
+	}
+
+	if sys.InitramfsCompress != "" {
+		if err := b.configureInitramfsCompress(sys.InitramfsCompress); err != nil {
+			return fmt.Errorf("kompresja initramfs: %w", err)
+		}
+	}
+
+	if sys.Microcode {
+		// Oba pakiety: obraz (i nosnik live) nie zna procesora docelowego.
+		// Hooki initramfs-tools pakietow dopisuja mikrokod na poczatek
+		// initrd -- jadro laduje go przed czymkolwiek innym, bez zmian w GRUB.
+		util.Infof("  [system] mikrokod CPU (intel-microcode, amd64-microcode)")
+		if err := b.aptInstall("intel-microcode", "amd64-microcode"); err != nil {
+			return err
+		}
+	}
+
+	if sys.Nvidia != "" {
+		if err := b.configureNvidia(sys.Nvidia); err != nil {
+			return fmt.Errorf("sterownik NVIDIA: %w", err)
+		}
+	}
+
+	if sys.BashCompletion {
+		util.Infof("  [system] bash-completion")
+		if err := b.aptInstall("bash-completion"); err != nil {
+			return err
+		}
+	}
+
+	if sys.ColorPrompt {
+		util.Infof("  [system] kolorowy prompt (/etc/skel/.bashrc)")
+		path := filepath.Join(b.RootfsDir, "etc", "skel", ".bashrc")
+		if err := setConfigValue(path, "force_color_prompt", "force_color_prompt=yes"); err != nil {
+			return fmt.Errorf("kolorowy prompt: %w", err)
+		}
+	}
+
+	return nil
+}
+
+// configureInitramfsCompress instaluje kompresor, ustawia COMPRESS= w
+// initramfs.conf i przebudowuje initramfs wszystkich jader w obrazie
+// (initrd kopiowany do ISO i na dysk powstaje juz z nowa kompresja).
+// mkinitramfs przy braku kompresora po cichu wraca do gzip -- dlatego
+// sprawdzamy binarke przed przebudowa.
+func (b *Builder) configureInitramfsCompress(compress string) error {
+	util.Infof("  [system] kompresja initramfs: %s", compress)
+	if err := b.aptInstall(config.InitramfsCompressors[compress]); err != nil {
+		return err
+	}
+	found := false
+	for _, dir := range []string{"usr/bin", "bin"} {
+		if _, err := os.Stat(filepath.Join(b.RootfsDir, dir, compress)); err == nil {
+			found = true
+			break
+		}
+	}
+	if !found {
+		return fmt.Errorf("brak /usr/bin/%s w obrazie po instalacji pakietu %s",
+			compress, config.InitramfsCompressors[compress])
+	}
+
+	path := filepath.Join(b.RootfsDir, "etc", "initramfs-tools", "initramfs.conf")
+	if err := setConfigValue(path, "COMPRESS", "COMPRESS="+compress); err != nil {
+		return err
+	}
+	if err := b.sandboxExec("update-initramfs", "-u", "-k", "all"); err != nil {
+		return fmt.Errorf("update-initramfs: %w", err)
+	}
+	return nil
+}
+
+// configureEditor instaluje pakiet edytora i ustawia go jako alternatywe
+// "editor". --install przed --set: nie kazdy pakiet rejestruje sie sam
+// jako "editor" (a jesli juz jest -- tylko aktualizuje priorytet).
+func (b *Builder) configureEditor(name string) error {
+	ed := config.Editors[name]
+	util.Infof("  [system] domyslny edytor: %s (%s)", name, ed.Path)
+	if err := b.aptInstall(ed.Package); err != nil {
+		return err
+	}
+	if err := b.sandboxExec("update-alternatives", "--install", "/usr/bin/editor", "editor", ed.Path, "50"); err != nil {
+		return fmt.Errorf("update-alternatives --install editor %s: %w", ed.Path, err)
+	}
+	if err := b.sandboxExec("update-alternatives", "--set", "editor", ed.Path); err != nil {
+		return fmt.Errorf("update-alternatives --set editor %s: %w", ed.Path, err)
+	}
+	return nil
+}
+
+// configurePasswordPolicy ustawia PASS_MAX_DAYS / PASS_WARN_AGE w
+// /etc/login.defs. Dotyczy kont tworzonych PO buildzie (useradd czyta
+// login.defs) -- w tym konta zakladanego przez Calamares; konta systemowe
+// z debootstrap zostaja bez zmian. maxDays 0 albo setWarn == false --
+// klucz nie jest ruszany (PASS_WARN_AGE 0 to poprawna wartosc).
+func (b *Builder) configurePasswordPolicy(maxDays, warnDays int, setWarn bool) error {
+	path := filepath.Join(b.RootfsDir, "etc", "login.defs")
+	if maxDays != 0 {
+		util.Infof("  [system] PASS_MAX_DAYS %d", maxDays)
+		if err := setConfigValue(path, "PASS_MAX_DAYS", fmt.Sprintf("PASS_MAX_DAYS\t%d", maxDays)); err != nil {
+			return err
+		}
+	}
+	if setWarn {
+		util.Infof("  [system] PASS_WARN_AGE %d", warnDays)
+		if err := setConfigValue(path, "PASS_WARN_AGE", fmt.Sprintf("PASS_WARN_AGE\t%d", warnDays)); err != nil {
+			return err
+		}
+	}
+	return nil
+}
+
+// pamUmaskProfile to profil pam-auth-update wlaczajacy pam_umask: bez
+// niego UMASK z login.defs dotyczy tylko useradd (HOME_MODE), a nie
+// plikow tworzonych w sesji.
+const (
+	pamUmaskProfilePath = "usr/share/pam-configs/hackeros-umask"
+	pamUmaskProfile     = `Name: HackerOS: umask z /etc/login.defs (pam_umask)
+Default: yes
+Priority: 0
+Session-Type: Additional
+Session:
+	optional	pam_umask.so
+`
+)
+
+// configureLoginDefs naklada preset [system] -> login_defs i umask na
+// /etc/login.defs. Obraz powstaje przed instalacja, wiec konto tworzone
+// przez Calamares (useradd) dostaje juz HOME_MODE/UMASK i wygasanie hasel
+// z presetu.
+func (b *Builder) configureLoginDefs(preset, umask string) error {
+	path := filepath.Join(b.RootfsDir, "etc", "login.defs")
+	if preset != "" {
+		util.Infof("  [system] login.defs: preset %s", preset)
+		for _, d := range config.LoginDefsPresets[preset] {
+			if umask != "" && d.Key == "UMASK" {
+				continue
+			}
+			if err := setConfigValue(path, d.Key, d.Key+"\t"+d.Value); err != nil {
+				return err
+			}
+		}
+	}
+	if umask != "" {
+		util.Infof("  [system] umask %s", umask)
+		if err := setConfigValue(path, "UMASK", "UMASK\t"+umask); err != nil {
+			return err
+		}
+	}
+
+	if err := b.writeRootfsFile(pamUmaskProfilePath, pamUmaskProfile); err != nil {
+		return err
+	}
+	if err := b.sandboxExec("pam-auth-update", "--package"); err != nil {
+		return fmt.Errorf("pam-auth-update (pam_umask): %w", err)
+	}
+	return nil
+}
+
+// consoleFontUnit to usluga wybierajaca font konsoli przy starcie
+// ([system] -> console_font => auto).
+const (
+	consoleFontUnit       = "hackeros-console-font.service"
+	consoleFontScriptPath = "usr/local/sbin/hackeros-console-font"
+)
+
+// consoleFontScript: framebuffer o szerokosci od 2560 px (2560x1440, 4K)
+// to HiDPI -- wtedy HiDPIConsoleFont na tty1-tty6, inaczej font Debiana
+// bez zmian. Rozdzielczosc jest znana dopiero na docelowym sprzecie, wiec
+// decyzja zapada przy kazdym starcie (takze na nosniku live).
+const consoleFontScript = `#!/bin/sh
+# Wygenerowane przez hackeros-builder: [system] -> console_font => auto.
+size=$(cat /sys/class/graphics/fb0/virtual_size 2>/dev/null) || exit 0
+width=${size%%,*}
+[ "$width" -ge 2560 ] 2>/dev/null || exit 0
+for tty in /dev/tty[1-6]; do
+    setfont -C "$tty" /usr/share/consolefonts/` + config.HiDPIConsoleFont + `.psf.gz
+done
+`
+
+const consoleFontUnitContent = `[Unit]
+Description=Wiekszy font konsoli na ekranach HiDPI (hackeros-builder)
+ConditionPathExists=/sys/class/graphics/fb0/virtual_size
+After=console-setup.service
+
+[Service]
+Type=oneshot
+ExecStart=/` + consoleFontScriptPath + `
+
+[Install]
+WantedBy=multi-user.target
+`
+
+// configureConsoleFont instaluje console-setup i ustawia FONT= w
+// /etc/default/console-setup. console-setup.service naklada font przy
+// kazdym starcie (takze na nosniku live, zanim wystartuje instalator),
+// wiec nie trzeba osobno wolac setfont. Dla config.ConsoleFontAuto
+// zamiast FONT= wlaczana jest consoleFontUnit.
+func (b *Builder) configureConsoleFont(font string) error {
+	auto := font == config.ConsoleFontAuto
+	if auto {
+		font = config.HiDPIConsoleFont
+		util.Infof("  [system] font konsoli: auto (%s na ekranach HiDPI)", font)
+	} else {
+		util.Infof("  [system] font konsoli: %s", font)
+	}
+	if err := b.aptInstall("console-setup"); err != nil {
+		return err
+	}
+
+	fontFile := font + ".psf.gz"
+	if _, err := os.Stat(filepath.Join(b.RootfsDir, "usr", "share", "consolefonts", fontFile)); err != nil {
+		return fmt.Errorf(
+			"font %q nie istnieje w obrazie (/usr/share/consolefonts/%s) -- "+
+				"sprawdz nazwe w [system] -> console_font", font, fontFile)
+	}
+
+	if auto {
+		if err := b.writeRootfsFile(consoleFontScriptPath, consoleFontScript); err != nil {
+			return err
+		}
+		if err := os.Chmod(filepath.Join(b.RootfsDir, consoleFontScriptPath), 0o755); err != nil {
+			return err
+		}
+		return enableUnit(b.RootfsDir, consoleFontUnit, consoleFontUnitContent, "multi-user.target")
+	}
+
+	path := filepath.Join(b.RootfsDir, "etc", "default", "console-setup")
+	return setConfigValue(path, "FONT", fmt.Sprintf("FONT=%q", fontFile))
+}
+
+// aptInstall instaluje pakiety wewnatrz rootfs z tymi samymi opcjami dpkg
+// co installPackages (bez pytan o pliki konfiguracyjne).
+func (b *Builder) aptInstall(pkgs ...string) error {
+	args := append([]string{
+		"install", "-y", "--no-install-recommends",
+		"-o", "Dpkg::Options::=--force-confdef",
+		"-o", "Dpkg::Options::=--force-confold",
+	}, pkgs...)
+	if err := b.aptGet(args...); err != nil {
+		return fmt.Errorf("apt-get install %v: %w", pkgs, err)
+	}
+	return nil
+}
+
+// setConfigValue ustawia klucz w prostym pliku konfiguracyjnym w stylu
+// /etc/default/* albo /etc/login.defs (jedna linia na klucz). Zastepuje
+// pierwsza aktywna linie z tym kluczem; jesli jej nie ma -- pierwsza
+// zakomentowana ("#KLUCZ=...", "#KLUCZ wartosc" -- nie opis w komentarzu,
+// patrz commentSetsKey); w ostatecznosci dopisuje linie na koncu.
+// Plik jest tworzony jesli nie istnieje. line to pelna nowa tresc linii,
+// np. `FONT="ter-v32b.psf.gz"` albo "UMASK 077".
+func setConfigValue(path, key, line string) error {
+	data, err := os.ReadFile(path)
+	if err != nil && !os.IsNotExist(err) {
+		return err
+	}
+
+	var lines []string
+	if len(data) > 0 {
+		lines = strings.Split(strings.TrimSuffix(string(data), "\n"), "\n")
+	}
+
+	active, commented := -1, -1
+	for i, l := range lines {
+		trimmed := strings.TrimSpace(l)
+		if !strings.HasPrefix(trimmed, "#") {
+			if active == -1 && configLineHasKey(trimmed, key) {
+				active = i
+			}
+			continue
+		}
+		if commented == -1 && commentSetsKey(strings.TrimLeft(trimmed, "# \t"), key) {
+			commented = i
+		}
+	}
+
+	switch {
+	case active != -1:
+		lines[active] = line
+	case commented != -1:
+		lines[commented] = line
+	default:
+		lines = append(lines, line)
+	}
+
+	if err := os.MkdirAll(filepath.Dir(path), 0o755); err != nil {
+		return err
+	}
+	return os.WriteFile(path, []byte(strings.Join(lines, "\n")+"\n"), 0o644)
+}
+
+// configLineHasKey zwraca true jesli linia ustawia dany klucz -- w formie
+// "KLUCZ=..." albo "KLUCZ <wartosc>".
+func configLineHasKey(line, key string) bool {
+	if !strings.HasPrefix(line, key) {
+		return false
+	}
+	rest := line[len(key):]
+	return rest == "" || rest[0] == '=' || rest[0] == ' ' || rest[0] == '\t'
+}
+
+// commentSetsKey zwraca true jesli tresc zakomentowanej linii to
+// wylaczone przypisanie klucza: "KLUCZ=..." albo "KLUCZ <wartosc>" z
+// jedna wartoscia. Opis w komentarzu ("HOME_MODE is used by useradd(8)
+// ...") zostaje nietkniety.
+func commentSetsKey(line, key string) bool {
+	if !configLineHasKey(line, key) {
+		return false
+	}
+	rest := line[len(key):]
+	return strings.HasPrefix(rest, "=") || len(strings.Fields(rest)) == 1
+}
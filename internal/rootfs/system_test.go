@@ -0,0 +1,80 @@
+package rootfs
+
+import (
+	"os"
+	"path/filepath"
+	"strings"
+	"testing"
+
+	"github.com/HackerOS-Linux-System/hackeros-builder/internal/config"
+)
+
+func TestSetConfigValue_ReplacesActiveLine(t *testing.T) {
+	path := filepath.Join(t.TempDir(), "console-setup")
+	initial := "CHARMAP=\"UTF-8\"\n#FONT=\"stary.psf.gz\"\nFONT=\"Lat15-Fixed16.psf.gz\"\n"
+	if err := os.WriteFile(path, []byte(initial), 0o644); err != nil {
+		t.Fatal(err)
+	}
+
+	if err := setConfigValue(path, "FONT", `FONT="ter-v32b.psf.gz"`); err != nil {
+		t.Fatalf("setConfigValue zwrocilo blad: %v", err)
+	}
+
+	got, _ := os.ReadFile(path)
+	want := "CHARMAP=\"UTF-8\"\n#FONT=\"stary.psf.gz\"\nFONT=\"ter-v32b.psf.gz\"\n"
+	if string(got) != want {
+		t.Fatalf("oczekiwano:\n%s\notrzymano:\n%s", want, got)
+	}
+}
+
+func TestSetConfigValue_UncommentsOrAppends(t *testing.T) {
+	path := filepath.Join(t.TempDir(), "login.defs")
+	if err := os.WriteFile(path, []byte("# UMASK 022\nUMASK_OTHER 1\n"), 0o644); err != nil {
+		t.Fatal(err)
+	}
+
+	if err := setConfigValue(path, "UMASK", "UMASK 077"); err != nil {
+		t.Fatalf("setConfigValue zwrocilo blad: %v", err)
+	}
+	if err := setConfigValue(path, "PASS_MAX_DAYS", "PASS_MAX_DAYS 90"); err != nil {
+		t.Fatalf("setConfigValue zwrocilo blad: %v", err)
+	}
+
+	got, _ := os.ReadFile(path)
+	want := "UMASK 077\nUMASK_OTHER 1\nPASS_MAX_DAYS 90\n"
+	if string(got) != want {
+		t.Fatalf("oczekiwano:\n%s\notrzymano:\n%s", want, got)
+	}
+}
+
+func TestSetConfigValue_SkipsCommentProse(t *testing.T) {
+	path := filepath.Join(t.TempDir(), "login.defs")
+	// Fragment /etc/login.defs z Debiana (trixie).
+	initial := "#\n" +
+		"# HOME_MODE is used by useradd(8) and newusers(8) to set the mode for new\n" +
+		"# home directories.\n" +
+		"# If HOME_MODE is not set, the value of UMASK is used to create the mode.\n" +
+		"#HOME_MODE\t0700\n"
+	if err := os.WriteFile(path, []byte(initial), 0o644); err != nil {
+		t.Fatal(err)
+	}
+
+	if err := setConfigValue(path, "HOME_MODE", "HOME_MODE 0700"); err != nil {
+		t.Fatalf("setConfigValue zwrocilo blad: %v", err)
+	}
+
+	got, _ := os.ReadFile(path)
+	want := strings.Replace(initial, "#HOME_MODE\t0700\n", "HOME_MODE 0700\n", 1)
+	if string(got) != want {
+		t.Fatalf("oczekiwano:\n%s\notrzymano:\n%s", want, got)
+	}
+}
+
+func TestConsoleFontUnit(t *testing.T) {
+	if !strings.Contains(consoleFontScript, "/usr/share/consolefonts/"+config.HiDPIConsoleFont+".psf.gz") {
+		t.Errorf("skrypt nie naklada fontu HiDPI:\n%s", consoleFontScript)
+	}
+	if !strings.Contains(consoleFontUnitContent, "ExecStart=/usr/local/sbin/hackeros-console-font\n") {
+		t.Errorf("unit nie uruchamia skryptu:\n%s", consoleFontUnitContent)
+	}
+}
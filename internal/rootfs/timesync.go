@@ -0,0 +1,101 @@
+package rootfs
+
+import (
+	"fmt"
+	"os"
+	"path/filepath"
+	"strings"
+
+	"github.com/HackerOS-Linux-System/hackeros-builder/internal/config"
+	"github.com/HackerOS-Linux-System/hackeros-builder/internal/util"
+)
+
+// timeSyncUnit opisuje usluge synchronizacji czasu: pakiet, unit systemd,
+// target z [Install] WantedBy i plik z serwerami NTP.
+type timeSyncUnit struct {
+	pkg      string
+	unit     string
+	target   string
+	confPath string
+	conf     func(servers []string) string
+}
+
+var timeSyncUnits = map[string]timeSyncUnit{
+	config.TimeSyncTimesyncd: {
+		pkg: "systemd-timesyncd", unit: "systemd-timesyncd.service", target: "sysinit.target",
+		confPath: "etc/systemd/timesyncd.conf.d/hackeros.conf", conf: timesyncdDropIn,
+	},
+	config.TimeSyncChrony: {
+		pkg: "chrony", unit: "chrony.service", target: "multi-user.target",
+		confPath: "etc/chrony/sources.d/hackeros.sources", conf: chronySources,
+	},
+}
+
+// otherTimeDaemons to binarki innych demonow czasu -- ich obecnosc (z
+// package-lists projektu) wylacza domyslne timesyncd: pakiety sa
+// wzajemnie wykluczajace (time-daemon), apt usunalby wybor projektu.
+var otherTimeDaemons = []string{"usr/sbin/chronyd", "usr/sbin/ntpd", "usr/sbin/ntpsec"}
+
+// configureTimeSync instaluje i wlacza usluge z [system] -> time_sync
+// (domyslnie systemd-timesyncd) -- zegar bez synchronizacji (VM bez RTC,
+// padnieta bateria) psuje TLS i apt. Na koncu sprawdza, ze usluga jest
+// wlaczona w obrazie.
+func (b *Builder) configureTimeSync(mode string, servers []string) error {
+	if mode == config.TimeSyncNone {
+		return nil
+	}
+	if mode == "" {
+		for _, d := range otherTimeDaemons {
+			if _, err := os.Stat(filepath.Join(b.RootfsDir, d)); err == nil {
+				util.Infof("  [system] synchronizacja czasu: /%s juz w obrazie -- timesyncd pominiety", d)
+				return nil
+			}
+		}
+		mode = config.TimeSyncTimesyncd
+	}
+
+	ts := timeSyncUnits[mode]
+	util.Infof("  [system] synchronizacja czasu: %s", ts.unit)
+	if err := b.aptInstall(ts.pkg); err != nil {
+		return err
+	}
+
+	if len(servers) > 0 {
+		path := filepath.Join(b.RootfsDir, ts.confPath)
+		if err := os.MkdirAll(filepath.Dir(path), 0o755); err != nil {
+			return err
+		}
+		if err := os.WriteFile(path, []byte(ts.conf(servers)), 0o644); err != nil {
+			return err
+		}
+	}
+
+	// postinst wlacza usluge przez deb-systemd-helper -- gdyby nie
+	// (np. polityka projektu w hookach), wlaczamy jawnie.
+	wants := filepath.Join(b.RootfsDir, "etc", "systemd", "system", ts.target+".wants", ts.unit)
+	if _, err := os.Lstat(wants); err != nil {
+		if err := b.sandboxExec("systemctl", "enable", ts.unit); err != nil {
+			return fmt.Errorf("systemctl enable %s: %w", ts.unit, err)
+		}
+		if _, err := os.Lstat(wants); err != nil {
+			return fmt.Errorf("%s nie jest wlaczony (brak %s)", ts.unit, wants)
+		}
+	}
+	return nil
+}
+
+// timesyncdDropIn zwraca drop-in timesyncd z wlasnymi serwerami NTP.
+func timesyncdDropIn(servers []string) string {
+	return "# Wygenerowane przez hackeros-builder z [system] -> ntp_servers.\n" +
+		"[Time]\nNTP=" + strings.Join(servers, " ") + "\n"
+}
+
+// chronySources zwraca plik sources.d chrony z wlasnymi serwerami NTP.
+func chronySources(servers []string) string {
+	var sb strings.Builder
+	sb.WriteString("# Wygenerowane przez hackeros-builder z [system] -> ntp_servers.\n")
+	for _, s := range servers {
+		fmt.Fprintf(&sb, "server %s iburst\n", s)
+	}
+	return sb.String()
+}
@@ -0,0 +1,21 @@
+package rootfs
+
+import "testing"
+
+func TestTimeSyncConfigs(t *testing.T) {
+	servers := []string{"ntp1.example.org", "192.0.2.10"}
+
+	got := timesyncdDropIn(servers)
+	want := "# Wygenerowane przez hackeros-builder z [system] -> ntp_servers.\n" +
+		"[Time]\nNTP=ntp1.example.org 192.0.2.10\n"
+	if got != want {
+		t.Errorf("timesyncd:\noczekiwano:\n%s\notrzymano:\n%s", want, got)
+	}
+
+	got = chronySources(servers)
+	want = "# Wygenerowane przez hackeros-builder z [system] -> ntp_servers.\n" +
+		"server ntp1.example.org iburst\nserver 192.0.2.10 iburst\n"
+	if got != want {
+		t.Errorf("chrony:\noczekiwano:\n%s\notrzymano:\n%s", want, got)
+	}
+}
@@ -0,0 +1,68 @@
+package rootfs
+
+import (
+	"fmt"
+	"strings"
+
+	"github.com/HackerOS-Linux-System/hackeros-builder/internal/util"
+)
+
+// autoUpgradesPath to plik, ktory postinst unattended-upgrades tworzy po
+// odpowiedzi "tak" w debconf -- wlacza codzienne "apt-get update" i
+// unattended-upgrade (apt-daily*.timer).
+const autoUpgradesPath = "etc/apt/apt.conf.d/20auto-upgrades"
+
+const autoUpgrades = "// Wygenerowane przez hackeros-builder ([system] -> security_updates).\n" +
+	"APT::Periodic::Update-Package-Lists \"1\";\n" +
+	"APT::Periodic::Unattended-Upgrade \"1\";\n"
+
+// unattendedOriginsPath: wlasny drop-in po 50unattended-upgrades pakietu.
+// Tamten plik to conffile (wlasna kopia dawalaby pytanie dpkg przy kazdej
+// jego zmianie), a jego Origins-Pattern obejmuje tez glowne archiwum
+// wydania (label=Debian, point release) -- "#clear" zastepuje te liste.
+const unattendedOriginsPath = "etc/apt/apt.conf.d/52hackeros-unattended-upgrades"
+
+// unattendedOrigins zwraca drop-in ograniczajacy unattended-upgrades do
+// archiwum bezpieczenstwa wydania. Do buster suite to "<wydanie>/updates",
+// a Codename w jego Release to samo "<wydanie>".
+func unattendedOrigins(release string) string {
+	codename := strings.TrimSuffix(securitySuite(release), "/updates")
+	return "// Wygenerowane przez hackeros-builder ([system] -> security_updates).\n" +
+		"#clear Unattended-Upgrade::Allowed-Origins;\n" +
+		"#clear Unattended-Upgrade::Origins-Pattern;\n" +
+		"Unattended-Upgrade::Origins-Pattern {\n" +
+		"\t\"origin=Debian,codename=" + codename + ",label=Debian-Security\";\n" +
+		"};\n"
+}
+
+// unattendedCheck sprawdza konfiguracje apt po zapisie: "apt-config dump"
+// konczy sie bledem przy bledzie skladni w apt.conf.d, a kazde aktywne
+// zrodlo unattended-upgrades (Origins-Pattern albo Allowed-Origins) musi
+// byc archiwum bezpieczenstwa Debiana.
+const unattendedCheck = `out=$(apt-config dump) || exit 1; ` +
+	`echo "$out" | grep -q '^APT::Periodic::Unattended-Upgrade "1"' || { echo "20auto-upgrades nie jest wczytywany" >&2; exit 1; }; ` +
+	`origins=$(echo "$out" | grep -E '^Unattended-Upgrade::(Origins-Pattern|Allowed-Origins):: '); ` +
+	`[ -n "$origins" ] || { echo "unattended-upgrades bez zrodel w Origins-Pattern" >&2; exit 1; }; ` +
+	`if echo "$origins" | grep -v '^Unattended-Upgrade::Origins-Pattern:: "origin=Debian,codename=[^,]*,label=Debian-Security";$'; then ` +
+	`echo "unattended-upgrades: zrodla spoza archiwum bezpieczenstwa (wyzej)" >&2; exit 1; fi`
+
+// configureSecurityUpdates instaluje unattended-upgrades i wlacza
+// automatyczne poprawki bezpieczenstwa -- tylko z archiwum Debian-Security
+// (unattendedOrigins). apt-listchanges zapisuje zmiany (changelogi)
+// instalowanych poprawek.
+func (b *Builder) configureSecurityUpdates() error {
+	util.Infof("  [system] automatyczne poprawki bezpieczenstwa (unattended-upgrades)")
+	if err := b.aptInstall("unattended-upgrades", "apt-listchanges"); err != nil {
+		return err
+	}
+	if err := b.writeRootfsFile(autoUpgradesPath, autoUpgrades); err != nil {
+		return err
+	}
+	if err := b.writeRootfsFile(unattendedOriginsPath, unattendedOrigins(b.Config.Release)); err != nil {
+		return err
+	}
+	if err := b.sandboxExec("sh", "-c", unattendedCheck); err != nil {
+		return fmt.Errorf("weryfikacja konfiguracji unattended-upgrades: %w", err)
+	}
+	return nil
+}
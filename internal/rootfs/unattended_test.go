@@ -0,0 +1,21 @@
+package rootfs
+
+import (
+	"strings"
+	"testing"
+)
+
+func TestUnattendedOrigins_OnlySecurityArchive(t *testing.T) {
+	for release, want := range map[string]string{
+		"trixie": `"origin=Debian,codename=trixie-security,label=Debian-Security";`,
+		"buster": `"origin=Debian,codename=buster,label=Debian-Security";`,
+	} {
+		got := unattendedOrigins(release)
+		if !strings.Contains(got, "#clear Unattended-Upgrade::Origins-Pattern;\n") {
+			t.Errorf("%s: drop-in musi czyscic Origins-Pattern z 50unattended-upgrades:\n%s", release, got)
+		}
+		if !strings.Contains(got, want) || strings.Count(got, "origin=") != 1 {
+			t.Errorf("%s: oczekiwano jedynego zrodla %s, otrzymano:\n%s", release, want, got)
+		}
+	}
+}
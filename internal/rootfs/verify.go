@@ -0,0 +1,129 @@
+package rootfs
+
+import (
+	"fmt"
+	"os"
+	"path/filepath"
+	"sort"
+	"strings"
+
+	"github.com/HackerOS-Linux-System/hackeros-builder/internal/util"
+)
+
+const (
+	dpkgStatusPath = "var/lib/dpkg/status"
+
+	// debsumsLog to plik w rootfs, do ktorego debsums zapisuje wynik --
+	// sandbox przekazuje wyjscie tylko na terminal.
+	debsumsLog = "tmp-hackeros-debsums.log"
+)
+
+// verifyPackages sprawdza zainstalowane pakiety ([project] ->
+// verify_packages): "apt-get check" (spojnosc zaleznosci -- blad przerywa
+// build) i "debsums -s" (pliki rozne od sum MD5 z pakietow albo brakujace
+// -- ostrzezenia, bo includes.chroot i hooki moga je celowo zmieniac).
+// debsums jest instalowany na czas sprawdzenia i usuwany razem z
+// zaleznosciami, ktore dociagnal. Zwraca liste problemow debsums.
+func (b *Builder) verifyPackages() ([]string, error) {
+	if err := b.sandboxExec("apt-get", "check"); err != nil {
+		return nil, fmt.Errorf("apt-get check (niespelnione zaleznosci pakietow): %w", err)
+	}
+
+	before, err := b.installedPackages()
+	if err != nil {
+		return nil, err
+	}
+	var added []string
+	if !before["debsums"] {
+		if err := b.aptInstall("debsums"); err != nil {
+			return nil, err
+		}
+		after, err := b.installedPackages()
+		if err != nil {
+			return nil, err
+		}
+		for pkg := range after {
+			if !before[pkg] {
+				added = append(added, pkg)
+			}
+		}
+		sort.Strings(added)
+	}
+
+	util.Infof("  debsums: sprawdzanie sum kontrolnych plikow pakietow...")
+	logPath := filepath.Join(b.RootfsDir, debsumsLog)
+	runErr := b.sandboxExec("sh", "-c", "debsums -s >/"+debsumsLog+" 2>&1 || true")
+	out, readErr := os.ReadFile(logPath)
+	os.Remove(logPath)
+
+	if len(added) > 0 {
+		args := append([]string{"purge", "-y"}, added...)
+		if err := b.aptGet(args...); err != nil {
+			util.Warnf("Nie mozna usunac debsums z obrazu (%v): %v", added, err)
+		}
+	}
+
+	if runErr != nil {
+		return nil, fmt.Errorf("debsums: %w", runErr)
+	}
+	if readErr != nil {
+		return nil, fmt.Errorf("debsums: %w", readErr)
+	}
+
+	problems := debsumsProblems(string(out))
+	if len(problems) == 0 {
+		util.Infof("  debsums: wszystkie pliki pakietow zgodne")
+		return nil, nil
+	}
+	util.Warnf("debsums: %d plik(ow) rozni sie od pakietow (zmiany z includes.chroot/hookow sa oczekiwane):", len(problems))
+	for _, p := range problems {
+		util.Warnf("  %s", p)
+	}
+	return problems, nil
+}
+
+// installedPackages zwraca pakiety zainstalowane w rootfs wg
+// /var/lib/dpkg/status.
+func (b *Builder) installedPackages() (map[string]bool, error) {
+	data, err := os.ReadFile(filepath.Join(b.RootfsDir, dpkgStatusPath))
+	if err != nil {
+		return nil, err
+	}
+	return parseDpkgStatus(string(data)), nil
+}
+
+// parseDpkgStatus zwraca nazwy pakietow ze stanem "install ok installed"
+// (pakiety usuniete z pozostawiona konfiguracja maja inny stan).
+func parseDpkgStatus(status string) map[string]bool {
+	pkgs := map[string]bool{}
+	for _, para := range strings.Split(status, "\n\n") {
+		var name string
+		installed := false
+		for _, line := range strings.Split(para, "\n") {
+			if v, ok := strings.CutPrefix(line, "Package: "); ok {
+				name = strings.TrimSpace(v)
+			}
+			if v, ok := strings.CutPrefix(line, "Status: "); ok {
+				installed = strings.TrimSpace(v) == "install ok installed"
+			}
+		}
+		if name != "" && installed {
+			pkgs[name] = true
+		}
+	}
+	return pkgs
+}
+
+// debsumsProblems wybiera z wyjscia "debsums -s" zmienione i brakujace
+// pliki ("debsums: changed file /usr/bin/x (from y package)"). Pozostale
+// linie (np. pakiety bez md5sums) sa pomijane.
+func debsumsProblems(out string) []string {
+	var problems []string
+	for _, line := range strings.Split(out, "\n") {
+		line = strings.TrimPrefix(strings.TrimSpace(line), "debsums: ")
+		if strings.HasPrefix(line, "changed file ") || strings.HasPrefix(line, "missing file ") {
+			problems = append(problems, line)
+		}
+	}
+	return problems
+}
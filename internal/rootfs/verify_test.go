@@ -0,0 +1,33 @@
+package rootfs
+
+import (
+	"reflect"
+	"testing"
+)
+
+func TestParseDpkgStatus(t *testing.T) {
+	status := "Package: bash\nStatus: install ok installed\nVersion: 5.2\n\n" +
+		"Package: debsums\nStatus: deinstall ok config-files\n\n" +
+		"Package: libfile-fnmatch-perl\nVersion: 0.02\nStatus: install ok installed\n"
+	got := parseDpkgStatus(status)
+	want := map[string]bool{"bash": true, "libfile-fnmatch-perl": true}
+	if !reflect.DeepEqual(got, want) {
+		t.Errorf("oczekiwano %v, otrzymano %v", want, got)
+	}
+}
+
+func TestDebsumsProblems(t *testing.T) {
+	out := "debsums: no md5sums for hackeros-branding\n" +
+		"debsums: changed file /usr/share/plymouth/themes/default.plymouth (from plymouth package)\n" +
+		"debsums: missing file /usr/share/doc/bash/README (from bash package)\n"
+	want := []string{
+		"changed file /usr/share/plymouth/themes/default.plymouth (from plymouth package)",
+		"missing file /usr/share/doc/bash/README (from bash package)",
+	}
+	if got := debsumsProblems(out); !reflect.DeepEqual(got, want) {
+		t.Errorf("oczekiwano %v, otrzymano %v", want, got)
+	}
+	if got := debsumsProblems(""); len(got) != 0 {
+		t.Errorf("puste wyjscie: oczekiwano braku problemow, otrzymano %v", got)
+	}
+}
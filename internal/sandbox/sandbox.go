@@ -7,6 +7,9 @@ import (
 	"os/exec"
 	"path/filepath"
 	"strings"
+	"time"
+
+	"github.com/HackerOS-Linux-System/hackeros-builder/internal/util"
 )
 
 // noninteractiveEnv to zmienne srodowiskowe wstrzykiwane do KAZDEGO procesu
@@ -28,23 +31,35 @@ var noninteractiveEnv = []string{
 // srodowisku (wlasny namespace mount+PID+UTS) przez unshare + chroot.
 // stdout i stderr sa przekazywane na zywo do terminala (streaming).
 func Exec(rootfsDir string, command string, args ...string) error {
-	return execInternal(rootfsDir, nil, nil, command, args...)
+	return execInternal(rootfsDir, nil, nil, 0, command, args...)
+}
+
+// ExecTimeout jak Exec, ale przerywa komende po timeout (0 -- bez limitu).
+// Zabity zostaje caly namespace (--kill-child), a blad opakowuje
+// util.ErrTimeout.
+func ExecTimeout(rootfsDir string, timeout time.Duration, command string, args ...string) error {
+	return execInternal(rootfsDir, nil, nil, timeout, command, args...)
 }
 
 // ExecEnv jak Exec, ale dopisuje dodatkowe zmienne srodowiskowe do
 // noninteractiveEnv (format "KLUCZ=WARTOSC").
 func ExecEnv(rootfsDir string, extraEnv []string, command string, args ...string) error {
-	return execInternal(rootfsDir, extraEnv, nil, command, args...)
+	return execInternal(rootfsDir, extraEnv, nil, 0, command, args...)
+}
+
+// ExecEnvTimeout laczy ExecEnv i ExecTimeout.
+func ExecEnvTimeout(rootfsDir string, extraEnv []string, timeout time.Duration, command string, args ...string) error {
+	return execInternal(rootfsDir, extraEnv, nil, timeout, command, args...)
 }
 
 // ExecWithStdin jak Exec, ale podaje stdinData na stdin komendy wewnatrz
 // sandbox (np. dla "debconf-set-selections", ktore czyta preseed z stdin).
 func ExecWithStdin(rootfsDir string, stdinData []byte, command string, args ...string) error {
-	return execInternal(rootfsDir, nil, stdinData, command, args...)
+	return execInternal(rootfsDir, nil, stdinData, 0, command, args...)
 }
 
-// execInternal to wspolna implementacja Exec/ExecEnv/ExecWithStdin.
-func execInternal(rootfsDir string, extraEnv []string, stdin []byte, command string, args ...string) error {
+// execInternal to wspolna implementacja Exec*.
+func execInternal(rootfsDir string, extraEnv []string, stdin []byte, timeout time.Duration, command string, args ...string) error {
 	// Zapewnij istnienie punktow montowania wewnatrz rootfs przed wejsciem
 	// do namespace -- chroot nie tworzy ich automatycznie, a mount -t proc
 	// wysypie sie jesli katalog docelowy nie istnieje.
@@ -77,7 +92,9 @@ func execInternal(rootfsDir string, extraEnv []string, stdin []byte, command str
 		cmd.Stdin = bytes.NewReader(stdin)
 	}
 
-	if err := cmd.Run(); err != nil {
+	// Po przekroczeniu limitu RunCmdTimeout zabija unshare, a --kill-child
+	// i koniec namespace PID zabieraja wszystko, co w nim dzialalo.
+	if err := util.RunCmdTimeout(cmd, timeout); err != nil {
 		return fmt.Errorf("sandbox: exec %q w %s nie powiodl sie: %w", command, rootfsDir, err)
 	}
 	return nil
@@ -86,28 +103,29 @@ func execInternal(rootfsDir string, extraEnv []string, stdin []byte, command str
 // buildMountAndChrootScript buduje skrypt sh wykonywany wewnatrz nowego
 // namespace mount (po unshare). Skrypt:
 //  1. Montuje /proc,/sys,/dev,/dev/pts wewnatrz rootfsDir (prywatnie).
+//     Gdy /etc/resolv.conf w rootfs to wiszacy symlink (stub
+//     systemd-resolved w /run, patrz [system] -> dns), montuje tmpfs na
+//     /run z kopia resolv.conf hosta -- apt i hooki dalej rozwiazuja nazwy.
+//     Obraz sie nie zmienia: tmpfs znika razem z namespace.
 //  2. Rejestruje trap EXIT ktory odmontowuje je przy kazdym wyjsciu
 //     (normalnym, bledzie, przerwaniu) -- defensywnie, bo namespace
 //     i tak by to sprzatnal, ale trap eliminuje rzadkie edge-case'y
 //     ze starszymi wersjami kernela gdzie --kill-child nie dzialal.
 //  3. Wykonuje chroot rootfsDir command args...
 //
-// Argumenty sa shell-escapowane apostrofami (apostrofy w tresci sa
-// zamieniane na '\”) -- wystarczajace dla sciezek i nazw pakietow Debiana.
+// Sciezka, komenda i argumenty przechodza przez ShellQuote -- dowolna tresc
+// (spacje, $, `, cudzyslowy, nowe linie) trafia do chroot jako dokladnie
+// jeden argument, bez interpretacji przez sh.
 func buildMountAndChrootScript(rootfsDir, command string, args []string) string {
-	quot := func(s string) string {
-		return "'" + strings.ReplaceAll(s, "'", `'\''`) + "'"
-	}
-
-	qRootfs := quot(rootfsDir)
-	qCmd := quot(command)
+	qRootfs := ShellQuote(rootfsDir)
+	qCmd := ShellQuote(command)
 
 	var qArgs strings.Builder
 	for i, a := range args {
 		if i > 0 {
 			qArgs.WriteByte(' ')
 		}
-		qArgs.WriteString(quot(a))
+		qArgs.WriteString(ShellQuote(a))
 	}
 
 	return fmt.Sprintf(`set -e
@@ -116,7 +134,13 @@ mount -t proc    proc       "$ROOTFS/proc"
 mount -t sysfs   sysfs      "$ROOTFS/sys"
 mount --bind     /dev       "$ROOTFS/dev"
 mount --bind     /dev/pts   "$ROOTFS/dev/pts"
+if [ -L "$ROOTFS/etc/resolv.conf" ] && [ ! -e "$ROOTFS/etc/resolv.conf" ]; then
+    mount -t tmpfs tmpfs "$ROOTFS/run"
+    mkdir -p "$ROOTFS/run/systemd/resolve"
+    cp -L /etc/resolv.conf "$ROOTFS/run/systemd/resolve/stub-resolv.conf"
+fi
 _cleanup() {
+    umount -l "$ROOTFS/run"     2>/dev/null || true
     umount -l "$ROOTFS/dev/pts" 2>/dev/null || true
     umount -l "$ROOTFS/dev"     2>/dev/null || true
     umount -l "$ROOTFS/sys"     2>/dev/null || true
@@ -126,3 +150,11 @@ trap _cleanup EXIT
 exec chroot "$ROOTFS" %s %s
 `, qRootfs, qCmd, qArgs.String())
 }
+
+// ShellQuote zwraca s jako pojedynczy argument sh: w apostrofach, z kazdym
+// apostrofem w tresci zamienionym na '\'' (zamkniecie, escapowany
+// apostrof, otwarcie). Wewnatrz apostrofow sh niczego nie interpretuje,
+// wiec to jedyna zamiana potrzebna dla dowolnego ciagu bez bajtu NUL.
+func ShellQuote(s string) string {
+	return "'" + strings.ReplaceAll(s, "'", `'\''`) + "'"
+}
@@ -0,0 +1,46 @@
+package sandbox
+
+import (
+	"os/exec"
+	"strings"
+	"testing"
+)
+
+var adversarialInputs = []string{
+	"",
+	"zwykly",
+	"ze spacja",
+	"it's",
+	`"cudzyslow"`,
+	"$HOME $(id) `id`",
+	"a;b && c || d | e",
+	"'; rm -rf / #",
+	"linia1\nlinia2",
+	`\\backslash\`,
+}
+
+func TestShellQuote_RoundTripThroughSh(t *testing.T) {
+	if _, err := exec.LookPath("sh"); err != nil {
+		t.Skip("brak sh w PATH")
+	}
+	for _, in := range adversarialInputs {
+		out, err := exec.Command("sh", "-c", "printf '%s' "+ShellQuote(in)).Output()
+		if err != nil {
+			t.Fatalf("%q: sh zwrocil blad: %v", in, err)
+		}
+		if string(out) != in {
+			t.Errorf("%q: po przejsciu przez sh otrzymano %q", in, out)
+		}
+	}
+}
+
+func TestBuildMountAndChrootScript_QuotesEveryArg(t *testing.T) {
+	script := buildMountAndChrootScript("/tmp/root fs", "useradd", []string{"-c", "$(reboot)", "o'brien"})
+	want := `exec chroot "$ROOTFS" 'useradd' '-c' '$(reboot)' 'o'\''brien'`
+	if !strings.Contains(script, want) {
+		t.Errorf("oczekiwano linii %q w skrypcie:\n%s", want, script)
+	}
+	if !strings.Contains(script, "ROOTFS='/tmp/root fs'\n") {
+		t.Errorf("sciezka rootfs nie jest zacytowana:\n%s", script)
+	}
+}
@@ -83,6 +83,8 @@ func (m *Manager) PrepareAll() error {
 		return fmt.Errorf("toolchain: tworzenie %s: %w", m.binDir, err)
 	}
 
+	m.removeStaleTempDirs()
+
 	m.preparedPath = m.binDir + ":" + os.Getenv("PATH")
 
 	var missing []Tool
@@ -150,15 +152,37 @@ func (m *Manager) toolAvailable(binary string) bool {
 	return false
 }
 
+// tmpDirPrefix to prefiks katalogow tymczasowych downloadAndExtract w binDir.
+const tmpDirPrefix = ".tmp-"
+
+// removeStaleTempDirs usuwa katalogi tymczasowe pozostawione przez
+// przerwany build (SIGKILL/awaria zasilania -- defer nie zdazyl posprzatac).
+// Wywolywane na starcie, pod blokada workDir, wiec nie koliduje z innym
+// buildem.
+func (m *Manager) removeStaleTempDirs() {
+	entries, err := os.ReadDir(m.binDir)
+	if err != nil {
+		return
+	}
+	for _, e := range entries {
+		if e.IsDir() && strings.HasPrefix(e.Name(), tmpDirPrefix) {
+			util.Debugf("toolchain: usuwam pozostalosc %s", e.Name())
+			os.RemoveAll(filepath.Join(m.binDir, e.Name()))
+		}
+	}
+}
+
 // downloadAndExtract pobiera pakiety .deb dla danego Tool przez "apt-get download"
 // do tymczasowego podkatalogu w binDir, rozpakuje je przez "dpkg-deb --extract"
 // i kopiuje binarki (usr/bin/*, usr/sbin/*, sbin/*, bin/*) do binDir.
 // Nie modyfikuje bazy danych dpkg hosta.
 func (m *Manager) downloadAndExtract(t Tool) error {
-	// Tymczasowy katalog na pobrane .deb i rozpakowane drzewa dla tego narzedzia
-	tmpDir := filepath.Join(m.binDir, ".tmp-"+t.Binary)
-	if err := os.MkdirAll(tmpDir, 0o755); err != nil {
-		return fmt.Errorf("mkdir %s: %w", tmpDir, err)
+	// Tymczasowy katalog na pobrane .deb i rozpakowane drzewa dla tego
+	// narzedzia -- unikalny (MkdirTemp), wiec pozostalosci po przerwanym
+	// buildzie nie mieszaja sie z nowym pobraniem.
+	tmpDir, err := os.MkdirTemp(m.binDir, tmpDirPrefix+t.Binary+"-")
+	if err != nil {
+		return fmt.Errorf("mkdir tymczasowy w %s: %w", m.binDir, err)
 	}
 	defer os.RemoveAll(tmpDir) // sprzatamy po sobie bez wzgledu na wynik
 
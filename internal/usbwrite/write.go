@@ -0,0 +1,283 @@
+package usbwrite
+
+import (
+	"bufio"
+	"crypto/sha256"
+	"encoding/hex"
+	"fmt"
+	"io"
+	"os"
+	"path/filepath"
+	"regexp"
+	"strconv"
+	"strings"
+	"syscall"
+
+	"github.com/HackerOS-Linux-System/hackeros-builder/internal/util"
+)
+
+const (
+	sysBlockDir = "/sys/block"
+	mountsPath  = "/proc/mounts"
+
+	// copyBufferSize: pendrive'y zapisuja duze bloki wyraznie szybciej
+	// niz domyslne 32 KiB io.Copy.
+	copyBufferSize = 4 << 20
+)
+
+// Options to parametry "hackeros-builder write".
+type Options struct {
+	ISO        string // plik .iso (np. wynik "build iso")
+	Device     string // caly dysk, np. /dev/sdb albo /dev/disk/by-id/usb-...
+	AllowFixed bool   // --allow-fixed: zgoda na dysk niewymienny
+	Yes        bool   // --yes: bez pytania o potwierdzenie
+}
+
+// Write zapisuje obraz ISO na nosnik USB i weryfikuje zapis, czytajac
+// nosnik z powrotem i porownujac SHA256 z plikiem. Odmawia, gdy
+// urzadzenie to partycja, dysk niewymienny (bez AllowFixed), ma
+// zamontowane partycje albo jest mniejsze od obrazu.
+func Write(opts Options) error {
+	dev, name, err := resolveDisk(opts.Device)
+	if err != nil {
+		return err
+	}
+
+	removable, err := isRemovable(sysBlockDir, name)
+	if err != nil {
+		return err
+	}
+	if !removable && !opts.AllowFixed {
+		return fmt.Errorf("usbwrite: %s nie jest nosnikiem wymiennym (USB) -- "+
+			"to moze byc dysk systemowy. Jesli na pewno chcesz go nadpisac, dodaj --allow-fixed", dev)
+	}
+
+	mounts, err := os.ReadFile(mountsPath)
+	if err != nil {
+		return fmt.Errorf("usbwrite: odczyt %s: %w", mountsPath, err)
+	}
+	if m := mountedPartitions(string(mounts), name); len(m) > 0 {
+		return fmt.Errorf("usbwrite: %s ma zamontowane partycje (%s) -- odmontuj je przed zapisem",
+			dev, strings.Join(m, ", "))
+	}
+
+	st, err := os.Stat(opts.ISO)
+	if err != nil {
+		return fmt.Errorf("usbwrite: %w", err)
+	}
+	devSize, err := diskSize(sysBlockDir, name)
+	if err != nil {
+		return err
+	}
+	if st.Size() > devSize {
+		return fmt.Errorf("usbwrite: obraz %s (%d MiB) nie zmiesci sie na %s (%d MiB)",
+			opts.ISO, st.Size()>>20, dev, devSize>>20)
+	}
+
+	desc := fmt.Sprintf("%s (%s, %d MiB)", dev, diskModel(sysBlockDir, name), devSize>>20)
+	if !opts.Yes {
+		ok, err := confirm(os.Stdin, desc, name)
+		if err != nil {
+			return err
+		}
+		if !ok {
+			return fmt.Errorf("usbwrite: przerwano -- nic nie zostalo zapisane na %s", dev)
+		}
+	}
+
+	util.Infof("Zapis %s na %s...", opts.ISO, desc)
+	want, err := copyImage(opts.ISO, dev, st.Size())
+	if err != nil {
+		return err
+	}
+
+	// Bez oproznienia buforow odczyt kontrolny przeczytalby to, co jeszcze
+	// lezy w page cache jadra, a nie to, co trafilo na nosnik.
+	if res, err := util.Run("blockdev", "--flushbufs", dev); err != nil || !res.Ok() {
+		util.Warnf("blockdev --flushbufs %s nie powiodlo sie -- weryfikacja moze czytac z cache", dev)
+	}
+
+	util.Infof("Weryfikacja zapisu (odczyt %d MiB z %s)...", st.Size()>>20, dev)
+	got, err := hashPrefix(dev, st.Size())
+	if err != nil {
+		return err
+	}
+	if got != want {
+		return fmt.Errorf("usbwrite: weryfikacja nieudana -- SHA256 nosnika %s, obrazu %s. "+
+			"Nosnik moze byc uszkodzony albo falszywej pojemnosci", got, want)
+	}
+	util.Infof("Zapis zweryfikowany (SHA256 %s)", want)
+	return nil
+}
+
+// resolveDisk zwraca sciezke urzadzenia po rozwinieciu symlinkow
+// (/dev/disk/by-id/...) i jego nazwe w /sys/block. Partycje (sdb1) nie
+// maja tam wpisu -- obraz ISO zapisuje sie na caly dysk.
+func resolveDisk(device string) (string, string, error) {
+	dev, err := filepath.EvalSymlinks(device)
+	if err != nil {
+		return "", "", fmt.Errorf("usbwrite: %w", err)
+	}
+	st, err := os.Stat(dev)
+	if err != nil {
+		return "", "", fmt.Errorf("usbwrite: %w", err)
+	}
+	if st.Mode()&os.ModeDevice == 0 || st.Mode()&os.ModeCharDevice != 0 {
+		return "", "", fmt.Errorf("usbwrite: %s nie jest urzadzeniem blokowym", device)
+	}
+	name := filepath.Base(dev)
+	if _, err := os.Stat(filepath.Join(sysBlockDir, name)); err != nil {
+		return "", "", fmt.Errorf("usbwrite: %s to partycja albo nie jest dyskiem -- podaj caly dysk, np. /dev/sdb", device)
+	}
+	return dev, name, nil
+}
+
+// isRemovable zwraca true dla nosnika wymiennego: flaga "removable"
+// jadra albo dysk podlaczony przez USB (dyski USB/SSD w obudowie czesto
+// maja removable=0).
+func isRemovable(sysBlock, name string) (bool, error) {
+	flag, err := os.ReadFile(filepath.Join(sysBlock, name, "removable"))
+	if err != nil {
+		return false, fmt.Errorf("usbwrite: %w", err)
+	}
+	if strings.TrimSpace(string(flag)) == "1" {
+		return true, nil
+	}
+	path, err := filepath.EvalSymlinks(filepath.Join(sysBlock, name))
+	if err != nil {
+		return false, fmt.Errorf("usbwrite: %w", err)
+	}
+	return strings.Contains(path, "/usb"), nil
+}
+
+// diskSize zwraca rozmiar dysku w bajtach (/sys/block/<dysk>/size jest
+// zawsze w sektorach 512 B).
+func diskSize(sysBlock, name string) (int64, error) {
+	data, err := os.ReadFile(filepath.Join(sysBlock, name, "size"))
+	if err != nil {
+		return 0, fmt.Errorf("usbwrite: %w", err)
+	}
+	sectors, err := strconv.ParseInt(strings.TrimSpace(string(data)), 10, 64)
+	if err != nil {
+		return 0, fmt.Errorf("usbwrite: rozmiar %s: %w", name, err)
+	}
+	return sectors * 512, nil
+}
+
+// diskModel zwraca model dysku do komunikatu potwierdzenia ("?" gdy
+// jadro go nie podaje).
+func diskModel(sysBlock, name string) string {
+	data, err := os.ReadFile(filepath.Join(sysBlock, name, "device", "model"))
+	if err != nil || strings.TrimSpace(string(data)) == "" {
+		return "?"
+	}
+	return strings.TrimSpace(string(data))
+}
+
+// mountedPartitions zwraca zamontowane urzadzenia dysku name (sam dysk
+// albo jego partycje: sdb1, nvme0n1p2) wg tresci /proc/mounts.
+func mountedPartitions(mounts, name string) []string {
+	re := regexp.MustCompile(`^/dev/` + regexp.QuoteMeta(name) + `(p?[0-9]+)?$`)
+	var out []string
+	for _, line := range strings.Split(mounts, "\n") {
+		fields := strings.Fields(line)
+		if len(fields) >= 2 && re.MatchString(fields[0]) {
+			out = append(out, fields[0]+" -> "+fields[1])
+		}
+	}
+	return out
+}
+
+// confirm pyta o zgode na nadpisanie -- trzeba wpisac nazwe dysku, samo
+// "t"/Enter nie wystarczy.
+func confirm(in io.Reader, desc, name string) (bool, error) {
+	fmt.Printf("%s Wszystkie dane na %s zostana zniszczone.\n"+
+		"Wpisz nazwe urzadzenia (%s), aby potwierdzic: ",
+		util.Colorize(util.ColorRed, "UWAGA:"), desc, name)
+	line, err := bufio.NewReader(in).ReadString('\n')
+	if err != nil && err != io.EOF {
+		return false, fmt.Errorf("usbwrite: odczyt potwierdzenia: %w", err)
+	}
+	return strings.TrimSpace(line) == name, nil
+}
+
+// copyImage kopiuje obraz src na urzadzenie dst z paskiem postepu i
+// zwraca SHA256 zapisanych danych. O_EXCL na urzadzeniu blokowym to
+// wylaczne otwarcie -- jadro odmawia, gdy dysk jest w uzyciu.
+func copyImage(src, dst string, size int64) (string, error) {
+	in, err := os.Open(src)
+	if err != nil {
+		return "", fmt.Errorf("usbwrite: %w", err)
+	}
+	defer in.Close()
+
+	out, err := os.OpenFile(dst, os.O_WRONLY|syscall.O_EXCL, 0)
+	if err != nil {
+		return "", fmt.Errorf("usbwrite: otwarcie %s: %w", dst, err)
+	}
+
+	h := sha256.New()
+	pw := &progressWriter{label: "zapis", total: size}
+	// LimitReader takze po to, by CopyBuffer nie ominal bufora przez
+	// (*os.File).WriteTo.
+	_, err = io.CopyBuffer(io.MultiWriter(out, h, pw), io.LimitReader(in, size), make([]byte, copyBufferSize))
+	pw.done()
+	if err != nil {
+		out.Close()
+		return "", fmt.Errorf("usbwrite: zapis %s: %w", dst, err)
+	}
+	// Sync czeka, az dane faktycznie trafia na nosnik -- bez tego
+	// "gotowe" pojawiloby sie, gdy wiekszosc obrazu jest jeszcze w cache.
+	if err := out.Sync(); err != nil {
+		out.Close()
+		return "", fmt.Errorf("usbwrite: sync %s: %w", dst, err)
+	}
+	if err := out.Close(); err != nil {
+		return "", fmt.Errorf("usbwrite: %w", err)
+	}
+	return hex.EncodeToString(h.Sum(nil)), nil
+}
+
+// hashPrefix zwraca SHA256 pierwszych size bajtow path (reszta nosnika
+// nie nalezy do obrazu).
+func hashPrefix(path string, size int64) (string, error) {
+	f, err := os.Open(path)
+	if err != nil {
+		return "", fmt.Errorf("usbwrite: %w", err)
+	}
+	defer f.Close()
+
+	h := sha256.New()
+	pw := &progressWriter{label: "weryfikacja", total: size}
+	_, err = io.CopyBuffer(io.MultiWriter(h, pw), io.LimitReader(f, size), make([]byte, copyBufferSize))
+	pw.done()
+	if err != nil {
+		return "", fmt.Errorf("usbwrite: odczyt %s: %w", path, err)
+	}
+	return hex.EncodeToString(h.Sum(nil)), nil
+}
+
+// progressWriter wypisuje postep na stderr (jedna linia nadpisywana
+// przez \r, co 1%).
+type progressWriter struct {
+	label   string
+	total   int64
+	written int64
+	percent int64
+}
+
+func (p *progressWriter) Write(b []byte) (int, error) {
+	p.written += int64(len(b))
+	if p.total > 0 {
+		if pct := p.written * 100 / p.total; pct != p.percent {
+			p.percent = pct
+			fmt.Fprintf(os.Stderr, "\r  %s: %3d%% (%d/%d MiB)", p.label, pct, p.written>>20, p.total>>20)
+		}
+	}
+	return len(b), nil
+}
+
+// done konczy linie postepu.
+func (p *progressWriter) done() {
+	fmt.Fprintln(os.Stderr)
+}
@@ -0,0 +1,136 @@
+package usbwrite
+
+import (
+	"bytes"
+	"os"
+	"path/filepath"
+	"reflect"
+	"strings"
+	"testing"
+)
+
+// fakeSysBlock tworzy /sys/block/<name> jako symlink do katalogu urzadzenia
+// pod devPath (jak w prawdziwym sysfs) z podanymi plikami.
+func fakeSysBlock(t *testing.T, name, devPath string, files map[string]string) string {
+	t.Helper()
+	root := t.TempDir()
+	target := filepath.Join(root, "devices", devPath, "block", name)
+	if err := os.MkdirAll(target, 0o755); err != nil {
+		t.Fatal(err)
+	}
+	for f, content := range files {
+		if err := os.WriteFile(filepath.Join(target, f), []byte(content), 0o644); err != nil {
+			t.Fatal(err)
+		}
+	}
+	sysBlock := filepath.Join(root, "block")
+	if err := os.MkdirAll(sysBlock, 0o755); err != nil {
+		t.Fatal(err)
+	}
+	if err := os.Symlink(target, filepath.Join(sysBlock, name)); err != nil {
+		t.Fatal(err)
+	}
+	return sysBlock
+}
+
+func TestIsRemovable(t *testing.T) {
+	cases := []struct {
+		name    string
+		devPath string
+		flag    string
+		want    bool
+	}{
+		{"sdb", "pci0000:00/0000:00:14.0/usb1/1-1/host6/target6:0:0/6:0:0:0", "1\n", true},
+		{"sdc", "pci0000:00/0000:00:14.0/usb2/2-1/host7/target7:0:0/7:0:0:0", "0\n", true},
+		{"nvme0n1", "pci0000:00/0000:00:1d.0/0000:3d:00.0/nvme/nvme0", "0\n", false},
+	}
+	for _, c := range cases {
+		sysBlock := fakeSysBlock(t, c.name, c.devPath, map[string]string{"removable": c.flag})
+		got, err := isRemovable(sysBlock, c.name)
+		if err != nil {
+			t.Fatal(err)
+		}
+		if got != c.want {
+			t.Errorf("%s: oczekiwano removable=%v, otrzymano %v", c.name, c.want, got)
+		}
+	}
+}
+
+func TestDiskSize(t *testing.T) {
+	sysBlock := fakeSysBlock(t, "sdb", "usb1/1-1", map[string]string{"size": "62521344\n"})
+	got, err := diskSize(sysBlock, "sdb")
+	if err != nil {
+		t.Fatal(err)
+	}
+	if want := int64(62521344) * 512; got != want {
+		t.Errorf("oczekiwano %d B, otrzymano %d", want, got)
+	}
+}
+
+func TestMountedPartitions(t *testing.T) {
+	mounts := "/dev/sda2 / ext4 rw 0 0\n" +
+		"/dev/sdb1 /media/usb vfat rw 0 0\n" +
+		"/dev/sdba1 /mnt/inny ext4 rw 0 0\n" +
+		"/dev/nvme0n1p1 /boot/efi vfat rw 0 0\n"
+	if got, want := mountedPartitions(mounts, "sdb"), []string{"/dev/sdb1 -> /media/usb"}; !reflect.DeepEqual(got, want) {
+		t.Errorf("sdb: oczekiwano %v, otrzymano %v", want, got)
+	}
+	if got, want := mountedPartitions(mounts, "nvme0n1"), []string{"/dev/nvme0n1p1 -> /boot/efi"}; !reflect.DeepEqual(got, want) {
+		t.Errorf("nvme0n1: oczekiwano %v, otrzymano %v", want, got)
+	}
+	if got := mountedPartitions(mounts, "sdc"); len(got) != 0 {
+		t.Errorf("sdc: oczekiwano braku montowan, otrzymano %v", got)
+	}
+}
+
+func TestConfirm(t *testing.T) {
+	if ok, err := confirm(strings.NewReader("sdb\n"), "/dev/sdb", "sdb"); err != nil || !ok {
+		t.Errorf("nazwa dysku powinna potwierdzic: ok=%v err=%v", ok, err)
+	}
+	if ok, _ := confirm(strings.NewReader("t\n"), "/dev/sdb", "sdb"); ok {
+		t.Error("\"t\" nie powinno potwierdzac")
+	}
+	if ok, _ := confirm(strings.NewReader(""), "/dev/sdb", "sdb"); ok {
+		t.Error("pusty stdin nie powinien potwierdzac")
+	}
+}
+
+// TestCopyImage_Verify: "urzadzenie" to zwykly plik wiekszy od obrazu --
+// weryfikacja czyta tylko rozmiar obrazu.
+func TestCopyImage_Verify(t *testing.T) {
+	dir := t.TempDir()
+	iso := filepath.Join(dir, "test.iso")
+	dev := filepath.Join(dir, "dev")
+	image := bytes.Repeat([]byte("hackeros"), 300000)
+	if err := os.WriteFile(iso, image, 0o644); err != nil {
+		t.Fatal(err)
+	}
+	if err := os.WriteFile(dev, make([]byte, 4<<20), 0o644); err != nil {
+		t.Fatal(err)
+	}
+
+	want, err := copyImage(iso, dev, int64(len(image)))
+	if err != nil {
+		t.Fatal(err)
+	}
+	got, err := hashPrefix(dev, int64(len(image)))
+	if err != nil {
+		t.Fatal(err)
+	}
+	if got != want {
+		t.Errorf("SHA256 po zapisie %s, oczekiwano %s", got, want)
+	}
+
+	// Uszkodzony bajt na "nosniku" musi zostac wykryty.
+	f, err := os.OpenFile(dev, os.O_WRONLY, 0)
+	if err != nil {
+		t.Fatal(err)
+	}
+	if _, err := f.WriteAt([]byte{0}, 1000); err != nil {
+		t.Fatal(err)
+	}
+	f.Close()
+	if bad, _ := hashPrefix(dev, int64(len(image))); bad == want {
+		t.Error("weryfikacja nie wykryla uszkodzonego bajtu")
+	}
+}
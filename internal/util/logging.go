@@ -15,10 +15,14 @@ func SetVerbose(v bool) { verbose = v }
 // golang.org/x/term), ktore wymagalyby ponownego "go mod tidy" z dostepem
 // do sieci. Kolory ANSI sa wlaczane tylko gdy to jest prawda, zeby
 // przekierowane logi (np. "hackeros-builder build cloud > log.txt") nie
-// byly zasmiecone kodami escape.
+// byly zasmiecone kodami escape. Ustawiona (niepusta) zmienna NO_COLOR
+// (konwencja no-color.org) wylacza kolory takze na terminalu.
 var isTerminal = detectTerminal()
 
 func detectTerminal() bool {
+	if os.Getenv("NO_COLOR") != "" {
+		return false
+	}
 	info, err := os.Stdout.Stat()
 	if err != nil {
 		return false
@@ -26,6 +30,10 @@ func detectTerminal() bool {
 	return (info.Mode() & os.ModeCharDevice) != 0
 }
 
+// DisableColor wylacza kody ANSI w calym wyjsciu CLI (flaga --no-color) --
+// dla terminali/ekranow, na ktorych paleta ponizej jest nieczytelna.
+func DisableColor() { isTerminal = false }
+
 // Kolory ANSI eksportowane dla main.go (printUsage, komunikaty sukcesu/bledu) --
 // ta sama paleta co logi poziomowe nizej, zeby caly CLI mial jednolity styl.
 const (
@@ -2,11 +2,18 @@ package util
 
 import (
 	"bytes"
+	"errors"
 	"fmt"
 	"os"
 	"os/exec"
+	"time"
 )
 
+// ErrTimeout opakowuje blad komendy zabitej po przekroczeniu limitu czasu
+// (RunCmdTimeout) -- caller odroznia go przez errors.Is i moze ponowic
+// probe, zamiast traktowac jak zwykly blad komendy.
+var ErrTimeout = errors.New("przekroczony limit czasu")
+
 // RunResult to wynik wykonania komendy zewnetrznej.
 type RunResult struct {
 	ExitCode int
@@ -87,6 +94,16 @@ func RunStreaming(dir string, name string, args ...string) error {
 // srodowiska procesu hackeros-builder (nie zastepuje go), wiec PATH i reszta
 // pozostaja nienaruszone.
 func RunStreamingEnv(dir string, env []string, name string, args ...string) error {
+	return runStreaming(dir, env, 0, name, args...)
+}
+
+// RunStreamingTimeout jak RunStreaming, ale zabija komende po timeout
+// (patrz RunCmdTimeout); timeout <= 0 -- bez limitu.
+func RunStreamingTimeout(dir string, timeout time.Duration, name string, args ...string) error {
+	return runStreaming(dir, nil, timeout, name, args...)
+}
+
+func runStreaming(dir string, env []string, timeout time.Duration, name string, args ...string) error {
 	cmd := exec.Command(name, args...)
 	if dir != "" {
 		cmd.Dir = dir
@@ -97,14 +114,43 @@ func RunStreamingEnv(dir string, env []string, name string, args ...string) erro
 	cmd.Stdout = os.Stdout
 	cmd.Stderr = os.Stderr
 
-	Debugf("exec (streaming): %s %v env=%v", name, args, env)
+	Debugf("exec (streaming): %s %v env=%v timeout=%s", name, args, env, timeout)
 
-	if err := cmd.Run(); err != nil {
+	if err := RunCmdTimeout(cmd, timeout); err != nil {
 		return fmt.Errorf("komenda %q %v nie powiodla sie: %w", name, args, err)
 	}
 	return nil
 }
 
+// RunCmdTimeout uruchamia przygotowana komende i czeka na nia najwyzej
+// timeout; po tym czasie wysyla jej SIGKILL i zwraca blad opakowujacy
+// ErrTimeout. timeout <= 0 -- zwykle cmd.Run(), bez limitu.
+//
+// Zabijany jest tylko proces cmd, nie jego potomkowie -- komenda, ktora
+// forkuje (debootstrap -> wget/dpkg), powinna byc uruchomiona pod
+// "unshare --pid --fork --kill-child", jak w sandbox.
+func RunCmdTimeout(cmd *exec.Cmd, timeout time.Duration) error {
+	if timeout <= 0 {
+		return cmd.Run()
+	}
+	if err := cmd.Start(); err != nil {
+		return err
+	}
+	done := make(chan error, 1)
+	go func() { done <- cmd.Wait() }()
+
+	timer := time.NewTimer(timeout)
+	defer timer.Stop()
+	select {
+	case err := <-done:
+		return err
+	case <-timer.C:
+		_ = cmd.Process.Kill()
+		<-done
+		return fmt.Errorf("%w (%s)", ErrTimeout, timeout)
+	}
+}
+
 // RunWithStdin wykonuje komende synchronicznie, podajac stdinData na stdin
 // procesu i przechwytujac stdout/stderr -- uzywane np. dla pomocniczych
 // komend hosta ktore czytaja dane z wejscia standardowego.
@@ -0,0 +1,29 @@
+package util
+
+import (
+	"errors"
+	"os/exec"
+	"testing"
+	"time"
+)
+
+func TestRunCmdTimeout_Kills(t *testing.T) {
+	start := time.Now()
+	err := RunCmdTimeout(exec.Command("sleep", "30"), 100*time.Millisecond)
+	if !errors.Is(err, ErrTimeout) {
+		t.Fatalf("oczekiwano ErrTimeout, otrzymano %v", err)
+	}
+	if d := time.Since(start); d > 10*time.Second {
+		t.Errorf("komenda nie zostala zabita po limicie (trwala %s)", d)
+	}
+}
+
+func TestRunCmdTimeout_Finishes(t *testing.T) {
+	if err := RunCmdTimeout(exec.Command("true"), 10*time.Second); err != nil {
+		t.Errorf("oczekiwano sukcesu, otrzymano %v", err)
+	}
+	err := RunCmdTimeout(exec.Command("false"), 10*time.Second)
+	if err == nil || errors.Is(err, ErrTimeout) {
+		t.Errorf("oczekiwano zwyklego bledu komendy, otrzymano %v", err)
+	}
+}
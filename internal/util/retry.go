@@ -0,0 +1,28 @@
+package util
+
+import (
+	"fmt"
+	"time"
+)
+
+// Retry wywoluje fn najwyzej attempts razy, czekajac miedzy probami delay,
+// potem 2*delay, 4*delay... (backoff). Kazda nieudana proba jest logowana
+// przez Warnf; po ostatniej zwracany jest jej blad.
+func Retry(what string, attempts int, delay time.Duration, fn func() error) error {
+	var err error
+	for i := 1; i <= attempts; i++ {
+		if err = fn(); err == nil {
+			if i > 1 {
+				Infof("%s: udane w probie %d/%d", what, i, attempts)
+			}
+			return nil
+		}
+		if i == attempts {
+			break
+		}
+		Warnf("%s: proba %d/%d nieudana (%v) -- ponowienie za %s", what, i, attempts, err, delay)
+		time.Sleep(delay)
+		delay *= 2
+	}
+	return fmt.Errorf("%s: %d nieudanych prob, ostatni blad: %w", what, attempts, err)
+}
@@ -0,0 +1,35 @@
+package util
+
+import (
+	"errors"
+	"testing"
+)
+
+func TestRetry_SucceedsAfterFailures(t *testing.T) {
+	calls := 0
+	err := Retry("test", 3, 0, func() error {
+		calls++
+		if calls < 3 {
+			return errors.New("chwilowy blad")
+		}
+		return nil
+	})
+	if err != nil || calls != 3 {
+		t.Errorf("oczekiwano sukcesu w 3. probie, otrzymano err=%v po %d wywolaniach", err, calls)
+	}
+}
+
+func TestRetry_GivesUp(t *testing.T) {
+	calls := 0
+	last := errors.New("mirror nie odpowiada")
+	err := Retry("test", 2, 0, func() error {
+		calls++
+		return last
+	})
+	if calls != 2 {
+		t.Errorf("oczekiwano 2 wywolan, otrzymano %d", calls)
+	}
+	if !errors.Is(err, last) {
+		t.Errorf("oczekiwano opakowanego ostatniego bledu, otrzymano %v", err)
+	}
+}
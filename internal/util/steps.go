@@ -0,0 +1,27 @@
+package util
+
+import "fmt"
+
+// Steps numeruje kroki dluzszego przeplywu w logach ("Krok 3/10: ...") --
+// uzytkownik widzi, jak daleko jest build, a numeracja nie rozjezdza sie
+// przy dodaniu kroku (liczba krokow jest podawana w jednym miejscu).
+type Steps struct {
+	current int
+	total   int
+}
+
+// NewSteps tworzy licznik dla przeplywu o total krokach.
+func NewSteps(total int) *Steps {
+	return &Steps{total: total}
+}
+
+// Next przechodzi do nastepnego kroku i loguje go przez Infof.
+func (s *Steps) Next(format string, args ...any) {
+	s.current++
+	Infof("Krok %d/%d: %s", s.current, s.total, fmt.Sprintf(format, args...))
+}
+
+// Skip przechodzi do nastepnego kroku, logujac go jako pominiety.
+func (s *Steps) Skip(format string, args ...any) {
+	s.Next(format+" -- pominieto", args...)
+}
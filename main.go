@@ -6,6 +6,7 @@ import (
 	"path/filepath"
 
 	"github.com/HackerOS-Linux-System/hackeros-builder/internal/buildflow"
+	"github.com/HackerOS-Linux-System/hackeros-builder/internal/usbwrite"
 	"github.com/HackerOS-Linux-System/hackeros-builder/internal/util"
 )
 
@@ -33,6 +34,7 @@ func printUsage() {
   hackeros-builder [opcje] <cloud|iso|all>          (forma skrocona, bez "build")
   hackeros-builder clean                            Usun katalog roboczy (--workdir).
   hackeros-builder clean --all                      Jak wyzej + usun wynikowy plik .iso (--output).
+  hackeros-builder write <plik.iso> <urzadzenie>    Zapisz ISO na pendrive (z weryfikacja).
 
 %s
   build cloud          Zbuduj rootfs (debootstrap + hooks + package-lists)
@@ -44,9 +46,15 @@ func printUsage() {
   build all              Wykonaj 'build cloud', nastepnie 'build iso'.
   clean                  Usun katalog roboczy (rootfs/oci-push/iso-build/...).
   clean --all             Jak 'clean', plus usun wynikowy plik .iso.
+  write <iso> <dysk>      Zapisz obraz ISO na nosnik USB (caly dysk, np.
+                        /dev/sdb), potem odczytaj go z powrotem i porownaj
+                        SHA256. Pyta o potwierdzenie; odmawia zapisu na
+                        dysk niewymienny i na dysk z zamontowanymi
+                        partycjami.
 
 %s
   -v, --verbose            Wlacz logi DEBUG.
+  --no-color               Wylacz kolory ANSI (to samo co zmienna NO_COLOR).
   -p, --project <dir>      Katalog projektu (musi zawierac 'config/config.hk').
                            Domyslnie: katalog biezacy.
   -w, --workdir <dir>      Katalog roboczy na pliki tymczasowe.
@@ -69,6 +77,9 @@ func printUsage() {
                            instalacji.
   --all                    (tylko 'clean') Usun rowniez plik wyjsciowy .iso,
                            nie tylko katalog roboczy.
+  --yes                    (tylko 'write') Nie pytaj o potwierdzenie.
+  --allow-fixed            (tylko 'write') Pozwol zapisac na dysk niewymienny
+                           (nie-USB) -- np. drugi dysk SATA jako nosnik.
   -h, --help               Wyswietl ta pomoc i wyjdz.
   -V, --version             Wyswietl wersje i wyjdz.
 
@@ -80,6 +91,7 @@ func printUsage() {
     includes.chroot/...
     archives/*.list.chroot
     archives/*.key.chroot
+    archives/*.fingerprint.chroot   <- odcisk klucza (opcjonalnie)
 
 Wszystkie komendy 'build' wymagaja uprawnien roota (debootstrap, chroot, mount).
 `,
@@ -103,13 +115,25 @@ func main() {
 		skipPreflight    bool
 		noInstaller      bool
 		cleanAll         bool
+		writeYes         bool
+		writeAllowFixed  bool
 	)
 
+	// --no-color musi zadzialac zanim cokolwiek zostanie wypisane (takze
+	// --help ponizej), wiec jest sprawdzane przed glowna petla.
+	for _, a := range args {
+		if a == "--no-color" {
+			util.DisableColor()
+		}
+	}
+
 	var positional []string
 	for i := 0; i < len(args); i++ {
 		switch args[i] {
 		case "-v", "--verbose":
 			verboseFlag = true
+		case "--no-color":
+			// obsluzone przed petla
 		case "-p", "--project":
 			i++
 			if i >= len(args) {
@@ -136,6 +160,10 @@ func main() {
 			noInstaller = true
 		case "--all":
 			cleanAll = true
+		case "--yes":
+			writeYes = true
+		case "--allow-fixed":
+			writeAllowFixed = true
 		case "-h", "--help", "help":
 			printUsage()
 			os.Exit(0)
@@ -177,6 +205,28 @@ func main() {
 		os.Exit(0)
 	}
 
+	// "write <iso> <urzadzenie>" tez nie potrzebuje projektu -- zapisuje
+	// gotowy plik ISO (z tego albo innego komputera).
+	if len(positional) > 0 && positional[0] == "write" {
+		if len(positional) != 3 {
+			fail("oczekiwano 'write <plik.iso> <urzadzenie>', np. 'write output.iso /dev/sdb'. Zobacz --help.")
+		}
+		if os.Geteuid() != 0 {
+			fail("hackeros-builder write wymaga uprawnien roota (zapis na urzadzenie blokowe)")
+		}
+		err := usbwrite.Write(usbwrite.Options{
+			ISO:        positional[1],
+			Device:     positional[2],
+			AllowFixed: writeAllowFixed,
+			Yes:        writeYes,
+		})
+		if err != nil {
+			fail(err.Error())
+		}
+		fmt.Println(util.Colorize(util.ColorGreen, "Nosnik gotowy:") + " " + positional[2])
+		os.Exit(0)
+	}
+
 	// Akceptujemy DWIE formy wywolania:
 	//   hackeros-builder build cloud   (positional = ["build", "cloud"])
 	//   hackeros-builder cloud         (positional = ["cloud"])